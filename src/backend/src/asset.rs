@@ -0,0 +1,238 @@
+// backend/src/asset.rs
+
+use crate::error::BackendError;
+use crate::types::{AssetInfo, AssetOptions};
+
+// =====================
+// 1) 지원 형식과 크기 제한
+// =====================
+
+/// 레코드로 나누지 않고 하나의 블롭으로 저장하는 MIME 타입과 최대 크기(바이트)
+const ASSET_TYPES: [(&str, usize); 8] = [
+    ("image/png", 5 * 1024 * 1024),
+    ("image/jpeg", 5 * 1024 * 1024),
+    ("image/gif", 5 * 1024 * 1024),
+    ("image/webp", 5 * 1024 * 1024),
+    ("application/pdf", 8 * 1024 * 1024),
+    ("application/zip", 10 * 1024 * 1024),
+    ("application/gzip", 10 * 1024 * 1024),
+    ("application/x-tar", 10 * 1024 * 1024),
+];
+
+/// 썸네일로 허용하는 이미지 타입
+const THUMBNAIL_TYPES: [&str; 4] = ["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+const MAX_THUMBNAIL_SIZE: usize = 64 * 1024;
+const MAX_FILE_NAME_LENGTH: usize = 255;
+const MAX_PREVIEW_TEXT_LENGTH: usize = 1_000;
+
+/// 바이너리 자산 MIME 타입인지 확인
+pub fn is_asset_mime_type(mime_type: &str) -> bool {
+    size_limit(mime_type).is_some()
+}
+
+/// MIME 타입별 최대 크기
+fn size_limit(mime_type: &str) -> Option<usize> {
+    ASSET_TYPES
+        .iter()
+        .find(|(name, _)| *name == mime_type)
+        .map(|(_, limit)| *limit)
+}
+
+// =====================
+// 2) 콘텐츠 스니핑
+// =====================
+
+/// 매직 바이트로 실제 형식 판별
+pub fn sniff_mime_type(content: &[u8]) -> Option<&'static str> {
+    if content.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if content.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if content.starts_with(b"GIF87a") || content.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if content.starts_with(b"RIFF") && content.get(8..12) == Some(b"WEBP") {
+        Some("image/webp")
+    } else if content.starts_with(b"%PDF-") {
+        Some("application/pdf")
+    } else if content.starts_with(b"PK\x03\x04") || content.starts_with(b"PK\x05\x06") {
+        Some("application/zip")
+    } else if content.starts_with(&[0x1F, 0x8B]) {
+        Some("application/gzip")
+    } else if content.get(257..262) == Some(b"ustar") {
+        Some("application/x-tar")
+    } else {
+        None
+    }
+}
+
+// =====================
+// 3) 업로드 검증 및 메타데이터 구성
+// =====================
+
+/// 바이너리 자산 검증 후 저장할 메타데이터 구성
+///
+/// 선언한 MIME 타입과 매직 바이트가 일치해야 하며, 이미지는 헤더에서 크기를 읽는다.
+pub fn prepare_asset(
+    content: &[u8],
+    mime_type: &str,
+    options: Option<AssetOptions>,
+) -> Result<AssetInfo, BackendError> {
+    let limit = size_limit(mime_type)
+        .ok_or_else(|| BackendError::validation("mime_type", "unsupported"))?;
+    if content.is_empty() {
+        return Err(BackendError::validation("content", "empty"));
+    }
+    if content.len() > limit {
+        return Err(BackendError::quota_exceeded("upload_bytes", limit as u64));
+    }
+    if sniff_mime_type(content) != Some(mime_type) {
+        return Err(BackendError::validation("content", "content_mismatch"));
+    }
+
+    let options = options.unwrap_or_default();
+    let file_name = trimmed(options.file_name);
+    if let Some(length) = file_name.as_ref().map(|name| name.chars().count()) {
+        if length > MAX_FILE_NAME_LENGTH {
+            return Err(BackendError::too_long(
+                "file_name",
+                length,
+                MAX_FILE_NAME_LENGTH,
+            ));
+        }
+    }
+    let preview_text = trimmed(options.preview_text);
+    if let Some(length) = preview_text.as_ref().map(|text| text.chars().count()) {
+        if length > MAX_PREVIEW_TEXT_LENGTH {
+            return Err(BackendError::too_long(
+                "preview_text",
+                length,
+                MAX_PREVIEW_TEXT_LENGTH,
+            ));
+        }
+    }
+
+    let thumbnail_mime_type = match &options.thumbnail {
+        Some(thumbnail) => {
+            if thumbnail.len() > MAX_THUMBNAIL_SIZE {
+                return Err(BackendError::quota_exceeded(
+                    "thumbnail",
+                    MAX_THUMBNAIL_SIZE as u64,
+                ));
+            }
+            match sniff_mime_type(thumbnail) {
+                Some(kind) if THUMBNAIL_TYPES.contains(&kind) => Some(kind.to_string()),
+                _ => return Err(BackendError::validation("thumbnail", "content_mismatch")),
+            }
+        }
+        None => None,
+    };
+
+    let (width, height) = image_dimensions(content, mime_type).unzip();
+
+    Ok(AssetInfo {
+        file_name,
+        width,
+        height,
+        thumbnail: options.thumbnail,
+        thumbnail_mime_type,
+        preview_text,
+    })
+}
+
+/// 공백 제거 후 비어 있으면 None
+fn trimmed(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// 이미지 헤더에서 (너비, 높이) 읽기 (PNG/GIF/JPEG)
+fn image_dimensions(content: &[u8], mime_type: &str) -> Option<(u32, u32)> {
+    match mime_type {
+        "image/png" => {
+            let width = u32::from_be_bytes(content.get(16..20)?.try_into().ok()?);
+            let height = u32::from_be_bytes(content.get(20..24)?.try_into().ok()?);
+            Some((width, height))
+        }
+        "image/gif" => {
+            let width = u16::from_le_bytes(content.get(6..8)?.try_into().ok()?);
+            let height = u16::from_le_bytes(content.get(8..10)?.try_into().ok()?);
+            Some((u32::from(width), u32::from(height)))
+        }
+        "image/jpeg" => jpeg_dimensions(content),
+        _ => None,
+    }
+}
+
+/// JPEG 세그먼트를 따라가며 SOF 마커의 크기 읽기
+fn jpeg_dimensions(content: &[u8]) -> Option<(u32, u32)> {
+    let mut offset = 2;
+    loop {
+        if *content.get(offset)? != 0xFF {
+            return None;
+        }
+        let marker = *content.get(offset + 1)?;
+        match marker {
+            // 채움 바이트
+            0xFF => offset += 1,
+            // 길이가 없는 마커
+            0x01 | 0xD0..=0xD7 => offset += 2,
+            // SOF0-SOF15 (DHT/JPG/DAC 제외)
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                let height =
+                    u16::from_be_bytes(content.get(offset + 5..offset + 7)?.try_into().ok()?);
+                let width =
+                    u16::from_be_bytes(content.get(offset + 7..offset + 9)?.try_into().ok()?);
+                return Some((u32::from(width), u32::from(height)));
+            }
+            _ => {
+                let length =
+                    u16::from_be_bytes(content.get(offset + 2..offset + 4)?.try_into().ok()?);
+                offset += 2 + usize::from(length);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut content = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+        content.extend_from_slice(&width.to_be_bytes());
+        content.extend_from_slice(&height.to_be_bytes());
+        content.extend_from_slice(&[8, 6, 0, 0, 0]);
+        content
+    }
+
+    #[test]
+    fn test_prepare_asset_sniffs_content() {
+        let options = AssetOptions {
+            file_name: Some(" 지도.png ".to_string()),
+            thumbnail: Some(png(16, 16)),
+            preview_text: None,
+        };
+        let info = prepare_asset(&png(640, 480), "image/png", Some(options)).unwrap();
+        assert_eq!(info.file_name.as_deref(), Some("지도.png"));
+        assert_eq!((info.width, info.height), (Some(640), Some(480)));
+        assert_eq!(info.thumbnail_mime_type.as_deref(), Some("image/png"));
+
+        assert_eq!(
+            prepare_asset(&png(1, 1), "application/pdf", None),
+            Err(BackendError::validation("content", "content_mismatch"))
+        );
+        assert!(prepare_asset(b"%PDF-1.7\n", "application/pdf", None).is_ok());
+    }
+
+    #[test]
+    fn test_jpeg_dimensions() {
+        let content = [
+            0xFF, 0xD8, // SOI
+            0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, // APP0
+            0xFF, 0xC0, 0x00, 0x11, 0x08, 0x01, 0xE0, 0x02, 0x80, // SOF0 480x640
+        ];
+        assert_eq!(jpeg_dimensions(&content), Some((640, 480)));
+    }
+}
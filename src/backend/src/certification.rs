@@ -0,0 +1,318 @@
+// backend/src/certification.rs
+
+use crate::marketplace_types::Listing;
+use crate::types::{CertifiedResponse, CertifiedValue, MintStatus};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use ic_certification::{AsHashTree, Hash, HashTree, HashTreeNode, RbTree};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+
+// 인증 트리 최상위 라벨: 라벨 / ID(8바이트 big-endian) -> 값 해시
+pub const LISTINGS_LABEL: &str = "listings";
+pub const MINT_STATUS_LABEL: &str = "mint_status";
+pub const BLOBS_LABEL: &str = "blobs";
+// HTTP 게이트웨이 응답 인증 (v1): http_assets / 요청 경로 -> 응답 본문 해시
+pub const HTTP_ASSETS_LABEL: &str = "http_assets";
+
+const LABELS: [&str; 4] = [
+    LISTINGS_LABEL,
+    MINT_STATUS_LABEL,
+    BLOBS_LABEL,
+    HTTP_ASSETS_LABEL,
+];
+
+/// 인증 데이터 트리 (힙에만 유지, init/post_upgrade 시 저장소에서 재구성)
+pub struct CertifiedTree {
+    tree: RbTree<&'static str, RbTree<Vec<u8>, Hash>>,
+}
+
+impl Default for CertifiedTree {
+    fn default() -> Self {
+        let mut tree = RbTree::new();
+        for label in LABELS {
+            tree.insert(label, RbTree::new());
+        }
+        Self { tree }
+    }
+}
+
+impl CertifiedTree {
+    pub fn insert(&mut self, label: &'static str, key: &[u8], value_hash: Hash) {
+        self.tree.modify(label.as_bytes(), |entries| {
+            entries.insert(key.to_vec(), value_hash);
+        });
+    }
+
+    pub fn remove(&mut self, label: &'static str, key: &[u8]) {
+        self.tree.modify(label.as_bytes(), |entries| {
+            entries.delete(key);
+        });
+    }
+
+    pub fn root_hash(&self) -> Hash {
+        self.tree.root_hash()
+    }
+
+    /// 여러 키에 대한 witness (존재하지 않는 키는 부재 증명)
+    pub fn witness(&self, label: &'static str, keys: &[Vec<u8>]) -> HashTree {
+        keys.iter()
+            .map(|key| {
+                self.tree
+                    .nested_witness(label.as_bytes(), |entries| entries.witness(key))
+            })
+            .reduce(merge_witnesses)
+            .unwrap_or_else(|| {
+                self.tree
+                    .nested_witness(label.as_bytes(), |entries| entries.keys())
+            })
+    }
+}
+
+/// 정수 ID 키 (8바이트 big-endian)
+pub fn id_key(id: u64) -> Vec<u8> {
+    id.to_be_bytes().to_vec()
+}
+
+/// 같은 트리에서 나온 두 witness 병합 (pruned 노드는 상대 쪽 내용으로 대체)
+fn merge_witnesses(left: HashTree, right: HashTree) -> HashTree {
+    from_node(merge_nodes(left.into(), right.into()))
+}
+
+fn merge_nodes(left: HashTreeNode, right: HashTreeNode) -> HashTreeNode {
+    match (left, right) {
+        (HashTreeNode::Pruned(_), right) => right,
+        (left, HashTreeNode::Pruned(_)) => left,
+        (HashTreeNode::Fork(left), HashTreeNode::Fork(right)) => {
+            let (left_l, left_r) = *left;
+            let (right_l, right_r) = *right;
+            HashTreeNode::Fork(Box::new((
+                merge_nodes(left_l, right_l),
+                merge_nodes(left_r, right_r),
+            )))
+        }
+        (HashTreeNode::Labeled(label, left), HashTreeNode::Labeled(_, right)) => {
+            HashTreeNode::Labeled(label, Box::new(merge_nodes(*left, *right)))
+        }
+        (left, _) => left,
+    }
+}
+
+fn from_node(node: HashTreeNode) -> HashTree {
+    match node {
+        HashTreeNode::Empty() => ic_certification::empty(),
+        HashTreeNode::Fork(children) => {
+            let (left, right) = *children;
+            ic_certification::fork(from_node(left), from_node(right))
+        }
+        HashTreeNode::Labeled(label, child) => {
+            ic_certification::labeled(label.as_bytes().to_vec(), from_node(*child))
+        }
+        HashTreeNode::Leaf(value) => ic_certification::leaf(value),
+        HashTreeNode::Pruned(hash) => ic_certification::pruned(hash),
+    }
+}
+
+thread_local! {
+    static CERTIFIED_TREE: RefCell<CertifiedTree> = RefCell::new(CertifiedTree::default());
+}
+
+// =====================
+// 1) 값 해시 (클라이언트는 응답의 payload로 같은 해시를 계산해 검증)
+// =====================
+
+pub fn sha256(bytes: &[u8]) -> Hash {
+    Sha256::digest(bytes).into()
+}
+
+/// 판매글/민팅 상태는 CBOR 인코딩을 payload로 사용
+pub fn cbor_payload<T: Serialize>(value: &T) -> Vec<u8> {
+    serde_cbor::to_vec(value).expect("인증 payload 직렬화 실패")
+}
+
+// =====================
+// 2) 트리 갱신
+// =====================
+
+pub fn certify_listing(listing: &Listing) {
+    update_tree(|tree| insert_listing(tree, listing));
+}
+
+pub fn certify_mint_status(request_id: u64, status: &MintStatus) {
+    update_tree(|tree| insert_mint_status(tree, request_id, status));
+}
+
+/// 업로드 데이터는 원본 바이트 해시와 HTTP 응답 본문 해시를 인증
+pub fn certify_blob(data_id: u64, blob_hash: Hash, http_hash: Option<Hash>) {
+    update_tree(|tree| insert_blob(tree, data_id, blob_hash, http_hash));
+}
+
+pub fn uncertify_blob(data_id: u64) {
+    let data_path = crate::http::data_path(data_id);
+    update_tree(|tree| {
+        tree.remove(BLOBS_LABEL, &id_key(data_id));
+        tree.remove(HTTP_ASSETS_LABEL, data_path.as_bytes());
+    });
+}
+
+/// 판매글과 HTTP로 공개되는 판매글/미리보기 경로 등록
+pub fn insert_listing(tree: &mut CertifiedTree, listing: &Listing) {
+    let listing_path = crate::http::listing_path(listing.id);
+    let preview_path = crate::http::preview_path(listing.id);
+
+    tree.insert(
+        LISTINGS_LABEL,
+        &id_key(listing.id),
+        sha256(&cbor_payload(listing)),
+    );
+
+    // HTTP로 공개되지 않는 판매글은 경로 인증도 제거
+    match crate::http::listing_body(listing) {
+        Some(body) => tree.insert(HTTP_ASSETS_LABEL, listing_path.as_bytes(), sha256(&body)),
+        None => tree.remove(HTTP_ASSETS_LABEL, listing_path.as_bytes()),
+    }
+    match crate::http::preview_body(listing) {
+        Some(body) => tree.insert(HTTP_ASSETS_LABEL, preview_path.as_bytes(), sha256(&body)),
+        None => tree.remove(HTTP_ASSETS_LABEL, preview_path.as_bytes()),
+    }
+}
+
+pub fn insert_mint_status(tree: &mut CertifiedTree, request_id: u64, status: &MintStatus) {
+    tree.insert(
+        MINT_STATUS_LABEL,
+        &id_key(request_id),
+        sha256(&cbor_payload(status)),
+    );
+}
+
+/// 업로드 시 계산해 둔 해시로 데이터 등록 (블롭을 다시 읽지 않음)
+pub fn insert_blob(
+    tree: &mut CertifiedTree,
+    data_id: u64,
+    blob_hash: Hash,
+    http_hash: Option<Hash>,
+) {
+    tree.insert(BLOBS_LABEL, &id_key(data_id), blob_hash);
+    if let Some(http_hash) = http_hash {
+        let data_path = crate::http::data_path(data_id);
+        tree.insert(HTTP_ASSETS_LABEL, data_path.as_bytes(), http_hash);
+    }
+}
+
+fn update_tree(f: impl FnOnce(&mut CertifiedTree)) {
+    CERTIFIED_TREE.with(|tree| f(&mut tree.borrow_mut()));
+    publish_root_hash();
+}
+
+/// 저장소 전체로 인증 트리 재구성 (init/post_upgrade에서 호출)
+///
+/// 블롭은 저장해 둔 해시만 읽고, 루트 해시는 마지막에 한 번만 게시한다.
+pub fn rebuild_certified_tree() {
+    let mut tree = CertifiedTree::default();
+    crate::marketplace_storage::certify_all_listings(&mut tree);
+    crate::storage::certify_all_data(&mut tree);
+
+    CERTIFIED_TREE.with(|current| *current.borrow_mut() = tree);
+    publish_root_hash();
+}
+
+fn publish_root_hash() {
+    let root_hash = CERTIFIED_TREE.with(|tree| tree.borrow().root_hash());
+    // 네이티브 단위 테스트에서는 시스템 API를 사용할 수 없음
+    #[cfg(target_arch = "wasm32")]
+    ic_cdk::api::set_certified_data(&root_hash);
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = root_hash;
+}
+
+// =====================
+// 3) 응답용 witness
+// =====================
+
+/// 지정한 키들의 witness를 CBOR(self-describing)로 인코딩
+pub fn encoded_witness(label: &'static str, keys: &[Vec<u8>]) -> Vec<u8> {
+    let witness = CERTIFIED_TREE.with(|tree| tree.borrow().witness(label, keys));
+
+    let mut serializer = serde_cbor::Serializer::new(Vec::new());
+    serializer.self_describe().expect("witness 직렬화 실패");
+    witness
+        .serialize(&mut serializer)
+        .expect("witness 직렬화 실패");
+    serializer.into_inner()
+}
+
+/// HTTP 응답 인증 헤더 (IC-Certificate, v1 형식)
+///
+/// 쿼리 호출이 아니거나 경로가 인증 트리에 없으면 None.
+pub fn http_certificate_header(path: &str) -> Option<(String, String)> {
+    let certificate = ic_cdk::api::data_certificate()?;
+
+    let is_certified = CERTIFIED_TREE.with(|tree| {
+        tree.borrow()
+            .tree
+            .get(HTTP_ASSETS_LABEL.as_bytes())
+            .is_some_and(|assets| assets.get(path.as_bytes()).is_some())
+    });
+    if !is_certified {
+        return None;
+    }
+
+    let witness = encoded_witness(HTTP_ASSETS_LABEL, &[path.as_bytes().to_vec()]);
+    Some((
+        "IC-Certificate".to_string(),
+        format!(
+            "certificate=:{}:, tree=:{}:",
+            BASE64.encode(certificate),
+            BASE64.encode(witness)
+        ),
+    ))
+}
+
+/// 값과 witness, 인증서를 묶어 인증 응답 구성 (인증서는 쿼리 호출에서만 존재)
+pub fn certified_response(
+    label: &'static str,
+    values: Vec<(u64, Option<Vec<u8>>)>,
+    next_cursor: Option<String>,
+) -> CertifiedResponse {
+    let keys: Vec<Vec<u8>> = values.iter().map(|(id, _)| id_key(*id)).collect();
+
+    CertifiedResponse {
+        label: label.to_string(),
+        witness: encoded_witness(label, &keys),
+        certificate: ic_cdk::api::data_certificate(),
+        values: values
+            .into_iter()
+            .map(|(id, payload)| CertifiedValue { id, payload })
+            .collect(),
+        next_cursor,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic_certification::LookupResult;
+
+    #[test]
+    fn test_witness_matches_root_and_proves_values() {
+        let mut tree = CertifiedTree::default();
+        tree.insert(LISTINGS_LABEL, &id_key(1), sha256(b"first"));
+        tree.insert(LISTINGS_LABEL, &id_key(2), sha256(b"second"));
+        tree.insert(HTTP_ASSETS_LABEL, b"/data/7", sha256(b"blob"));
+
+        let witness = tree.witness(LISTINGS_LABEL, &[id_key(1), id_key(2), id_key(3)]);
+        assert_eq!(witness.digest(), tree.root_hash());
+
+        let found = witness.lookup_path([LISTINGS_LABEL.as_bytes(), &id_key(2)[..]]);
+        assert_eq!(found, LookupResult::Found(&sha256(b"second")[..]));
+
+        let absent = witness.lookup_path([LISTINGS_LABEL.as_bytes(), &id_key(3)[..]]);
+        assert_eq!(absent, LookupResult::Absent);
+
+        let http_witness = tree.witness(HTTP_ASSETS_LABEL, &[b"/data/7".to_vec()]);
+        assert_eq!(http_witness.digest(), tree.root_hash());
+
+        tree.remove(LISTINGS_LABEL, &id_key(2));
+        assert_ne!(witness.digest(), tree.root_hash());
+    }
+}
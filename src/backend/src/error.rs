@@ -0,0 +1,228 @@
+// backend/src/error.rs
+
+use crate::i18n::Locale;
+use crate::types::PiiFinding;
+use candid::{CandidType, Deserialize};
+use std::fmt;
+
+// =====================
+// 1) 백엔드 에러
+// =====================
+
+/// 백엔드 공통 에러
+///
+/// 변형(variant)과 필드는 클라이언트가 분기 처리할 수 있는 안정적인 형태이며,
+/// 사람이 읽을 메시지는 호출자 언어로 번역해 `ApiError::message`로 따로 전달합니다.
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq)]
+pub enum BackendError {
+    /// 대상 리소스를 찾을 수 없음 (resource: "listing", "data" 등)
+    NotFound {
+        resource: String,
+        id: Option<String>,
+    },
+    /// 호출자에게 해당 작업 권한이 없음
+    Unauthorized { action: String },
+    /// 입력값 검증 실패 (reason: "empty", "invalid_format" 등 snake_case 코드)
+    Validation { field: String, reason: String },
+    /// 길이(문자 수)가 최대 길이를 넘음
+    TooLong {
+        field: String,
+        length: u64,
+        max: u64,
+    },
+    /// 크기(바이트)가 최대 크기를 넘음
+    TooLarge { field: String, size: u64, max: u64 },
+    /// 크기(바이트)가 최소 크기보다 작음
+    TooSmall { field: String, size: u64, min: u64 },
+    /// 숫자 값이 허용 범위(min 이상 max 이하)를 벗어남
+    OutOfRange {
+        field: String,
+        value: u64,
+        min: u64,
+        max: u64,
+    },
+    /// 요청 안에서 같은 레코드가 반복됨 (중복으로 판단된 레코드의 인덱스)
+    DuplicateRecords { indices: Vec<u64> },
+    /// 이미 존재하는 리소스
+    AlreadyExists { resource: String },
+    /// 이미 민팅된 데이터가 포함됨 (요청 내 인덱스 또는 데이터 ID)
+    AlreadyMinted { indices: Vec<u64> },
+    /// 개수/크기 한도 초과
+    QuotaExceeded { resource: String, limit: u64 },
+    /// 다른 사용자가 민팅한 데이터셋과 거의 같음
+    NearDuplicate {
+        dataset_id: u64,
+        similarity_percent: u8,
+    },
+    /// 업로드 데이터에 개인정보가 포함됨 (거부 모드)
+    PiiDetected { findings: Vec<PiiFinding> },
+    /// 현재 상태에서 허용되지 않는 작업 (reason: snake_case 코드)
+    InvalidState { reason: String },
+    /// 직렬화 실패 등 내부 오류
+    Internal { reason: String },
+}
+
+impl BackendError {
+    pub fn not_found(resource: &str, id: impl ToString) -> Self {
+        BackendError::NotFound {
+            resource: resource.to_string(),
+            id: Some(id.to_string()),
+        }
+    }
+
+    pub fn unauthorized(action: &str) -> Self {
+        BackendError::Unauthorized {
+            action: action.to_string(),
+        }
+    }
+
+    pub fn validation(field: impl Into<String>, reason: &str) -> Self {
+        BackendError::Validation {
+            field: field.into(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn too_long(field: impl Into<String>, length: usize, max: usize) -> Self {
+        BackendError::TooLong {
+            field: field.into(),
+            length: length as u64,
+            max: max as u64,
+        }
+    }
+
+    pub fn too_large(field: impl Into<String>, size: usize, max: usize) -> Self {
+        BackendError::TooLarge {
+            field: field.into(),
+            size: size as u64,
+            max: max as u64,
+        }
+    }
+
+    pub fn too_small(field: impl Into<String>, size: usize, min: usize) -> Self {
+        BackendError::TooSmall {
+            field: field.into(),
+            size: size as u64,
+            min: min as u64,
+        }
+    }
+
+    pub fn out_of_range(field: &str, value: impl Into<u64>, min: u64, max: u64) -> Self {
+        BackendError::OutOfRange {
+            field: field.to_string(),
+            value: value.into(),
+            min,
+            max,
+        }
+    }
+
+    pub fn already_exists(resource: &str) -> Self {
+        BackendError::AlreadyExists {
+            resource: resource.to_string(),
+        }
+    }
+
+    pub fn quota_exceeded(resource: &str, limit: u64) -> Self {
+        BackendError::QuotaExceeded {
+            resource: resource.to_string(),
+            limit,
+        }
+    }
+
+    pub fn invalid_state(reason: &str) -> Self {
+        BackendError::InvalidState {
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn internal(reason: impl fmt::Display) -> Self {
+        BackendError::Internal {
+            reason: reason.to_string(),
+        }
+    }
+
+    /// 안정적인 에러 코드 (클라이언트 분기용, 변경 금지)
+    pub fn code(&self) -> &'static str {
+        match self {
+            BackendError::NotFound { .. } => "NOT_FOUND",
+            BackendError::Unauthorized { .. } => "UNAUTHORIZED",
+            BackendError::Validation { .. }
+            | BackendError::TooLong { .. }
+            | BackendError::TooLarge { .. }
+            | BackendError::TooSmall { .. }
+            | BackendError::OutOfRange { .. } => "VALIDATION_FAILED",
+            BackendError::DuplicateRecords { .. } => "DUPLICATE_RECORDS",
+            BackendError::AlreadyExists { .. } => "ALREADY_EXISTS",
+            BackendError::AlreadyMinted { .. } => "ALREADY_MINTED",
+            BackendError::QuotaExceeded { .. } => "QUOTA_EXCEEDED",
+            BackendError::NearDuplicate { .. } => "NEAR_DUPLICATE",
+            BackendError::PiiDetected { .. } => "PII_DETECTED",
+            BackendError::InvalidState { .. } => "INVALID_STATE",
+            BackendError::Internal { .. } => "INTERNAL_ERROR",
+        }
+    }
+
+    /// 지정한 언어의 메시지 (카탈로그는 i18n 모듈)
+    pub fn message(&self, locale: Locale) -> String {
+        crate::i18n::localize(self, locale)
+    }
+}
+
+impl fmt::Display for BackendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.code(), self.message(Locale::Ko))
+    }
+}
+
+// =====================
+// 2) API 에러 응답
+// =====================
+
+/// 엔드포인트가 반환하는 에러 (코드 + 구조화된 에러 + 선택적 메시지)
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ApiError {
+    pub code: String,
+    pub error: BackendError,
+    pub message: Option<String>,
+}
+
+impl ApiError {
+    /// 구조화된 에러에 지정한 언어의 메시지를 붙여 응답 생성
+    pub fn localized(error: BackendError, locale: Locale) -> Self {
+        ApiError {
+            code: error.code().to_string(),
+            message: Some(error.message(locale)),
+            error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_error_carries_stable_code() {
+        let err = ApiError::localized(
+            BackendError::AlreadyMinted {
+                indices: vec![1, 3],
+            },
+            Locale::Ko,
+        );
+        assert_eq!(err.code, "ALREADY_MINTED");
+        assert_eq!(
+            err.error,
+            BackendError::AlreadyMinted {
+                indices: vec![1, 3]
+            }
+        );
+        assert!(err.message.unwrap().contains("[1, 3]"));
+
+        let err = ApiError::localized(BackendError::too_long("title", 240, 200), Locale::En);
+        assert_eq!(err.code, "VALIDATION_FAILED");
+        assert_eq!(
+            err.message.as_deref(),
+            Some("Title is too long (maximum 200 characters, got 240)")
+        );
+    }
+}
@@ -0,0 +1,178 @@
+// backend/src/fingerprint.rs
+
+use serde_cbor::value::Value as CborValue;
+use sha2::{Digest, Sha256};
+
+// =====================
+// 1) 유사도 기준
+// =====================
+
+/// MinHash 서명 길이 (해시 함수 개수)
+pub const SIGNATURE_SIZE: usize = 64;
+
+/// 이 유사도(%) 이상이면 다른 사람이 민팅한 데이터셋의 재판매로 보고 거부
+pub const REJECT_SIMILARITY_PERCENT: u8 = 90;
+
+/// 이 유사도(%) 이상이면 경고만 반환
+pub const WARN_SIMILARITY_PERCENT: u8 = 60;
+
+/// 바이너리 자산을 나누는 조각 크기
+const ASSET_SHINGLE_SIZE: usize = 4 * 1024;
+
+// =====================
+// 2) 레코드 정규화
+// =====================
+
+/// 레코드를 컬럼 순서/이름, 공백, 대소문자, 숫자 표기에 무관한 문자열로 정규화
+///
+/// 필드 값만 정규화해 정렬하므로 CSV 컬럼 순서를 바꾸거나 헤더 이름을 바꿔도 같다.
+pub fn normalize_record(record: &CborValue) -> String {
+    let mut values = Vec::new();
+    collect_values(record, &mut values);
+    values.sort();
+    values.join("\u{1f}")
+}
+
+fn collect_values(value: &CborValue, values: &mut Vec<String>) {
+    match value {
+        CborValue::Map(fields) => fields.values().for_each(|v| collect_values(v, values)),
+        CborValue::Array(items) => items.iter().for_each(|v| collect_values(v, values)),
+        other => values.push(normalize_scalar(other)),
+    }
+}
+
+fn normalize_scalar(value: &CborValue) -> String {
+    match value {
+        CborValue::Text(text) => text
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase(),
+        CborValue::Integer(number) => number.to_string(),
+        CborValue::Float(number) if number.fract() == 0.0 && number.abs() < 1e15 => {
+            (*number as i64).to_string()
+        }
+        CborValue::Float(number) => number.to_string(),
+        CborValue::Bool(flag) => flag.to_string(),
+        CborValue::Bytes(bytes) => hex::encode(bytes),
+        _ => String::new(),
+    }
+}
+
+// =====================
+// 3) MinHash 서명
+// =====================
+
+/// 정규화된 레코드 집합의 MinHash 서명
+///
+/// 두 서명에서 같은 위치의 값이 같을 확률이 레코드 집합의 자카드 유사도와 같다.
+pub fn minhash_records(records: &[CborValue]) -> Vec<u64> {
+    minhash(records.iter().map(normalize_record))
+}
+
+/// 바이너리 자산의 MinHash 서명 (4 KiB 조각 집합)
+///
+/// 파일 전체를 한 조각으로 쓰면 완전히 같은 파일만 일치하므로, 일부만 바뀐 파일도
+/// 남은 조각 비율만큼 유사하게 나오도록 나눈다.
+pub fn minhash_bytes(content: &[u8]) -> Vec<u64> {
+    minhash(content.chunks(ASSET_SHINGLE_SIZE))
+}
+
+/// 바이트 조각 집합의 MinHash 서명 (비어 있으면 빈 서명)
+pub fn minhash<S: AsRef<[u8]>>(shingles: impl IntoIterator<Item = S>) -> Vec<u64> {
+    let mut signature = vec![u64::MAX; SIGNATURE_SIZE];
+    let mut empty = true;
+
+    for shingle in shingles {
+        empty = false;
+        let digest = Sha256::digest(shingle.as_ref());
+        let base = u64::from_le_bytes(digest[..8].try_into().unwrap_or_default());
+        for (seed, slot) in signature.iter_mut().enumerate() {
+            *slot = (*slot).min(mix(base ^ (seed as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)));
+        }
+    }
+
+    if empty {
+        Vec::new()
+    } else {
+        signature
+    }
+}
+
+/// 두 서명의 추정 유사도 (0-100, 서명이 없거나 길이가 다르면 0)
+pub fn similarity_percent(a: &[u64], b: &[u64]) -> u8 {
+    if a.is_empty() || a.len() != b.len() {
+        return 0;
+    }
+    let equal = a.iter().zip(b).filter(|(x, y)| x == y).count();
+    (equal * 100 / a.len()) as u8
+}
+
+/// splitmix64 혼합 함수
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn record(fields: &[(&str, CborValue)]) -> CborValue {
+        CborValue::Map(
+            fields
+                .iter()
+                .map(|(name, value)| (CborValue::Text(name.to_string()), value.clone()))
+                .collect::<BTreeMap<_, _>>(),
+        )
+    }
+
+    #[test]
+    fn test_reordered_and_reformatted_records_match() {
+        let original: Vec<CborValue> = (0..50)
+            .map(|i| {
+                record(&[
+                    ("city", CborValue::Text(format!("Seoul {}", i))),
+                    ("count", CborValue::Integer(i)),
+                ])
+            })
+            .collect();
+        // 컬럼 이름/순서, 공백, 대소문자, 숫자 표기, 레코드 순서를 바꾼 사본
+        let copied: Vec<CborValue> = (0..50)
+            .rev()
+            .map(|i| {
+                record(&[
+                    ("a_total", CborValue::Float(i as f64)),
+                    ("b_name", CborValue::Text(format!("  SEOUL   {} ", i))),
+                ])
+            })
+            .collect();
+        let unrelated: Vec<CborValue> = (100..150)
+            .map(|i| record(&[("x", CborValue::Integer(i))]))
+            .collect();
+
+        let signature = minhash_records(&original);
+        assert_eq!(
+            similarity_percent(&signature, &minhash_records(&copied)),
+            100
+        );
+        assert!(similarity_percent(&signature, &minhash_records(&unrelated)) < 20);
+        assert_eq!(similarity_percent(&signature, &[]), 0);
+    }
+
+    #[test]
+    fn test_partially_modified_asset_is_similar() {
+        let original: Vec<u8> = (0..100 * ASSET_SHINGLE_SIZE)
+            .map(|i| (i / ASSET_SHINGLE_SIZE) as u8)
+            .collect();
+        let mut modified = original.clone();
+        modified[..16].copy_from_slice(b"edited thumbnail");
+
+        let signature = minhash_bytes(&original);
+        assert!(similarity_percent(&signature, &minhash_bytes(&modified)) >= 80);
+        assert!(similarity_percent(&signature, &minhash_bytes(&[7u8; 4096])) < 20);
+    }
+}
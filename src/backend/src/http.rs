@@ -0,0 +1,390 @@
+// backend/src/http.rs
+
+use crate::marketplace_types::{Listing, ListingStatus};
+use crate::types::*;
+use crate::upload;
+use candid::Principal;
+use serde_cbor::value::Value as CborValue;
+
+// 스트리밍 조각 크기 (쿼리 응답 한도보다 충분히 작게)
+const HTTP_CHUNK_SIZE: usize = 1024 * 1024;
+const PUBLIC_CACHE_CONTROL: &str = "public, max-age=60";
+// 업로드 데이터는 소유자/구매자에게만 제공하므로 공유 캐시에 남기지 않음
+const PRIVATE_CACHE_CONTROL: &str = "private, no-store";
+
+/// 게이트웨이 경로
+#[derive(Debug, PartialEq)]
+enum Route {
+    Listing(u64),
+    ListingPreview(u64),
+    Data(u64), // 인증된 에이전트 전용 (게이트웨이 요청은 익명 주체라 401)
+}
+
+// =====================
+// 1) 경로 및 본문 생성 (인증 트리와 공유)
+// =====================
+
+pub fn listing_path(listing_id: u64) -> String {
+    format!("/listings/{}", listing_id)
+}
+
+pub fn preview_path(listing_id: u64) -> String {
+    format!("/listings/{}/preview", listing_id)
+}
+
+pub fn data_path(data_id: u64) -> String {
+    format!("/data/{}", data_id)
+}
+
+/// HTTP로 공개되는 판매글 (판매 중이거나 판매 완료)
+fn is_publicly_visible(listing: &Listing) -> bool {
+    matches!(listing.status, ListingStatus::Active | ListingStatus::Sold)
+}
+
+/// 판매글 JSON 본문 (공개되지 않는 판매글은 None)
+pub fn listing_body(listing: &Listing) -> Option<Vec<u8>> {
+    if !is_publicly_visible(listing) {
+        return None;
+    }
+    serde_json::to_vec(listing).ok()
+}
+
+/// 판매글 미리보기 본문 (자동 생성 미리보기와 판매자 메모의 JSON)
+///
+/// 미리보기는 항상 저장된 레코드에서 생성한 값을 쓰고, 판매자가 입력한 preview_data는
+/// 메모로만 함께 보낸다.
+pub fn preview_body(listing: &Listing) -> Option<Vec<u8>> {
+    if !is_publicly_visible(listing) {
+        return None;
+    }
+    if listing.preview.is_none() && listing.preview_data.is_none() {
+        return None;
+    }
+    serde_json::to_vec(&serde_json::json!({
+        "preview": listing.preview,
+        "seller_note": listing.preview_data,
+    }))
+    .ok()
+}
+
+/// 업로드 데이터의 응답 Content-Type
+fn data_content_type(blob: &DataBlob) -> String {
+    match blob.mime_type.as_str() {
+        "text/csv" => "text/csv; charset=utf-8".to_string(),
+        // 레코드 단위로 정규화되는 형식은 JSON으로 제공
+        upload::MIME_NDJSON | upload::MIME_XML | upload::MIME_PARQUET | upload::MIME_XLSX => {
+            upload::MIME_JSON.to_string()
+        }
+        other => other.to_string(),
+    }
+}
+
+/// 업로드 데이터를 원래 MIME 형식으로 렌더링
+///
+/// JSON/CSV 업로드는 레코드 단위 CBOR로 저장되므로 원래 형식으로 되돌린다.
+/// NDJSON/XML/Parquet/XLSX 레코드는 JSON 객체로 렌더링한다.
+pub fn render_data_body(blob: &DataBlob) -> Result<Vec<u8>, String> {
+    match blob.mime_type.as_str() {
+        upload::MIME_JSON
+        | upload::MIME_NDJSON
+        | upload::MIME_XML
+        | upload::MIME_PARQUET
+        | upload::MIME_XLSX => {
+            let value: CborValue = serde_cbor::from_slice(&blob.data)
+                .map_err(|e| format!("CBOR 디코딩 실패: {}", e))?;
+            serde_json::to_vec(&value).map_err(|e| format!("JSON 변환 실패: {}", e))
+        }
+        "text/csv" => {
+            let value: CborValue = serde_cbor::from_slice(&blob.data)
+                .map_err(|e| format!("CBOR 디코딩 실패: {}", e))?;
+            let fields: Vec<String> = match value {
+                CborValue::Array(fields) => fields.into_iter().map(csv_field).collect(),
+                // 컬럼 이름을 키로 하는 맵 레코드는 스키마의 컬럼 순서대로 출력
+                CborValue::Map(mut fields) => {
+                    let schema = blob.schema_id.and_then(crate::storage::get_schema);
+                    let mut values: Vec<String> = schema
+                        .into_iter()
+                        .flat_map(|schema| schema.columns)
+                        .filter_map(|column| fields.remove(&CborValue::Text(column.name)))
+                        .map(csv_field)
+                        .collect();
+                    values.extend(fields.into_values().map(csv_field));
+                    values
+                }
+                _ => return Err("CSV 레코드 형식이 아닙니다".to_string()),
+            };
+
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            writer
+                .write_record(&fields)
+                .map_err(|e| format!("CSV 변환 실패: {}", e))?;
+            writer
+                .into_inner()
+                .map_err(|e| format!("CSV 변환 실패: {}", e))
+        }
+        _ => Ok(blob.data.clone()),
+    }
+}
+
+/// CSV 필드 문자열 (null은 빈 값)
+pub fn csv_field(value: CborValue) -> String {
+    match value {
+        CborValue::Text(text) => text,
+        CborValue::Null => String::new(),
+        CborValue::Bool(flag) => flag.to_string(),
+        CborValue::Integer(number) => number.to_string(),
+        CborValue::Float(number) => number.to_string(),
+        other => serde_json::to_string(&other).unwrap_or_default(),
+    }
+}
+
+// =====================
+// 2) 요청 처리
+// =====================
+
+/// 경로 해석 (인증서는 정규 경로 기준이므로 끝 슬래시, 쿼리 문자열 등 다른 형태는 거부)
+fn parse_route(url: &str) -> Option<Route> {
+    let segments: Vec<&str> = url.strip_prefix('/')?.split('/').collect();
+
+    let route = match segments.as_slice() {
+        ["listings", id] => Route::Listing(id.parse().ok()?),
+        ["listings", id, "preview"] => Route::ListingPreview(id.parse().ok()?),
+        ["data", id] => Route::Data(id.parse().ok()?),
+        _ => return None,
+    };
+    (route_path(&route) == url).then_some(route)
+}
+
+fn route_path(route: &Route) -> String {
+    match route {
+        Route::Listing(listing_id) => listing_path(*listing_id),
+        Route::ListingPreview(listing_id) => preview_path(*listing_id),
+        Route::Data(data_id) => data_path(*data_id),
+    }
+}
+
+/// HTTP 게이트웨이 요청 처리 (requester는 업로드 데이터 접근 확인용)
+pub fn handle_http_request(request: HttpRequest, requester: Principal) -> HttpResponse {
+    let is_head = request.method.eq_ignore_ascii_case("HEAD");
+    if !is_head && !request.method.eq_ignore_ascii_case("GET") {
+        return error_response(405, "GET 또는 HEAD 요청만 지원합니다");
+    }
+
+    let mut response = match parse_route(&request.url) {
+        Some(Route::Listing(listing_id)) => serve_listing(listing_id),
+        Some(Route::ListingPreview(listing_id)) => serve_preview(listing_id),
+        Some(Route::Data(data_id)) => serve_data(data_id, requester),
+        None => error_response(404, "경로를 찾을 수 없습니다"),
+    };
+
+    if is_head {
+        response.body.clear();
+        response.streaming_strategy = None;
+    }
+    response
+}
+
+fn serve_listing(listing_id: u64) -> HttpResponse {
+    let body = crate::marketplace_storage::get_listing_readonly(listing_id)
+        .as_ref()
+        .and_then(listing_body);
+
+    match body {
+        Some(body) => ok_response(
+            &listing_path(listing_id),
+            "application/json",
+            PUBLIC_CACHE_CONTROL,
+            body,
+        ),
+        None => error_response(404, "판매글을 찾을 수 없습니다"),
+    }
+}
+
+fn serve_preview(listing_id: u64) -> HttpResponse {
+    let body = crate::marketplace_storage::get_listing_readonly(listing_id)
+        .as_ref()
+        .and_then(preview_body);
+
+    match body {
+        Some(body) => ok_response(
+            &preview_path(listing_id),
+            "application/json",
+            PUBLIC_CACHE_CONTROL,
+            body,
+        ),
+        None => error_response(404, "미리보기를 찾을 수 없습니다"),
+    }
+}
+
+fn serve_data(data_id: u64, requester: Principal) -> HttpResponse {
+    if crate::storage::get_data_blob(data_id).is_none() {
+        return error_response(404, "데이터를 찾을 수 없습니다");
+    }
+
+    // 원본은 소유자와 구매자에게만 제공 (그 외에는 판매글 미리보기 사용)
+    if requester == Principal::anonymous() {
+        return error_response(
+            401,
+            "데이터 원본은 인증된 에이전트로 http_request를 호출해야 조회할 수 있습니다",
+        );
+    }
+    let Some(masked_columns) = crate::marketplace_storage::data_masked_columns(requester, data_id)
+    else {
+        return error_response(403, "데이터 소유자 또는 구매자만 조회할 수 있습니다");
+    };
+    let Some(blob) = crate::storage::get_masked_data_blob(data_id, &masked_columns) else {
+        return error_response(500, "데이터를 읽을 수 없습니다");
+    };
+
+    let content_type = data_content_type(&blob);
+    let body = match data_body(blob) {
+        Ok(body) => body,
+        Err(e) => return error_response(500, &e),
+    };
+
+    let mut response = ok_response(
+        &data_path(data_id),
+        &content_type,
+        PRIVATE_CACHE_CONTROL,
+        body,
+    );
+    // 가린 본문은 인증 트리의 원본 해시와 다르므로 인증 헤더를 붙이지 않음
+    if !masked_columns.is_empty() {
+        response
+            .headers
+            .retain(|(name, _)| !name.eq_ignore_ascii_case("IC-Certificate"));
+    }
+
+    // 큰 데이터는 첫 조각만 보내고 나머지는 콜백으로 스트리밍
+    if response.body.len() > HTTP_CHUNK_SIZE {
+        response.body.truncate(HTTP_CHUNK_SIZE);
+        response.streaming_strategy = Some(StreamingStrategy::Callback {
+            callback: StreamingCallback::new(
+                ic_cdk::api::id(),
+                "http_request_streaming_callback".to_string(),
+            ),
+            token: StreamingCallbackToken {
+                data_id,
+                chunk_index: 1,
+            },
+        });
+    }
+    response
+}
+
+/// 스트리밍 콜백 (다음 조각 반환, 첫 요청과 같은 접근 확인)
+pub fn handle_streaming_callback(
+    token: StreamingCallbackToken,
+    requester: Principal,
+) -> StreamingCallbackHttpResponse {
+    let body = crate::marketplace_storage::data_masked_columns(requester, token.data_id)
+        .and_then(|masked_columns| {
+            crate::storage::get_masked_data_blob(token.data_id, &masked_columns)
+        })
+        .and_then(|blob| data_body(blob).ok())
+        .unwrap_or_default();
+
+    let (chunk, has_more) = chunk_at(&body, token.chunk_index);
+
+    StreamingCallbackHttpResponse {
+        body: chunk.to_vec(),
+        token: has_more.then(|| StreamingCallbackToken {
+            data_id: token.data_id,
+            chunk_index: token.chunk_index + 1,
+        }),
+    }
+}
+
+/// 응답 본문 (바이너리 자산은 변환 없이 저장된 바이트를 그대로 사용)
+fn data_body(blob: DataBlob) -> Result<Vec<u8>, String> {
+    if blob.asset.is_some() {
+        return Ok(blob.data);
+    }
+    render_data_body(&blob)
+}
+
+/// chunk_index번째 조각과 이후 조각 존재 여부
+fn chunk_at(body: &[u8], chunk_index: u64) -> (&[u8], bool) {
+    let start = (chunk_index as usize)
+        .saturating_mul(HTTP_CHUNK_SIZE)
+        .min(body.len());
+    let end = start.saturating_add(HTTP_CHUNK_SIZE).min(body.len());
+    (&body[start..end], end < body.len())
+}
+
+fn ok_response(path: &str, content_type: &str, cache_control: &str, body: Vec<u8>) -> HttpResponse {
+    let mut headers = vec![
+        ("Content-Type".to_string(), content_type.to_string()),
+        ("Content-Length".to_string(), body.len().to_string()),
+        ("Cache-Control".to_string(), cache_control.to_string()),
+    ];
+    if let Some(certificate_header) = crate::certification::http_certificate_header(path) {
+        headers.push(certificate_header);
+    }
+
+    HttpResponse {
+        status_code: 200,
+        headers,
+        body,
+        streaming_strategy: None,
+        upgrade: None,
+    }
+}
+
+fn error_response(status_code: u16, message: &str) -> HttpResponse {
+    let body = serde_json::json!({ "error": message })
+        .to_string()
+        .into_bytes();
+
+    HttpResponse {
+        status_code,
+        headers: vec![(
+            "Content-Type".to_string(),
+            "application/json; charset=utf-8".to_string(),
+        )],
+        body,
+        streaming_strategy: None,
+        upgrade: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_route() {
+        assert_eq!(parse_route("/listings/12"), Some(Route::Listing(12)));
+        assert_eq!(
+            parse_route("/listings/12/preview"),
+            Some(Route::ListingPreview(12))
+        );
+        assert_eq!(parse_route("/data/3"), Some(Route::Data(3)));
+        // 정규 경로가 아니면 인증서와 맞지 않으므로 거부
+        assert_eq!(parse_route("/listings/12/preview?lang=ko"), None);
+        assert_eq!(parse_route("/data/3/"), None);
+        assert_eq!(parse_route("/data/03"), None);
+        assert_eq!(parse_route("/data/abc"), None);
+        assert_eq!(parse_route("/"), None);
+    }
+
+    #[test]
+    fn test_render_csv_record_and_chunks() {
+        let row = CborValue::Array(vec![
+            CborValue::Text("seoul".to_string()),
+            CborValue::Text("12, 5".to_string()),
+        ]);
+        let blob = DataBlob {
+            data: serde_cbor::to_vec(&row).unwrap(),
+            mime_type: "text/csv".to_string(),
+            timestamp: 0,
+            schema_id: None,
+            asset: None,
+        };
+        assert_eq!(render_data_body(&blob).unwrap(), b"seoul,\"12, 5\"\n");
+
+        let body = vec![0u8; HTTP_CHUNK_SIZE + 10];
+        assert_eq!(chunk_at(&body, 0), (&body[..HTTP_CHUNK_SIZE], true));
+        assert_eq!(chunk_at(&body, 1), (&body[HTTP_CHUNK_SIZE..], false));
+    }
+}
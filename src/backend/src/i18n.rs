@@ -0,0 +1,407 @@
+// backend/src/i18n.rs
+
+use crate::error::BackendError;
+use crate::types::PiiKind;
+use candid::{CandidType, Deserialize};
+use ic_stable_structures::Storable;
+use serde::Serialize;
+use std::borrow::Cow;
+
+// =====================
+// 1) 로케일
+// =====================
+
+/// 메시지 언어
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    Ko,
+    En,
+}
+
+impl Storable for Locale {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(vec![match self {
+            Locale::Ko => 0,
+            Locale::En => 1,
+        }])
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        match bytes.first() {
+            Some(1) => Locale::En,
+            _ => Locale::Ko,
+        }
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound =
+        ic_stable_structures::storable::Bound::Bounded {
+            max_size: 1,
+            is_fixed_size: true,
+        };
+}
+
+// =====================
+// 2) 메시지 카탈로그
+// =====================
+
+/// (한국어, 영어) 쌍
+type Entry = (&'static str, &'static str);
+
+fn pick(entry: Entry, locale: Locale) -> String {
+    match locale {
+        Locale::Ko => entry.0.to_string(),
+        Locale::En => entry.1.to_string(),
+    }
+}
+
+/// 필드/리소스 이름
+fn term(name: &str) -> Option<Entry> {
+    let entry = match name {
+        "data" => ("데이터", "Data"),
+        "records" => ("레코드", "Records"),
+        "content" => ("업로드 내용", "Upload content"),
+        "mime_type" => ("MIME 타입", "MIME type"),
+        "cid" => ("CID", "CID"),
+        "metadata" => ("메타데이터", "Metadata"),
+        "title" => ("제목", "Title"),
+        "description" => ("설명", "Description"),
+        "price" => ("가격", "Price"),
+        "data_ids" => ("판매할 데이터", "Listed data"),
+        "dataset" => ("데이터셋", "Dataset"),
+        "record" => ("레코드", "Record"),
+        "dataset_ids" => ("판매할 데이터셋", "Listed datasets"),
+        "dataset_name" => ("데이터셋 이름", "Dataset name"),
+        "key_column" => ("키 컬럼", "Key column"),
+        "row_limit" => ("미리보기 행 수", "Preview row limit"),
+        "visible_columns" => ("공개 컬럼", "Visible columns"),
+        "masked_columns" => ("마스킹 컬럼", "Masked columns"),
+        "columns" => ("컬럼", "Columns"),
+        "filters" => ("필터", "Filters"),
+        "group_by" => ("그룹 컬럼", "Group-by columns"),
+        "aggregates" => ("집계", "Aggregates"),
+        "limit" => ("최대 행 수", "Row limit"),
+        "category" => ("카테고리", "Category"),
+        "tags" => ("태그", "Tags"),
+        "page_size" => ("페이지 크기", "Page size"),
+        "min_price" => ("최소 가격", "Minimum price"),
+        "min_quality" => ("최소 품질 점수", "Minimum quality score"),
+        "cursor" => ("커서", "Cursor"),
+        "name" => ("검색 이름", "Search name"),
+        "max_entries" => ("최대 보관 개수", "Maximum entries"),
+        "max_age_days" => ("최대 보관 일수", "Maximum age in days"),
+        "listing" => ("판매글", "Listing"),
+        "favorite" => ("즐겨찾기", "Favorite"),
+        "saved_search" => ("저장된 검색", "Saved search"),
+        "purchase" => ("구매 기록", "Purchase"),
+        "buyer" => ("구매자", "Buyer"),
+        "saved_searches" => ("저장된 검색", "Saved searches"),
+        "upload_bytes" => ("업로드 크기(바이트)", "Upload size in bytes"),
+        "queries" => ("검색어", "Search queries"),
+        "search_rate" => ("시간당 검색어 기록", "Recorded search queries per hour"),
+        "json_schema" => ("JSON 스키마", "JSON Schema"),
+        "csv_header" => ("CSV 헤더", "CSV header"),
+        "encoding" => ("문자 인코딩", "Text encoding"),
+        "quote" => ("따옴표 문자", "Quote character"),
+        "lines" => ("줄", "Line"),
+        "file_name" => ("파일 이름", "File name"),
+        "thumbnail" => ("썸네일", "Thumbnail"),
+        "preview_text" => ("미리보기 설명", "Preview text"),
+        "sheet" => ("시트", "Sheet"),
+        _ => return None,
+    };
+    Some(entry)
+}
+
+/// 검증 실패 사유
+fn reason(code: &str) -> Option<Entry> {
+    let entry = match code {
+        "required" => ("필수 항목입니다", "is required"),
+        "empty" => ("비어 있습니다", "is empty"),
+        "duplicate" => ("중복된 데이터가 있습니다", "contains duplicates"),
+        "invalid_format" => ("형식이 올바르지 않습니다", "has an invalid format"),
+        "unsupported" => ("지원하지 않는 값입니다", "is not supported"),
+        "invalid_cbor" => ("CBOR 형식이 아닙니다", "is not valid CBOR"),
+        "invalid_json" => ("JSON 파싱에 실패했습니다", "could not be parsed as JSON"),
+        "invalid_csv" => ("CSV 파싱에 실패했습니다", "could not be parsed as CSV"),
+        "content_mismatch" => (
+            "내용이 선언한 MIME 타입과 일치하지 않습니다",
+            "does not match the declared MIME type",
+        ),
+        "unknown_column" => ("존재하지 않는 컬럼입니다", "is not a known column"),
+        "masked_column" => (
+            "판매자가 가린 컬럼은 사용할 수 없습니다",
+            "cannot use a column masked by the seller",
+        ),
+        "invalid_xml" => ("XML 파싱에 실패했습니다", "could not be parsed as XML"),
+        "invalid_parquet" => (
+            "Parquet 파일을 읽을 수 없습니다",
+            "could not be read as a Parquet file",
+        ),
+        "invalid_xlsx" => (
+            "XLSX 파일을 읽을 수 없습니다",
+            "could not be read as an XLSX workbook",
+        ),
+        "invalid_encoding" => (
+            "지정한 인코딩으로 해석할 수 없습니다",
+            "could not be decoded with the selected encoding",
+        ),
+        "must_be_positive" => ("0보다 커야 합니다", "must be greater than zero"),
+        "schema_mismatch" => (
+            "지정한 JSON 스키마와 맞지 않습니다",
+            "does not match the supplied JSON Schema",
+        ),
+        "exceeds_max_price" => (
+            "최대 가격보다 클 수 없습니다",
+            "cannot exceed the maximum price",
+        ),
+        "scope_mismatch" => (
+            "다른 정렬 기준이나 검색 조건에서 발급되었습니다",
+            "was issued for a different sort order or query",
+        ),
+        _ => return None,
+    };
+    Some(entry)
+}
+
+/// 상태 오류 사유
+fn state_reason(code: &str) -> Option<Entry> {
+    let entry = match code {
+        "data_already_minted" => (
+            "민팅된 데이터는 삭제할 수 없습니다",
+            "Minted data cannot be deleted",
+        ),
+        "data_in_use" => (
+            "데이터셋이나 판매글에 포함된 데이터는 삭제할 수 없습니다",
+            "Data included in a dataset or listing cannot be deleted",
+        ),
+        "dataset_modified" => (
+            "데이터셋 레코드가 업로드 이후 변경되었습니다",
+            "Dataset records changed after upload",
+        ),
+        "not_latest_version" => (
+            "최신 버전에만 새 버전을 게시할 수 있습니다",
+            "A new version can only be published on top of the latest version",
+        ),
+        "different_lineage" => (
+            "같은 데이터셋의 버전끼리만 비교할 수 있습니다",
+            "Only versions of the same dataset can be compared",
+        ),
+        "listing_not_active" => ("판매 중인 판매글이 아닙니다", "The listing is not active"),
+        "pii_hash_key_not_ready" => (
+            "개인정보 해시 키가 아직 준비되지 않았습니다. 잠시 후 다시 시도해 주세요",
+            "The PII hashing key is not ready yet. Please try again shortly",
+        ),
+        "own_listing" => (
+            "자신의 판매글은 즐겨찾기할 수 없습니다",
+            "You cannot favorite your own listing",
+        ),
+        _ => return None,
+    };
+    Some(entry)
+}
+
+/// 개인정보 종류
+fn pii_kind(kind: PiiKind) -> Entry {
+    match kind {
+        PiiKind::Email => ("이메일", "email"),
+        PiiKind::Phone => ("전화번호", "phone number"),
+        PiiKind::ResidentRegistrationNumber => ("주민등록번호", "resident registration number"),
+        PiiKind::CardNumber => ("카드 번호", "card number"),
+        PiiKind::IpAddress => ("IP 주소", "IP address"),
+    }
+}
+
+/// 필드 이름 번역 ("records[3]"처럼 인덱스가 붙은 이름 포함)
+fn field_label(field: &str, locale: Locale) -> String {
+    let (base, index) = match field.split_once('[') {
+        Some((base, rest)) => (base, rest.strip_suffix(']')),
+        None => (field, None),
+    };
+    let label = term(base).map_or_else(|| base.to_string(), |entry| pick(entry, locale));
+    match index {
+        Some(index) => format!("{} {}", label, index),
+        None => label,
+    }
+}
+
+/// 에러를 지정한 언어의 메시지로 변환
+pub fn localize(error: &BackendError, locale: Locale) -> String {
+    match error {
+        BackendError::NotFound { resource, id } => {
+            let resource = field_label(resource, locale);
+            match (locale, id) {
+                (Locale::Ko, Some(id)) => format!("{} {}를 찾을 수 없습니다", resource, id),
+                (Locale::Ko, None) => format!("{}를 찾을 수 없습니다", resource),
+                (Locale::En, Some(id)) => format!("{} {} was not found", resource, id),
+                (Locale::En, None) => format!("{} was not found", resource),
+            }
+        }
+        BackendError::Unauthorized { action } => match locale {
+            Locale::Ko => format!("{} 작업에 대한 권한이 없습니다", action),
+            Locale::En => format!("You are not authorized to perform {}", action),
+        },
+        BackendError::Validation {
+            field,
+            reason: code,
+        } => {
+            let field = field_label(field, locale);
+            let reason = reason(code).map_or_else(|| code.clone(), |entry| pick(entry, locale));
+            match locale {
+                Locale::Ko => format!("{}: {}", field, reason),
+                Locale::En => format!("{} {}", field, reason),
+            }
+        }
+        BackendError::TooLong { field, length, max } => {
+            let field = field_label(field, locale);
+            match locale {
+                Locale::Ko => format!("{}: 너무 깁니다 (최대 {}자, 현재 {}자)", field, max, length),
+                Locale::En => format!(
+                    "{} is too long (maximum {} characters, got {})",
+                    field, max, length
+                ),
+            }
+        }
+        BackendError::TooLarge { field, size, max } => {
+            let field = field_label(field, locale);
+            match locale {
+                Locale::Ko => format!(
+                    "{}: 크기가 너무 큽니다 (최대 {}바이트, 현재 {}바이트)",
+                    field, max, size
+                ),
+                Locale::En => format!(
+                    "{} is too large (maximum {} bytes, got {})",
+                    field, max, size
+                ),
+            }
+        }
+        BackendError::TooSmall { field, size, min } => {
+            let field = field_label(field, locale);
+            match locale {
+                Locale::Ko => format!(
+                    "{}: 너무 짧습니다 (최소 {}바이트, 현재 {}바이트)",
+                    field, min, size
+                ),
+                Locale::En => format!(
+                    "{} is too short (minimum {} bytes, got {})",
+                    field, min, size
+                ),
+            }
+        }
+        BackendError::OutOfRange {
+            field,
+            value,
+            min,
+            max,
+        } => {
+            let field = field_label(field, locale);
+            match locale {
+                Locale::Ko => format!(
+                    "{}: 허용 범위({}-{})를 벗어났습니다 (현재 {})",
+                    field, min, max, value
+                ),
+                Locale::En => format!("{} is out of range ({}-{}, got {})", field, min, max, value),
+            }
+        }
+        BackendError::DuplicateRecords { indices } => match locale {
+            Locale::Ko => format!("중복된 레코드가 있습니다. 인덱스: {:?}", indices),
+            Locale::En => format!("Duplicate records found. Indices: {:?}", indices),
+        },
+        BackendError::AlreadyExists { resource } => {
+            let resource = field_label(resource, locale);
+            match locale {
+                Locale::Ko => format!("이미 존재하는 {}입니다", resource),
+                Locale::En => format!("{} already exists", resource),
+            }
+        }
+        BackendError::AlreadyMinted { indices } => match locale {
+            Locale::Ko => format!(
+                "이미 민팅된 데이터가 포함되어 있습니다. 인덱스: {:?}",
+                indices
+            ),
+            Locale::En => format!("Already minted data is included. Indices: {:?}", indices),
+        },
+        BackendError::QuotaExceeded { resource, limit } => {
+            let resource = field_label(resource, locale);
+            match locale {
+                Locale::Ko => format!("{} 한도({})를 초과했습니다", resource, limit),
+                Locale::En => format!("{} exceeds the limit of {}", resource, limit),
+            }
+        }
+        BackendError::NearDuplicate {
+            dataset_id,
+            similarity_percent,
+        } => match locale {
+            Locale::Ko => format!(
+                "이미 민팅된 데이터셋 {}와 {}% 유사합니다",
+                dataset_id, similarity_percent
+            ),
+            Locale::En => format!(
+                "Is {}% similar to already minted dataset {}",
+                similarity_percent, dataset_id
+            ),
+        },
+        BackendError::PiiDetected { findings } => {
+            let fields = findings
+                .iter()
+                .map(|finding| {
+                    format!(
+                        "{}({})",
+                        finding.field,
+                        pick(pii_kind(finding.kind), locale)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            match locale {
+                Locale::Ko => format!("개인정보가 포함되어 있습니다: {}", fields),
+                Locale::En => format!("Personal data detected: {}", fields),
+            }
+        }
+        BackendError::InvalidState { reason: code } => match state_reason(code) {
+            Some(entry) => pick(entry, locale),
+            None => match locale {
+                Locale::Ko => format!("현재 상태에서 허용되지 않는 작업입니다 ({})", code),
+                Locale::En => format!("Operation not allowed in the current state ({})", code),
+            },
+        },
+        BackendError::Internal { reason } => match locale {
+            Locale::Ko => format!("내부 오류: {}", reason),
+            Locale::En => format!("Internal error: {}", reason),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_localized_validation_messages() {
+        let error = BackendError::validation("content", "empty");
+        assert_eq!(localize(&error, Locale::Ko), "업로드 내용: 비어 있습니다");
+        assert_eq!(localize(&error, Locale::En), "Upload content is empty");
+
+        let error = BackendError::too_small("metadata[2]", 4, 10);
+        assert_eq!(
+            localize(&error, Locale::En),
+            "Metadata 2 is too short (minimum 10 bytes, got 4)"
+        );
+
+        let error = BackendError::out_of_range("min_quality", 120u8, 0, 100);
+        assert_eq!(
+            localize(&error, Locale::Ko),
+            "최소 품질 점수: 허용 범위(0-100)를 벗어났습니다 (현재 120)"
+        );
+
+        let error = BackendError::not_found("listing", 7);
+        assert_eq!(localize(&error, Locale::En), "Listing 7 was not found");
+    }
+
+    #[test]
+    fn test_locale_storable_roundtrip() {
+        for locale in [Locale::Ko, Locale::En] {
+            assert_eq!(Locale::from_bytes(locale.to_bytes()), locale);
+        }
+    }
+}
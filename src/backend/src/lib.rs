@@ -1,9 +1,21 @@
 // backend/src/lib.rs
 
+mod asset;
+mod certification;
+mod error;
+mod fingerprint;
+mod http;
+mod i18n;
 mod marketplace;
 mod marketplace_storage;
 mod marketplace_types;
+mod merkle;
 mod nft;
+mod pii;
+mod preview;
+mod quality;
+mod query;
+mod schema;
 mod storage;
 mod types;
 mod upload;
@@ -12,6 +24,8 @@ mod validation;
 // mod wallet_storage;
 // mod wallet_types;
 
+use crate::error::{ApiError, BackendError};
+use crate::i18n::Locale;
 use crate::marketplace::*;
 use crate::marketplace_types::*;
 use crate::storage::*;
@@ -37,6 +51,15 @@ fn init() {
     // 마켓플레이스 저장소 초기화
     marketplace_storage::init_marketplace_storage();
 
+    // 인증 데이터 트리 구성
+    certification::rebuild_certified_tree();
+
+    // 개인정보 해시 키 생성 예약
+    storage::ensure_pii_hash_key();
+
+    // 추천 모델 갱신 및 조회 기록 정리 타이머 시작
+    marketplace::start_maintenance_timers();
+
     // 지갑 저장소는 현재 비활성화
     // wallet_storage::init_wallet_storage();
 
@@ -60,9 +83,26 @@ fn post_upgrade() {
     marketplace_storage::init_marketplace_storage();
     // wallet_storage::init_wallet_storage();
 
+    // 인증 트리는 힙에만 있으므로 업그레이드 후 재구성
+    certification::rebuild_certified_tree();
+
+    // 타이머는 업그레이드 시 해제되므로 다시 등록
+    storage::ensure_pii_hash_key();
+    marketplace::start_maintenance_timers();
+
     ic_cdk::println!("Post-upgrade initialization completed");
 }
 
+/// 호출별 언어 지정이 없으면 호출자의 선호 언어 사용
+fn resolve_locale(locale: Option<Locale>) -> Locale {
+    locale.unwrap_or_else(|| storage::get_user_locale(caller()))
+}
+
+/// 구조화된 에러를 호출자 언어의 API 에러로 변환
+fn api_error(error: BackendError) -> ApiError {
+    ApiError::localized(error, resolve_locale(None))
+}
+
 /// 수동 저장소 초기화 (테스트/디버깅용)
 #[update]
 #[candid_method(update)]
@@ -76,6 +116,33 @@ fn init_storage_manual() -> String {
     "Storage manually initialized successfully".to_string()
 }
 
+// =====================
+// 사용자 언어 설정
+// =====================
+
+/// 에러 메시지 선호 언어 설정 (호출별 locale 인자가 없을 때 사용)
+#[update]
+#[candid_method(update)]
+fn set_preferred_locale(locale: Locale) -> Result<(), ApiError> {
+    let user = caller();
+    if user == Principal::anonymous() {
+        return Err(ApiError::localized(
+            BackendError::unauthorized("set_preferred_locale"),
+            locale,
+        ));
+    }
+
+    storage::set_user_locale(user, locale);
+    Ok(())
+}
+
+/// 에러 메시지 선호 언어 조회
+#[query]
+#[candid_method(query)]
+fn get_preferred_locale() -> Locale {
+    storage::get_user_locale(caller())
+}
+
 // =====================
 // 데이터 업로드 및 관리
 // =====================
@@ -83,36 +150,167 @@ fn init_storage_manual() -> String {
 /// 데이터 업로드
 #[update]
 #[candid_method(update)]
-fn upload(request: UploadRequest) -> Result<UploadResponse, String> {
+fn upload(request: UploadRequest, locale: Option<Locale>) -> Result<UploadResponse, ApiError> {
+    let localize = |error| ApiError::localized(error, resolve_locale(locale));
+
     // 파일 크기 검증
-    validate_data_size(&request.content, 10 * 1024 * 1024)?; // 10MB 제한
+    validate_data_size(&request.content, 10 * 1024 * 1024).map_err(localize)?; // 10MB 제한
 
     // MIME 타입 검증
-    validate_mime_type(&request.mime_type)?;
+    validate_mime_type(&request.mime_type).map_err(localize)?;
 
-    // 데이터 파싱
-    let parsed_data = upload_data(request.content, &request.mime_type)?;
+    // 데이터셋 이름 검증
+    validate_dataset_name(request.name.as_deref()).map_err(localize)?;
 
-    // 데이터 검증
-    validate_data(&parsed_data)?;
+    let uploader = caller();
 
-    // 저장소에 저장
-    let data_ids = store_upload_data(parsed_data, &request.mime_type)?;
+    // 새 버전 게시 권한 확인 (파싱 전에 거부)
+    if let Some(previous_id) = request.previous_version {
+        validate_new_version(previous_id, uploader).map_err(localize)?;
+    }
 
-    // 바이트 배열로 변환
-    let data_bytes: Vec<Vec<u8>> = data_ids
-        .into_iter()
-        .map(|id| id.to_le_bytes().to_vec())
-        .collect();
+    let (mut dataset, similar_datasets) = if asset::is_asset_mime_type(&request.mime_type) {
+        // 키 컬럼은 레코드가 있는 형식에만 의미가 있음
+        if request.key_column.is_some() {
+            return Err(localize(BackendError::validation(
+                "key_column",
+                "unsupported",
+            )));
+        }
 
-    Ok(UploadResponse { data: data_bytes })
+        // 이미지/PDF/압축 파일은 레코드로 나누지 않고 하나의 블롭으로 저장
+        let info =
+            asset::prepare_asset(&request.content, &request.mime_type, request.asset_options)
+                .map_err(localize)?;
+
+        // 다른 사용자가 민팅한 데이터셋의 재업로드인지 확인
+        let fingerprint = fingerprint::minhash_bytes(&request.content);
+        let similar = check_near_duplicates(&fingerprint, uploader).map_err(localize)?;
+
+        let mut dataset = store_binary_data(
+            uploader,
+            request.name,
+            request.content,
+            &request.mime_type,
+            info,
+        )
+        .map_err(localize)?;
+        dataset.fingerprint = fingerprint;
+        (dataset, similar)
+    } else {
+        // 데이터 파싱
+        let parsed = upload_data(
+            request.content,
+            &request.mime_type,
+            request.csv_options.as_ref(),
+            request.excel_options.as_ref(),
+        )
+        .map_err(localize)?;
+        let mut parsed_data = parsed.records;
+
+        // 데이터 검증 및 개인정보 탐지 (치환 모드면 치환된 레코드를 저장)
+        let pii_report = validate_data(&mut parsed_data, request.pii_mode.unwrap_or_default())
+            .map_err(localize)?;
+
+        // 스키마 추론 및 (지정 시) JSON Schema 검증
+        let mut dataset_schema = schema::infer_schema(&parsed_data);
+        if let Some(columns) = &parsed.columns {
+            schema::apply_column_order(&mut dataset_schema, columns, parsed.has_header);
+        }
+        if let Some(json_schema) = request.json_schema {
+            schema::validate_json_schema(&parsed_data, &json_schema).map_err(localize)?;
+            dataset_schema.json_schema = Some(json_schema);
+        }
+        if let Some(key_column) = &request.key_column {
+            if !dataset_schema
+                .columns
+                .iter()
+                .any(|column| &column.name == key_column)
+            {
+                return Err(localize(BackendError::validation(
+                    "key_column",
+                    "unknown_column",
+                )));
+            }
+        }
+
+        // 컬럼 순서/공백 등을 바꾼 재업로드인지 확인
+        let fingerprint = fingerprint::minhash_records(&parsed_data);
+        let similar = check_near_duplicates(&fingerprint, uploader).map_err(localize)?;
+
+        // 완전성/타입 일관성/중복률/이상치 품질 평가
+        let quality = quality::assess(&parsed_data, &dataset_schema);
+
+        // 저장소에 저장
+        let mut dataset = store_upload_data(
+            uploader,
+            request.name,
+            parsed_data,
+            &request.mime_type,
+            dataset_schema,
+        )
+        .map_err(localize)?;
+        dataset.fingerprint = fingerprint;
+        dataset.quality = Some(quality);
+        dataset.pii_report = Some(pii_report);
+        (dataset, similar)
+    };
+    dataset = publish_dataset_version(dataset, request.previous_version, request.key_column)
+        .map_err(localize)?;
+
+    // 이 계보의 최신 버전을 따르는 판매글을 새 버전 기준으로 갱신
+    if dataset.previous_version.is_some() {
+        marketplace_storage::refresh_following_listings(dataset.lineage());
+    }
+
+    storage::record_audit(
+        uploader,
+        AuditAction::DataUploaded,
+        format!(
+            "dataset_id={}, version={}, records={}, mime_type={}, pii_records={}",
+            dataset.id,
+            dataset.version,
+            dataset.record_ids.len(),
+            request.mime_type,
+            dataset
+                .pii_report
+                .as_ref()
+                .map_or(0, |report| report.records_affected)
+        ),
+    );
+
+    Ok(UploadResponse {
+        dataset_id: dataset.id,
+        record_ids: dataset.record_ids,
+        root_hash: dataset.root_hash,
+        similar_datasets,
+        pii_report: dataset.pii_report,
+    })
 }
 
-/// 업로드된 데이터 조회
+/// 업로드된 데이터 조회 (소유자와 구매자만, 그 외에는 None)
+///
+/// 구매자에게는 판매글 미리보기에서 가린 컬럼을 가린 레코드를 반환한다.
 #[query]
 #[candid_method(query)]
 fn get_uploaded_data(data_id: u64) -> Option<Vec<u8>> {
-    storage::get_uploaded_data(data_id)
+    let masked_columns = marketplace_storage::data_masked_columns(caller(), data_id)?;
+    storage::get_masked_data_blob(data_id, &masked_columns).map(|blob| blob.data)
+}
+
+/// 업로드된 데이터 인증 조회 (payload는 원본 바이트)
+///
+/// 접근 권한이 없으면 payload 없이 블롭 해시 witness만 반환한다.
+/// 가린 컬럼이 있는 구매자도 원본을 받을 수 없으므로 payload가 없다
+/// (가린 레코드는 인증할 수 없으니 get_uploaded_data 사용).
+#[query]
+#[candid_method(query)]
+fn get_uploaded_data_certified(data_id: u64) -> CertifiedResponse {
+    let payload = storage::get_uploaded_data(data_id).filter(|_| {
+        marketplace_storage::data_masked_columns(caller(), data_id)
+            .is_some_and(|masked_columns| masked_columns.is_empty())
+    });
+    certification::certified_response(certification::BLOBS_LABEL, vec![(data_id, payload)], None)
 }
 
 /// 업로드된 데이터 목록 조회
@@ -122,11 +320,25 @@ fn list_uploaded_data() -> Vec<DataInfo> {
     storage::list_uploaded_data()
 }
 
-/// 업로드된 데이터 삭제
+/// 업로드된 데이터 삭제 (컨트롤러 전용, 데이터셋이나 판매글에 포함된 레코드는 거부)
 #[update]
 #[candid_method(update)]
-fn delete_uploaded_data(data_id: u64) -> Result<String, String> {
-    storage::delete_uploaded_data(data_id)
+fn delete_uploaded_data(data_id: u64) -> Result<String, ApiError> {
+    // 데이터셋에 속하지 않은 레코드는 소유자를 알 수 없으므로 컨트롤러만 삭제
+    if !ic_cdk::api::is_controller(&caller()) {
+        return Err(api_error(BackendError::unauthorized(
+            "delete_uploaded_data",
+        )));
+    }
+    let message = storage::delete_uploaded_data(data_id).map_err(api_error)?;
+
+    storage::record_audit(
+        caller(),
+        AuditAction::DataDeleted,
+        format!("data_id={}", data_id),
+    );
+
+    Ok(message)
 }
 
 /// 저장소 통계 조회
@@ -136,6 +348,84 @@ fn get_storage_stats() -> StorageStats {
     storage::get_storage_stats()
 }
 
+// =====================
+// 데이터셋 조회
+// =====================
+
+/// 데이터셋 정보 조회
+#[query]
+#[candid_method(query)]
+fn get_dataset(dataset_id: u64) -> Option<DatasetInfo> {
+    storage::get_dataset_info(dataset_id)
+}
+
+/// 데이터셋의 레코드 ID 목록 (업로드 순서, 소유자와 구매자만)
+#[query]
+#[candid_method(query)]
+fn get_dataset_record_ids(dataset_id: u64) -> Option<Vec<u64>> {
+    storage::get_dataset(dataset_id)
+        .filter(|dataset| marketplace_storage::can_read_dataset(caller(), dataset))
+        .map(|dataset| dataset.record_ids)
+}
+
+/// 레코드의 데이터셋 포함 증명 (머클 경로)
+#[query]
+#[candid_method(query)]
+fn get_record_proof(dataset_id: u64, record_id: u64) -> Result<RecordProof, ApiError> {
+    storage::get_record_proof(dataset_id, record_id).map_err(api_error)
+}
+
+/// 레코드 바이트와 포함 증명 검증 (캐니스터 상태와 무관한 순수 계산)
+#[query]
+#[candid_method(query)]
+fn verify_record_proof(record: Vec<u8>, proof: RecordProof) -> bool {
+    merkle::verify_record_proof(&record, &proof)
+}
+
+/// 데이터셋 버전 이력 (최신 버전부터)
+#[query]
+#[candid_method(query)]
+fn get_dataset_versions(dataset_id: u64) -> Vec<DatasetVersionSummary> {
+    storage::get_dataset_versions(dataset_id)
+}
+
+/// 데이터셋의 최신 버전 정보
+#[query]
+#[candid_method(query)]
+fn get_latest_dataset(dataset_id: u64) -> Option<DatasetInfo> {
+    storage::get_dataset_info(storage::latest_dataset_id(dataset_id))
+}
+
+/// 두 버전 사이의 레코드 차이 (to_dataset_id를 생략하면 최신 버전과 비교, 소유자/구매자 전용)
+#[query]
+#[candid_method(query)]
+fn get_dataset_diff(
+    from_dataset_id: u64,
+    to_dataset_id: Option<u64>,
+) -> Result<DatasetDiff, ApiError> {
+    let requester = caller();
+    storage::get_dataset_diff(from_dataset_id, to_dataset_id, |dataset| {
+        marketplace_storage::can_read_dataset(requester, dataset)
+    })
+    .map_err(api_error)
+}
+
+/// 데이터셋 레코드 쿼리 (컬럼 선택, 필터, 그룹별 집계, limit/offset)
+///
+/// 데이터셋 소유자나 구매자만 호출할 수 있다.
+#[query]
+#[candid_method(query)]
+fn query_dataset(query: DatasetQuery) -> Result<QueryResult, ApiError> {
+    query::run_query(&query, caller()).map_err(api_error)
+}
+
+/// 내가 업로드한 데이터셋 목록
+#[query]
+#[candid_method(query)]
+fn list_my_datasets() -> Vec<DatasetInfo> {
+    storage::list_datasets_by_owner(caller())
+}
+
 // =====================
 // 데이터 존재 및 상태 확인
 // =====================
@@ -168,7 +458,10 @@ fn check_multiple_data_status(data_list: Vec<Vec<u8>>) -> Vec<(Option<u64>, bool
 /// 민팅 요청
 #[update]
 #[candid_method(update)]
-fn request_mint(request: MintRequest) -> RequestResponse {
+fn request_mint(mut request: MintRequest) -> RequestResponse {
+    let requester = caller();
+    let cid = request.cid.clone();
+
     // 1. 검증을 먼저 수행하고 실패 시 즉시 에러 상태로 저장
     let validation_result = validate_mint_request(&request.cid, &request.metadata);
     let user_validation_result = validate_user_permission(request.owner);
@@ -177,20 +470,46 @@ fn request_mint(request: MintRequest) -> RequestResponse {
     if let Err(validation_error) = validation_result {
         ic_cdk::println!("Mint request validation failed: {}", validation_error);
         let request_id = storage::store_mint_request(request);
-        let _ = storage::update_mint_status(request_id, MintStatus::Failed(validation_error));
+        record_mint_request_audit(requester, request_id, &cid);
+        let _ = storage::update_mint_status(
+            request_id,
+            MintStatus::Failed(validation_error.message(storage::get_user_locale(requester))),
+        );
         return RequestResponse { request_id };
     }
 
     if let Err(user_error) = user_validation_result {
         ic_cdk::println!("User permission validation failed: {}", user_error);
         let request_id = storage::store_mint_request(request);
-        let _ = storage::update_mint_status(request_id, MintStatus::Failed(user_error));
+        record_mint_request_audit(requester, request_id, &cid);
+        let _ = storage::update_mint_status(
+            request_id,
+            MintStatus::Failed(user_error.message(storage::get_user_locale(requester))),
+        );
         return RequestResponse { request_id };
     }
 
+    // 데이터셋 민팅이면 머클 루트 커밋먼트를 토큰 메타데이터에 추가
+    if let Some(dataset_id) = request.dataset_id {
+        match storage::dataset_commitment(dataset_id, requester) {
+            Ok(commitment) => request.metadata.push(commitment),
+            Err(dataset_error) => {
+                ic_cdk::println!("Dataset commitment failed: {}", dataset_error);
+                let request_id = storage::store_mint_request(request);
+                record_mint_request_audit(requester, request_id, &cid);
+                let _ = storage::update_mint_status(
+                    request_id,
+                    MintStatus::Failed(dataset_error.message(storage::get_user_locale(requester))),
+                );
+                return RequestResponse { request_id };
+            }
+        }
+    }
+
     // 2. 검증 통과 시에만 정상 저장
     ic_cdk::println!("Mint request validation passed, storing request");
     let request_id = storage::store_mint_request(request);
+    record_mint_request_audit(requester, request_id, &cid);
 
     // 3. 비동기 민팅 처리 시작
     ic_cdk::spawn(async move {
@@ -201,6 +520,15 @@ fn request_mint(request: MintRequest) -> RequestResponse {
     RequestResponse { request_id }
 }
 
+/// 민팅 요청 감사 로그 기록
+fn record_mint_request_audit(requester: Principal, request_id: u64, cid: &str) {
+    storage::record_audit(
+        requester,
+        AuditAction::MintRequested,
+        format!("request_id={}, cid={}", request_id, cid),
+    );
+}
+
 /// 민팅 상태 조회
 #[query]
 #[candid_method(query)]
@@ -208,6 +536,19 @@ fn get_mint_status(request_id: u64) -> Option<MintStatus> {
     storage::get_mint_status(request_id)
 }
 
+/// 민팅 상태 인증 조회 (payload는 상태의 CBOR 인코딩)
+#[query]
+#[candid_method(query)]
+fn get_mint_status_certified(request_id: u64) -> CertifiedResponse {
+    let payload =
+        storage::get_mint_status(request_id).map(|status| certification::cbor_payload(&status));
+    certification::certified_response(
+        certification::MINT_STATUS_LABEL,
+        vec![(request_id, payload)],
+        None,
+    )
+}
+
 /// 민팅 요청 목록 조회
 #[query]
 #[candid_method(query)]
@@ -279,42 +620,103 @@ fn list_tokens() -> Vec<u64> {
 // 마켓플레이스 기능
 // =====================
 
-/// 판매글 생성
+/// 판매글 생성 (유사한 민팅 데이터셋이 있으면 similar_datasets로 경고)
 #[update]
 #[candid_method(update)]
-fn create_listing(request: CreateListingRequest) -> Result<FavoriteRequest, String> {
-    let response = create_listing_service(request)?;
-    Ok(FavoriteRequest {
-        listing_id: response.listing_id,
-    })
+fn create_listing(
+    request: CreateListingRequest,
+    locale: Option<Locale>,
+) -> Result<CreateListingResponse, ApiError> {
+    create_listing_service(request)
+        .map_err(|error| ApiError::localized(error, resolve_locale(locale)))
 }
 
 /// 판매글 업데이트
 #[update]
 #[candid_method(update)]
-fn update_listing(request: UpdateListingRequest) -> Result<SuccessResponse, String> {
-    update_listing_service(request)
+fn update_listing(request: UpdateListingRequest) -> Result<SuccessResponse, ApiError> {
+    update_listing_service(request).map_err(api_error)
 }
 
 /// 판매글 삭제
 #[update]
 #[candid_method(update)]
-fn delete_listing(listing_id: u64) -> Result<SuccessResponse, String> {
-    delete_listing_service(listing_id)
+fn delete_listing(listing_id: u64) -> Result<SuccessResponse, ApiError> {
+    delete_listing_service(listing_id).map_err(api_error)
+}
+
+/// 판매 완료 기록 (판매자 전용)
+#[update]
+#[candid_method(update)]
+fn complete_sale(listing_id: u64, buyer: Principal) -> Result<Purchase, ApiError> {
+    complete_sale_service(listing_id, buyer).map_err(api_error)
+}
+
+/// 내 구매 기록 조회
+#[query]
+#[candid_method(query)]
+fn get_my_purchases() -> Vec<Purchase> {
+    get_my_purchases_service()
 }
 
-/// 판매글 상세 조회
+/// 판매글 게시 전 자동 미리보기 확인 (공개 컬럼/마스킹 컬럼 선택)
+#[query]
+#[candid_method(query)]
+fn preview_listing_data(
+    dataset_ids: Vec<u64>,
+    options: Option<PreviewOptions>,
+) -> Result<DataPreview, ApiError> {
+    preview_listing_data_service(dataset_ids, options).map_err(api_error)
+}
+
+/// 판매글 상세 조회 (조회수는 record_view로 별도 기록)
 #[query]
 #[candid_method(query)]
 fn get_listing_detail(listing_id: u64) -> Option<ListingDetail> {
     get_listing_detail_service(listing_id)
 }
 
-/// 판매글 목록 조회
+/// 판매글 조회 기록 (중복 조회는 집계하지 않음)
+#[update]
+#[candid_method(update)]
+fn record_view(listing_id: u64) -> Result<bool, ApiError> {
+    record_view_service(listing_id).map_err(api_error)
+}
+
+/// 판매글 조회수 통계 (최근 days일, 기본 30일)
+#[query]
+#[candid_method(query)]
+fn get_listing_view_stats(listing_id: u64, days: Option<u64>) -> Option<ListingViewStats> {
+    get_listing_view_stats_service(listing_id, days)
+}
+
+/// 판매글 인증 조회
 #[query]
 #[candid_method(query)]
-fn list_listings(status: Option<ListingStatus>, limit: Option<u64>) -> Vec<ListingSummary> {
-    list_listings_service(status, limit)
+fn get_listing_certified(listing_id: u64) -> CertifiedResponse {
+    get_listing_certified_service(listing_id)
+}
+
+/// 판매글 목록 인증 조회 (커서 기반, 반환한 항목만 인증하며 목록의 완전성은 증명하지 않음)
+#[query]
+#[candid_method(query)]
+fn list_listings_certified(
+    status: Option<ListingStatus>,
+    limit: Option<u64>,
+    cursor: Option<String>,
+) -> Result<CertifiedResponse, ApiError> {
+    list_listings_certified_service(status, limit, cursor).map_err(api_error)
+}
+
+/// 판매글 목록 조회 (커서 기반)
+#[query]
+#[candid_method(query)]
+fn list_listings(
+    status: Option<ListingStatus>,
+    limit: Option<u64>,
+    cursor: Option<String>,
+) -> Result<ListingPage, ApiError> {
+    list_listings_service(status, limit, cursor).map_err(api_error)
 }
 
 /// 내 판매글 조회
@@ -324,11 +726,15 @@ fn get_my_listings() -> Vec<ListingSummary> {
     get_my_listings_service()
 }
 
-/// 특정 사용자의 판매글 조회
+/// 특정 사용자의 판매글 조회 (커서 기반)
 #[query]
 #[candid_method(query)]
-fn get_user_listings(user: Principal) -> Vec<ListingSummary> {
-    get_user_listings_service(user)
+fn get_user_listings(
+    user: Principal,
+    limit: Option<u64>,
+    cursor: Option<String>,
+) -> Result<ListingPage, ApiError> {
+    get_user_listings_service(user, limit, cursor).map_err(api_error)
 }
 
 // =====================
@@ -338,8 +744,12 @@ fn get_user_listings(user: Principal) -> Vec<ListingSummary> {
 /// 판매글 검색
 #[query]
 #[candid_method(query)]
-fn search_listings(request: SearchListingsRequest) -> Result<SearchResult, String> {
+fn search_listings(
+    request: SearchListingsRequest,
+    locale: Option<Locale>,
+) -> Result<SearchResult, ApiError> {
     search_listings_service(request)
+        .map_err(|error| ApiError::localized(error, resolve_locale(locale)))
 }
 
 /// 고급 검색
@@ -354,13 +764,14 @@ fn advanced_search(
     seller: Option<Principal>,
     sort_by: Option<SortBy>,
     page: Option<u64>,
-) -> Result<SearchResult, String> {
+) -> Result<SearchResult, ApiError> {
     let price_range = match (min_price, max_price) {
         (Some(min), Some(max)) => Some((min, max)),
         _ => None,
     };
 
     advanced_search_service(keywords, category, price_range, tags, seller, sort_by, page)
+        .map_err(api_error)
 }
 
 /// 검색어 자동완성
@@ -370,18 +781,29 @@ fn get_search_suggestions(partial_query: String, limit: Option<u64>) -> Vec<Stri
     get_search_suggestions_service(partial_query, limit.map(|l| l as usize))
 }
 
-/// 인기 검색어 조회
+/// 인기 검색어 조회 (실제 검색 기록 기반)
 #[query]
 #[candid_method(query)]
-fn get_trending_keywords(limit: Option<u64>) -> Vec<(String, u32)> {
-    get_trending_keywords_service(limit.map(|l| l as usize))
+fn get_trending_keywords(limit: Option<u64>, window: Option<TrendingWindow>) -> Vec<(String, u32)> {
+    get_trending_keywords_service(limit.map(|l| l as usize), window)
+}
+
+/// 검색어 일괄 기록 (쿼리로 실행된 검색을 클라이언트가 모아서 전송)
+#[update]
+#[candid_method(update)]
+fn record_search_queries(queries: Vec<String>) -> Result<u64, ApiError> {
+    record_search_queries_service(queries).map_err(api_error)
 }
 
 /// 검색 결과 통계
 #[query]
 #[candid_method(query)]
-fn get_search_stats(request: SearchListingsRequest) -> Result<SearchStats, String> {
+fn get_search_stats(
+    request: SearchListingsRequest,
+    locale: Option<Locale>,
+) -> Result<SearchStats, ApiError> {
     get_search_stats_service(request)
+        .map_err(|error| ApiError::localized(error, resolve_locale(locale)))
 }
 
 /// 카테고리별 판매글 수 조회
@@ -419,15 +841,15 @@ fn normalize_search_query(query: String) -> String {
 /// 즐겨찾기 추가
 #[update]
 #[candid_method(update)]
-fn add_favorite(request: FavoriteRequest) -> Result<SuccessResponse, String> {
-    add_favorite_service(request)
+fn add_favorite(request: FavoriteRequest) -> Result<SuccessResponse, ApiError> {
+    add_favorite_service(request).map_err(api_error)
 }
 
 /// 즐겨찾기 제거
 #[update]
 #[candid_method(update)]
-fn remove_favorite(request: FavoriteRequest) -> Result<SuccessResponse, String> {
-    remove_favorite_service(request)
+fn remove_favorite(request: FavoriteRequest) -> Result<SuccessResponse, ApiError> {
+    remove_favorite_service(request).map_err(api_error)
 }
 
 /// 내 즐겨찾기 목록 조회
@@ -444,22 +866,29 @@ fn is_favorited(listing_id: u64) -> bool {
     is_favorited_service(listing_id)
 }
 
+/// 판매글을 즐겨찾기한 사용자 목록 (판매자 전용)
+#[query]
+#[candid_method(query)]
+fn get_listing_favoriters(listing_id: u64) -> Result<Vec<Favorite>, ApiError> {
+    get_listing_favoriters_service(listing_id).map_err(api_error)
+}
+
 // =====================
 // 추천 시스템
 // =====================
 
-/// 추천 판매글 조회
+/// 추천 판매글 조회 (협업 필터링 + 추천 사유)
 #[query]
 #[candid_method(query)]
-fn get_recommended_listings(limit: u64) -> Vec<ListingSummary> {
+fn get_recommended_listings(limit: u64) -> Vec<Recommendation> {
     let user = caller();
     get_recommended_listings_service(user, limit)
 }
 
-/// 유사한 판매글 찾기
+/// 유사한 판매글 찾기 (함께 즐겨찾기/구매/조회된 판매글 우선)
 #[query]
 #[candid_method(query)]
-fn get_similar_listings(listing_id: u64, limit: u64) -> Vec<ListingSummary> {
+fn get_similar_listings(listing_id: u64, limit: u64) -> Vec<Recommendation> {
     get_similar_listings_service(listing_id, limit)
 }
 
@@ -488,11 +917,72 @@ fn get_marketplace_stats() -> MarketplaceStats {
     get_marketplace_stats_service()
 }
 
-/// 최근 활동 조회
+/// 최근 활동 조회 (커서 기반)
+#[query]
+#[candid_method(query)]
+fn get_recent_activities(
+    limit: Option<u64>,
+    cursor: Option<String>,
+) -> Result<ActivityPage, ApiError> {
+    get_recent_activities_service(limit, cursor).map_err(api_error)
+}
+
+/// 판매글별 활동 조회 (활동 타입 필터 가능)
+#[query]
+#[candid_method(query)]
+fn get_listing_activities(
+    listing_id: u64,
+    activity_type: Option<ActivityType>,
+    limit: Option<u64>,
+    cursor: Option<String>,
+) -> Result<ActivityPage, ApiError> {
+    get_listing_activities_service(listing_id, activity_type, limit, cursor).map_err(api_error)
+}
+
+/// 사용자별 활동 조회 (본인 또는 관리자)
+#[query]
+#[candid_method(query)]
+fn get_user_activities(
+    user: Principal,
+    activity_type: Option<ActivityType>,
+    limit: Option<u64>,
+    cursor: Option<String>,
+) -> Result<ActivityPage, ApiError> {
+    get_user_activities_service(user, activity_type, limit, cursor).map_err(api_error)
+}
+
+/// 활동 로그 저장 현황 (관리자 전용)
+#[query]
+#[candid_method(query)]
+fn get_activity_log_status() -> Result<ActivityLogStatus, ApiError> {
+    get_activity_log_status_service().map_err(api_error)
+}
+
+/// 활동 로그 보존 정책 변경 (관리자 전용)
+#[update]
+#[candid_method(update)]
+fn set_activity_retention_policy(
+    policy: ActivityRetentionPolicy,
+) -> Result<SuccessResponse, ApiError> {
+    set_activity_retention_policy_service(policy).map_err(api_error)
+}
+
+/// 활동 로그 즉시 아카이브 (관리자 전용)
+#[update]
+#[candid_method(update)]
+fn archive_activities() -> Result<u64, ApiError> {
+    archive_activities_service().map_err(api_error)
+}
+
+/// 활동 로그 내보내기 (CBOR 또는 JSON Lines, 관리자 전용)
 #[query]
 #[candid_method(query)]
-fn get_recent_activities(limit: Option<u64>) -> Vec<ActivityLog> {
-    get_recent_activities_service(limit)
+fn export_activities(
+    format: ExportFormat,
+    from_id: Option<u64>,
+    limit: Option<u64>,
+) -> Result<ActivityExport, ApiError> {
+    export_activities_service(format, from_id, limit).map_err(api_error)
 }
 
 /// 비활성 판매글 정리
@@ -505,8 +995,103 @@ fn cleanup_inactive_listings() -> u64 {
 /// 관리자 판매글 삭제
 #[update]
 #[candid_method(update)]
-fn admin_delete_listing(listing_id: u64) -> Result<SuccessResponse, String> {
-    admin_delete_listing_service(listing_id)
+fn admin_delete_listing(listing_id: u64) -> Result<SuccessResponse, ApiError> {
+    admin_delete_listing_service(listing_id).map_err(api_error)
+}
+
+// =====================
+// 저장된 검색 및 알림
+// =====================
+
+/// 검색 저장
+#[update]
+#[candid_method(update)]
+fn save_search(request: SaveSearchRequest) -> Result<u64, ApiError> {
+    save_search_service(request).map_err(api_error)
+}
+
+/// 내 저장된 검색 목록 조회
+#[query]
+#[candid_method(query)]
+fn get_my_saved_searches() -> Vec<SavedSearch> {
+    get_my_saved_searches_service()
+}
+
+/// 저장된 검색 삭제
+#[update]
+#[candid_method(update)]
+fn delete_saved_search(saved_search_id: u64) -> Result<SuccessResponse, ApiError> {
+    delete_saved_search_service(saved_search_id).map_err(api_error)
+}
+
+/// 내 알림 조회
+#[query]
+#[candid_method(query)]
+fn get_my_notifications(
+    unread_only: Option<bool>,
+    limit: Option<u64>,
+    cursor: Option<String>,
+) -> Result<NotificationPage, ApiError> {
+    get_my_notifications_service(unread_only, limit, cursor).map_err(api_error)
+}
+
+/// 알림 읽음 처리 (ids를 생략하면 전체 읽음)
+#[update]
+#[candid_method(update)]
+fn mark_notifications_read(ids: Option<Vec<u64>>) -> Result<u64, ApiError> {
+    mark_notifications_read_service(ids).map_err(api_error)
+}
+
+// =====================
+// HTTP 게이트웨이
+// =====================
+
+/// HTTP 게이트웨이 요청 처리 (/listings/{id}, /listings/{id}/preview, /data/{id})
+///
+/// /data/{id}는 소유자와 구매자만 조회할 수 있으므로 인증된 에이전트 전용이다.
+/// HTTP 게이트웨이(브라우저, curl 등)를 거친 요청은 익명 주체로 도착하므로 401을 받는다.
+#[query]
+#[candid_method(query)]
+fn http_request(request: HttpRequest) -> HttpResponse {
+    http::handle_http_request(request, caller())
+}
+
+/// 큰 데이터 스트리밍 콜백
+#[query]
+#[candid_method(query)]
+fn http_request_streaming_callback(token: StreamingCallbackToken) -> StreamingCallbackHttpResponse {
+    http::handle_streaming_callback(token, caller())
+}
+
+// =====================
+// 감사 로그
+// =====================
+
+/// 감사 로그 조회 (컨트롤러 전용)
+#[query]
+#[candid_method(query)]
+fn get_audit_log(from_seq: Option<u64>, limit: Option<u64>) -> Result<AuditPage, ApiError> {
+    if !ic_cdk::api::is_controller(&caller()) {
+        return Err(api_error(BackendError::unauthorized("get_audit_log")));
+    }
+
+    let limit = limit.unwrap_or(100).clamp(1, 500);
+    Ok(storage::get_audit_log(from_seq, limit))
+}
+
+/// 감사 로그 해시 체인 검증 (컨트롤러 전용)
+#[query]
+#[candid_method(query)]
+fn verify_audit_log(
+    from_seq: Option<u64>,
+    limit: Option<u64>,
+) -> Result<AuditVerification, ApiError> {
+    if !ic_cdk::api::is_controller(&caller()) {
+        return Err(api_error(BackendError::unauthorized("verify_audit_log")));
+    }
+
+    let limit = limit.unwrap_or(1_000).clamp(1, 10_000);
+    Ok(storage::verify_audit_chain(from_seq, limit))
 }
 
 // =====================
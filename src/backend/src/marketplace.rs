@@ -1,7 +1,10 @@
 // backend/src/marketplace.rs
 
+use crate::certification::{cbor_payload, certified_response, LISTINGS_LABEL};
+use crate::error::BackendError;
 use crate::marketplace_storage::*;
 use crate::marketplace_types::*;
+use crate::types::{AuditAction, CertifiedResponse, DataPreview, PreviewOptions, SimilarityMatch};
 use candid::Principal;
 use ic_cdk::caller;
 
@@ -12,7 +15,7 @@ use ic_cdk::caller;
 /// 판매글 생성
 pub fn create_listing_service(
     request: CreateListingRequest,
-) -> Result<CreateListingResponse, String> {
+) -> Result<CreateListingResponse, BackendError> {
     // 요청 검증
     request.validate()?;
 
@@ -20,23 +23,37 @@ pub fn create_listing_service(
 
     // 익명 사용자 차단
     if seller == Principal::anonymous() {
-        return Err("익명 사용자는 판매글을 생성할 수 없습니다".to_string());
+        return Err(BackendError::unauthorized("create_listing"));
     }
 
-    // 데이터 소유권 확인
-    validate_data_ownership(&request.data_ids, seller)?;
+    // 데이터셋 소유권, 민팅 여부, 유사 데이터셋 확인 후 레코드 ID 수집
+    let (data_ids, similar_datasets) = validate_listing_datasets(&request.dataset_ids, seller)?;
 
-    // 이미 민팅된 데이터인지 확인
-    validate_data_not_minted(&request.data_ids)?;
+    // 판매자가 고른 컬럼으로 미리보기 자동 생성
+    let preview = crate::preview::preview_datasets(
+        &request.dataset_ids,
+        &request.preview_options.clone().unwrap_or_default(),
+    )?;
 
     // 판매글 생성
-    let listing_id = create_listing(request, seller)?;
+    let listing_id = create_listing(request, data_ids, preview, seller)?;
+
+    crate::storage::record_audit(
+        seller,
+        AuditAction::ListingCreated,
+        format!("listing_id={}", listing_id),
+    );
 
-    Ok(CreateListingResponse { listing_id })
+    Ok(CreateListingResponse {
+        listing_id,
+        similar_datasets,
+    })
 }
 
 /// 판매글 업데이트
-pub fn update_listing_service(request: UpdateListingRequest) -> Result<SuccessResponse, String> {
+pub fn update_listing_service(
+    request: UpdateListingRequest,
+) -> Result<SuccessResponse, BackendError> {
     // 요청 검증
     request.validate()?;
 
@@ -44,46 +61,134 @@ pub fn update_listing_service(request: UpdateListingRequest) -> Result<SuccessRe
 
     // 익명 사용자 차단
     if user == Principal::anonymous() {
-        return Err("익명 사용자는 판매글을 수정할 수 없습니다".to_string());
+        return Err(BackendError::unauthorized("update_listing"));
     }
 
     // 판매글 업데이트
+    let listing_id = request.listing_id;
     update_listing(request, user)?;
 
+    crate::storage::record_audit(
+        user,
+        AuditAction::ListingUpdated,
+        format!("listing_id={}", listing_id),
+    );
+
     Ok(SuccessResponse {
         message: "판매글이 성공적으로 업데이트되었습니다".to_string(),
     })
 }
 
 /// 판매글 삭제
-pub fn delete_listing_service(listing_id: u64) -> Result<SuccessResponse, String> {
+pub fn delete_listing_service(listing_id: u64) -> Result<SuccessResponse, BackendError> {
     let user = caller();
 
     // 익명 사용자 차단
     if user == Principal::anonymous() {
-        return Err("익명 사용자는 판매글을 삭제할 수 없습니다".to_string());
+        return Err(BackendError::unauthorized("delete_listing"));
     }
 
     // 판매글 삭제
     delete_listing(listing_id, user)?;
 
+    crate::storage::record_audit(
+        user,
+        AuditAction::ListingDeleted,
+        format!("listing_id={}", listing_id),
+    );
+
     Ok(SuccessResponse {
         message: "판매글이 성공적으로 삭제되었습니다".to_string(),
     })
 }
 
+/// 판매 완료 기록 (판매자가 대금을 받은 뒤 구매자를 등록)
+pub fn complete_sale_service(listing_id: u64, buyer: Principal) -> Result<Purchase, BackendError> {
+    let seller = caller();
+
+    if seller == Principal::anonymous() {
+        return Err(BackendError::unauthorized("complete_sale"));
+    }
+
+    let purchase = record_sale(listing_id, seller, buyer, ic_cdk::api::time())?;
+
+    crate::storage::record_audit(
+        seller,
+        AuditAction::ListingSold,
+        format!("listing_id={} buyer={}", listing_id, buyer),
+    );
+
+    Ok(purchase)
+}
+
+/// 내 구매 기록 조회
+pub fn get_my_purchases_service() -> Vec<Purchase> {
+    let user = caller();
+
+    if user == Principal::anonymous() {
+        return Vec::new();
+    }
+
+    get_purchases_by_buyer(user)
+}
+
+/// 게시 전 미리보기 확인 (판매자 소유 데이터셋만)
+pub fn preview_listing_data_service(
+    dataset_ids: Vec<u64>,
+    options: Option<PreviewOptions>,
+) -> Result<DataPreview, BackendError> {
+    if dataset_ids.len() > 100 {
+        return Err(BackendError::quota_exceeded("dataset_ids", 100));
+    }
+    validate_dataset_ownership(&dataset_ids, caller())?;
+    crate::preview::preview_datasets(&dataset_ids, &options.unwrap_or_default())
+}
+
 /// 판매글 상세 조회
 pub fn get_listing_detail_service(listing_id: u64) -> Option<ListingDetail> {
     get_listing_detail(listing_id)
 }
 
+/// 판매글 인증 조회 (payload는 판매글의 CBOR 인코딩)
+pub fn get_listing_certified_service(listing_id: u64) -> CertifiedResponse {
+    let payload = get_listing_readonly(listing_id).map(|listing| cbor_payload(&listing));
+    certified_response(LISTINGS_LABEL, vec![(listing_id, payload)], None)
+}
+
+/// 판매글 목록 인증 조회
+///
+/// 반환하는 판매글 각각의 내용만 인증한다. 목록이 빠짐없이 완전한지
+/// (다른 판매글이 빠지지 않았는지)는 증명하지 않는다.
+pub fn list_listings_certified_service(
+    status: Option<ListingStatus>,
+    limit: Option<u64>,
+    cursor: Option<String>,
+) -> Result<CertifiedResponse, BackendError> {
+    let limit = limit.unwrap_or(20).min(50); // witness 크기를 고려해 일반 목록보다 작게 제한
+    let cursor = parse_cursor(cursor, listing_page_scope(status.as_ref()))?;
+    let page = list_listings_page(status, limit, cursor);
+
+    let values = page
+        .listings
+        .iter()
+        .map(|summary| {
+            let payload = get_listing_readonly(summary.id).map(|listing| cbor_payload(&listing));
+            (summary.id, payload)
+        })
+        .collect();
+
+    Ok(certified_response(LISTINGS_LABEL, values, page.next_cursor))
+}
+
 /// 판매글 목록 조회
 pub fn list_listings_service(
     status: Option<ListingStatus>,
     limit: Option<u64>,
-) -> Vec<ListingSummary> {
+    cursor: Option<String>,
+) -> Result<ListingPage, BackendError> {
     let limit = limit.unwrap_or(50).min(100); // 최대 100개로 제한
-    list_listings(status, Some(limit))
+    let cursor = parse_cursor(cursor, listing_page_scope(status.as_ref()))?;
+    Ok(list_listings_page(status, limit, cursor))
 }
 
 /// 내 판매글 조회
@@ -98,8 +203,14 @@ pub fn get_my_listings_service() -> Vec<ListingSummary> {
 }
 
 /// 특정 사용자의 판매글 조회
-pub fn get_user_listings_service(user: Principal) -> Vec<ListingSummary> {
-    get_listings_by_seller(user)
+pub fn get_user_listings_service(
+    user: Principal,
+    limit: Option<u64>,
+    cursor: Option<String>,
+) -> Result<ListingPage, BackendError> {
+    let limit = limit.unwrap_or(50).min(100);
+    let cursor = parse_cursor(cursor, seller_listing_page_scope(user))?;
+    Ok(get_listings_by_seller_page(user, limit, cursor))
 }
 
 // =====================
@@ -107,13 +218,63 @@ pub fn get_user_listings_service(user: Principal) -> Vec<ListingSummary> {
 // =====================
 
 /// 판매글 검색 (개선된 스마트 검색 사용)
-pub fn search_listings_service(request: SearchListingsRequest) -> Result<SearchResult, String> {
+pub fn search_listings_service(
+    request: SearchListingsRequest,
+) -> Result<SearchResult, BackendError> {
     // 요청 검증
     request.validate()?;
 
+    // 검색어 기록 (업데이트로 호출된 경우에만 저장됨)
+    if let Some(ref query) = request.query {
+        record_search_query_service(query);
+    }
+
     Ok(search_listings(&request))
 }
 
+/// 검색어 기록
+///
+/// 쿼리 호출에서는 상태 변경이 버려지므로 복제 실행(업데이트 호출)일 때만 기록한다.
+/// 익명 사용자의 검색과 시간당 기록 한도를 넘은 검색은 기록하지 않는다.
+pub fn record_search_query_service(query: &str) {
+    if !ic_cdk::api::in_replicated_execution() {
+        return;
+    }
+
+    let user = caller();
+    let now = ic_cdk::api::time();
+    if user == Principal::anonymous() || take_search_quota(user, 1, now) != Ok(1) {
+        return;
+    }
+
+    record_search_query(query, now);
+}
+
+/// 클라이언트가 모아둔 검색어 일괄 기록 (로그인 사용자만, 시간당 기록 한도 적용)
+///
+/// 한도에 걸리면 앞에서부터 남은 한도만큼만 기록한다.
+pub fn record_search_queries_service(queries: Vec<String>) -> Result<u64, BackendError> {
+    let user = caller();
+
+    if user == Principal::anonymous() {
+        return Err(BackendError::unauthorized("record_search_queries"));
+    }
+
+    if queries.len() > 50 {
+        return Err(BackendError::quota_exceeded("queries", 50));
+    }
+
+    let now = ic_cdk::api::time();
+    let allowed = take_search_quota(user, queries.len(), now)?;
+    let recorded = queries
+        .iter()
+        .take(allowed)
+        .filter(|query| record_search_query(query, now))
+        .count();
+
+    Ok(recorded as u64)
+}
+
 /// 검색어 자동완성
 pub fn get_search_suggestions_service(partial_query: String, limit: Option<usize>) -> Vec<String> {
     let limit = limit.unwrap_or(10).min(20); // 최대 20개로 제한
@@ -125,14 +286,28 @@ pub fn get_search_suggestions_service(partial_query: String, limit: Option<usize
     get_search_suggestions(&partial_query, limit)
 }
 
-/// 인기 검색어 조회
-pub fn get_trending_keywords_service(limit: Option<usize>) -> Vec<(String, u32)> {
+/// 인기 검색어 조회 (기본 최근 24시간)
+pub fn get_trending_keywords_service(
+    limit: Option<usize>,
+    window: Option<TrendingWindow>,
+) -> Vec<(String, u32)> {
     let limit = limit.unwrap_or(10).min(50);
-    get_trending_keywords(limit)
+    let window = window.unwrap_or(TrendingWindow::Day);
+
+    let keywords = get_trending_keywords(window.as_nanos(), limit, ic_cdk::api::time());
+
+    // 검색 기록이 아직 없으면 판매글 인기도 기반 키워드로 대체
+    if keywords.is_empty() {
+        return get_listing_keywords_by_popularity(limit);
+    }
+
+    keywords
 }
 
 /// 검색 결과 통계
-pub fn get_search_stats_service(request: SearchListingsRequest) -> Result<SearchStats, String> {
+pub fn get_search_stats_service(
+    request: SearchListingsRequest,
+) -> Result<SearchStats, BackendError> {
     // 요청 검증
     request.validate()?;
 
@@ -209,7 +384,7 @@ pub fn advanced_search_service(
     seller: Option<Principal>,
     sort_by: Option<SortBy>,
     page: Option<u64>,
-) -> Result<SearchResult, String> {
+) -> Result<SearchResult, BackendError> {
     let request = SearchListingsRequest {
         query: keywords,
         category,
@@ -222,6 +397,8 @@ pub fn advanced_search_service(
         sort_by,
         page,
         page_size: Some(20),
+        cursor: None,
+        min_quality: None,
     };
 
     search_listings_service(request)
@@ -279,21 +456,21 @@ pub fn get_related_keywords_service(query: String) -> Vec<String> {
 // =====================
 
 /// 즐겨찾기 추가
-pub fn add_favorite_service(request: FavoriteRequest) -> Result<SuccessResponse, String> {
+pub fn add_favorite_service(request: FavoriteRequest) -> Result<SuccessResponse, BackendError> {
     let user = caller();
 
     // 익명 사용자 차단
     if user == Principal::anonymous() {
-        return Err("익명 사용자는 즐겨찾기를 추가할 수 없습니다".to_string());
+        return Err(BackendError::unauthorized("add_favorite"));
     }
 
     // 자신의 판매글은 즐겨찾기할 수 없음
     if let Some(listing) = get_listing_readonly(request.listing_id) {
         if listing.seller == user {
-            return Err("자신의 판매글은 즐겨찾기할 수 없습니다".to_string());
+            return Err(BackendError::invalid_state("own_listing"));
         }
     } else {
-        return Err("판매글을 찾을 수 없습니다".to_string());
+        return Err(BackendError::not_found("listing", request.listing_id));
     }
 
     // 즐겨찾기 추가
@@ -305,12 +482,12 @@ pub fn add_favorite_service(request: FavoriteRequest) -> Result<SuccessResponse,
 }
 
 /// 즐겨찾기 제거
-pub fn remove_favorite_service(request: FavoriteRequest) -> Result<SuccessResponse, String> {
+pub fn remove_favorite_service(request: FavoriteRequest) -> Result<SuccessResponse, BackendError> {
     let user = caller();
 
     // 익명 사용자 차단
     if user == Principal::anonymous() {
-        return Err("익명 사용자는 즐겨찾기를 제거할 수 없습니다".to_string());
+        return Err(BackendError::unauthorized("remove_favorite"));
     }
 
     // 즐겨찾기 제거
@@ -343,6 +520,20 @@ pub fn is_favorited_service(listing_id: u64) -> bool {
     is_favorited(user, listing_id)
 }
 
+/// 판매글을 즐겨찾기한 사용자 목록 조회 (판매자 전용)
+pub fn get_listing_favoriters_service(listing_id: u64) -> Result<Vec<Favorite>, BackendError> {
+    let user = caller();
+
+    let listing = get_listing_readonly(listing_id)
+        .ok_or_else(|| BackendError::not_found("listing", listing_id))?;
+
+    if listing.seller != user {
+        return Err(BackendError::unauthorized("get_listing_favoriters"));
+    }
+
+    Ok(get_listing_favoriters(listing_id))
+}
+
 // =====================
 // 4) 통계 및 분석
 // =====================
@@ -353,39 +544,145 @@ pub fn get_marketplace_stats_service() -> MarketplaceStats {
 }
 
 /// 최근 활동 조회
-pub fn get_recent_activities_service(limit: Option<u64>) -> Vec<ActivityLog> {
+pub fn get_recent_activities_service(
+    limit: Option<u64>,
+    cursor: Option<String>,
+) -> Result<ActivityPage, BackendError> {
     let limit = limit.unwrap_or(10).min(50);
-    get_recent_activities(limit)
+    let cursor = parse_cursor(cursor, PageCursor::ACTIVITY_SCOPE)?;
+    Ok(get_recent_activities_page(limit, cursor))
+}
+
+/// 판매글별 활동 조회
+pub fn get_listing_activities_service(
+    listing_id: u64,
+    activity_type: Option<ActivityType>,
+    limit: Option<u64>,
+    cursor: Option<String>,
+) -> Result<ActivityPage, BackendError> {
+    let limit = limit.unwrap_or(20).min(100);
+    let cursor = parse_cursor(cursor, PageCursor::ACTIVITY_SCOPE)?;
+    Ok(get_listing_activities(
+        listing_id,
+        activity_type,
+        limit,
+        cursor,
+    ))
+}
+
+/// 사용자별 활동 조회 (본인 또는 관리자만)
+pub fn get_user_activities_service(
+    user: Principal,
+    activity_type: Option<ActivityType>,
+    limit: Option<u64>,
+    cursor: Option<String>,
+) -> Result<ActivityPage, BackendError> {
+    let requester = caller();
+
+    if requester != user && !is_admin(requester) {
+        return Err(BackendError::unauthorized("get_user_activities"));
+    }
+
+    let limit = limit.unwrap_or(20).min(100);
+    let cursor = parse_cursor(cursor, PageCursor::ACTIVITY_SCOPE)?;
+    Ok(get_user_activities(user, activity_type, limit, cursor))
 }
 
 // =====================
 // 5) 검증 함수들
 // =====================
 
-/// 데이터 소유권 확인
-fn validate_data_ownership(data_ids: &[u64], user: Principal) -> Result<(), String> {
-    // storage.rs의 새로운 함수 사용
-    crate::storage::validate_data_ids_exist(data_ids)?;
+/// 데이터셋 소유권 확인 (모두 판매자가 업로드한 데이터셋이어야 함)
+///
+/// 데이터셋들의 레코드 ID를 중복 없이 순서대로 반환한다.
+/// 판매할 데이터셋 검증 (레코드 ID와 유사 데이터셋 경고 목록 반환)
+///
+/// 판매글 생성과, 최신 버전을 따르는 판매글을 새 버전으로 옮길 때 같은 검증을 쓴다.
+pub fn validate_listing_datasets(
+    dataset_ids: &[u64],
+    seller: Principal,
+) -> Result<(Vec<u64>, Vec<SimilarityMatch>), BackendError> {
+    // 데이터셋 소유권 확인 후 레코드 ID 수집
+    let data_ids = validate_dataset_ownership(dataset_ids, seller)?;
 
-    // TODO: 실제 구현에서는 데이터 업로더 정보를 저장하고 검증해야 함
-    // 현재는 모든 사용자가 모든 데이터에 접근 가능하다고 가정
+    // 이미 민팅된 데이터인지 확인
+    validate_data_not_minted(&data_ids)?;
 
-    Ok(())
+    // 다른 사용자가 민팅한 데이터셋과 거의 같은지 확인
+    let similar_datasets = check_listing_near_duplicates(dataset_ids, seller)?;
+    Ok((data_ids, similar_datasets))
+}
+
+fn validate_dataset_ownership(
+    dataset_ids: &[u64],
+    user: Principal,
+) -> Result<Vec<u64>, BackendError> {
+    let mut data_ids = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    for &dataset_id in dataset_ids {
+        let dataset = crate::storage::get_dataset(dataset_id)
+            .ok_or_else(|| BackendError::not_found("dataset", dataset_id))?;
+        if dataset.owner != user {
+            return Err(BackendError::unauthorized("create_listing"));
+        }
+        data_ids.extend(
+            dataset
+                .record_ids
+                .into_iter()
+                .filter(|&data_id| seen.insert(data_id)),
+        );
+    }
+
+    crate::storage::validate_data_ids_exist(&data_ids)?;
+    Ok(data_ids)
+}
+
+/// 판매할 데이터셋들의 유사 데이터셋 확인 (거부 기준 이상이면 에러, 아니면 경고 목록)
+fn check_listing_near_duplicates(
+    dataset_ids: &[u64],
+    seller: Principal,
+) -> Result<Vec<SimilarityMatch>, BackendError> {
+    let mut matches: Vec<SimilarityMatch> = Vec::new();
+    for dataset in dataset_ids
+        .iter()
+        .filter_map(|&dataset_id| crate::storage::get_dataset(dataset_id))
+    {
+        for found in crate::storage::check_near_duplicates(&dataset.fingerprint, seller)? {
+            if !matches.iter().any(|m| m.dataset_id == found.dataset_id) {
+                matches.push(found);
+            }
+        }
+    }
+    Ok(matches)
+}
+
+/// 클라이언트가 전달한 페이지 커서 검증 (scope: 조회하는 목록의 커서 범위)
+fn parse_cursor(cursor: Option<String>, scope: u64) -> Result<Option<PageCursor>, BackendError> {
+    cursor
+        .map(|cursor| PageCursor::decode_scoped(&cursor, scope))
+        .transpose()
 }
 
 /// 이미 민팅된 데이터인지 확인
-fn validate_data_not_minted(data_ids: &[u64]) -> Result<(), String> {
+fn validate_data_not_minted(data_ids: &[u64]) -> Result<(), BackendError> {
+    let mut minted_ids = Vec::new();
     for &data_id in data_ids {
         // 데이터 가져오기
         let data = crate::storage::get_uploaded_data(data_id)
-            .ok_or_else(|| format!("데이터 ID {}를 찾을 수 없습니다", data_id))?;
+            .ok_or_else(|| BackendError::not_found("data", data_id))?;
 
         // 민팅 여부 확인
         if crate::storage::check_data_minted(&data) {
-            return Err(format!("데이터 ID {}는 이미 민팅되었습니다", data_id));
+            minted_ids.push(data_id);
         }
     }
 
+    if !minted_ids.is_empty() {
+        return Err(BackendError::AlreadyMinted {
+            indices: minted_ids,
+        });
+    }
+
     Ok(())
 }
 
@@ -393,53 +690,211 @@ fn validate_data_not_minted(data_ids: &[u64]) -> Result<(), String> {
 // 6) 관리자 기능 (추후 확장용)
 // =====================
 
-/// 관리자 권한 확인
+/// 관리자 권한 확인 (캐니스터 컨트롤러)
 fn is_admin(user: Principal) -> bool {
-    // TODO: 관리자 목록을 저장하고 확인하는 로직 구현
-    // 현재는 모든 사용자를 관리자로 취급하지 않음
-    false
+    // TODO: 컨트롤러 외 별도 관리자 목록 지원
+    ic_cdk::api::is_controller(&user)
 }
 
 /// 판매글 강제 삭제 (관리자용)
-pub fn admin_delete_listing_service(listing_id: u64) -> Result<SuccessResponse, String> {
+pub fn admin_delete_listing_service(listing_id: u64) -> Result<SuccessResponse, BackendError> {
     let user = caller();
 
     if !is_admin(user) {
-        return Err("관리자 권한이 필요합니다".to_string());
+        return Err(BackendError::unauthorized("admin_delete_listing"));
     }
 
     // 강제 삭제 (소유자 확인 없음)
-    if let Some(mut listing) = get_listing_readonly(listing_id) {
-        listing.status = ListingStatus::Suspended;
-        listing.updated_at = ic_cdk::api::time();
+    set_listing_status(listing_id, ListingStatus::Suspended)?;
+
+    log_activity(
+        ActivityType::ListingDeleted,
+        user,
+        Some(listing_id),
+        "관리자에 의한 강제 삭제".to_string(),
+    );
+    crate::storage::record_audit(
+        user,
+        AuditAction::AdminAction,
+        format!("admin_delete_listing listing_id={}", listing_id),
+    );
 
-        log_activity(
-            ActivityType::ListingDeleted,
-            user,
-            Some(listing_id),
-            "관리자에 의한 강제 삭제".to_string(),
-        );
+    Ok(SuccessResponse {
+        message: "판매글이 관리자에 의해 삭제되었습니다".to_string(),
+    })
+}
 
-        Ok(SuccessResponse {
-            message: "판매글이 관리자에 의해 삭제되었습니다".to_string(),
-        })
-    } else {
-        Err("판매글을 찾을 수 없습니다".to_string())
+/// 활동 로그 저장 현황 조회 (관리자용)
+pub fn get_activity_log_status_service() -> Result<ActivityLogStatus, BackendError> {
+    if !is_admin(caller()) {
+        return Err(BackendError::unauthorized("get_activity_log_status"));
     }
+
+    Ok(get_activity_log_status())
+}
+
+/// 활동 로그 보존 정책 변경 (관리자용)
+pub fn set_activity_retention_policy_service(
+    policy: ActivityRetentionPolicy,
+) -> Result<SuccessResponse, BackendError> {
+    if !is_admin(caller()) {
+        return Err(BackendError::unauthorized("set_activity_retention_policy"));
+    }
+
+    let details = format!(
+        "set_activity_retention_policy max_entries={}, max_age_days={}",
+        policy.max_entries, policy.max_age_days
+    );
+    set_activity_retention_policy(policy)?;
+    crate::storage::record_audit(caller(), AuditAction::AdminAction, details);
+
+    Ok(SuccessResponse {
+        message: "활동 로그 보존 정책이 변경되었습니다".to_string(),
+    })
+}
+
+/// 보존 정책에 따라 활동 로그 즉시 아카이브 (관리자용)
+pub fn archive_activities_service() -> Result<u64, BackendError> {
+    if !is_admin(caller()) {
+        return Err(BackendError::unauthorized("archive_activities"));
+    }
+
+    let archived = archive_activities(ic_cdk::api::time());
+    crate::storage::record_audit(
+        caller(),
+        AuditAction::AdminAction,
+        format!("archive_activities archived={}", archived),
+    );
+
+    Ok(archived)
+}
+
+/// 활동 로그 내보내기 (관리자용)
+pub fn export_activities_service(
+    format: ExportFormat,
+    from_id: Option<u64>,
+    limit: Option<u64>,
+) -> Result<ActivityExport, BackendError> {
+    if !is_admin(caller()) {
+        return Err(BackendError::unauthorized("export_activities"));
+    }
+
+    let limit = limit.unwrap_or(500).clamp(1, 2_000);
+    export_activities(format, from_id, limit)
 }
 
 // =====================
 // 7) 추천 시스템 기능
 // =====================
 
-/// 사용자 맞춤 추천 판매글 (개선된 검색 활용)
-pub fn get_recommended_listings_service(user: Principal, limit: u64) -> Vec<ListingSummary> {
-    // 간단한 추천 알고리즘: 사용자의 즐겨찾기 기반
+/// 사용자 맞춤 추천 판매글
+///
+/// 협업 필터링 모델 결과를 우선하고, 부족하면 즐겨찾기 키워드 기반 추천과
+/// 인기 판매글 순으로 채운다.
+pub fn get_recommended_listings_service(user: Principal, limit: u64) -> Vec<Recommendation> {
+    let limit = limit.min(50) as usize;
+    let mut recommendations = get_collaborative_recommendations(user, limit);
+
+    if recommendations.len() < limit {
+        let content_based = get_content_based_recommendations(user, limit as u64);
+        extend_recommendations(
+            &mut recommendations,
+            content_based.into_iter().map(|listing| {
+                to_recommendation(listing, 0.0, RecommendationReason::SimilarContent, None)
+            }),
+            user,
+            limit,
+        );
+    }
+
+    if recommendations.len() < limit {
+        extend_recommendations(
+            &mut recommendations,
+            update_trending_listings().into_iter().map(|listing| {
+                let score = (listing.view_count + listing.favorite_count * 2) as f64;
+                to_recommendation(listing, score, RecommendationReason::Trending, None)
+            }),
+            user,
+            limit,
+        );
+    }
+
+    recommendations
+}
+
+/// 유사한 판매글 찾기
+///
+/// "이 판매글을 즐겨찾기한 사용자들이 함께 즐겨찾기한 판매글"을 우선하고,
+/// 부족하면 키워드/카테고리 기반 유사 판매글로 채운다.
+pub fn get_similar_listings_service(listing_id: u64, limit: u64) -> Vec<Recommendation> {
+    let limit = limit.min(50) as usize;
+    let Some(source) = get_listing_readonly(listing_id) else {
+        return Vec::new();
+    };
+
+    let mut recommendations = get_cooccurring_listings(listing_id, limit);
+
+    if recommendations.len() < limit {
+        let content_similar = get_content_similar_listings(listing_id, limit as u64);
+        extend_recommendations(
+            &mut recommendations,
+            content_similar.into_iter().map(|listing| {
+                to_recommendation(
+                    listing,
+                    0.0,
+                    RecommendationReason::SimilarContent,
+                    Some((listing_id, &source.title)),
+                )
+            }),
+            source.seller,
+            limit,
+        );
+    }
+
+    recommendations
+}
+
+/// 중복과 본인 판매글을 제외하고 추천 목록을 limit까지 채움
+fn extend_recommendations(
+    recommendations: &mut Vec<Recommendation>,
+    candidates: impl Iterator<Item = Recommendation>,
+    exclude_seller: Principal,
+    limit: usize,
+) {
+    for candidate in candidates {
+        if recommendations.len() >= limit {
+            break;
+        }
+        let duplicate = recommendations
+            .iter()
+            .any(|existing| existing.listing.id == candidate.listing.id);
+        if !duplicate && candidate.listing.seller != exclude_seller {
+            recommendations.push(candidate);
+        }
+    }
+}
+
+/// 추천 모델 재계산 (한 단계씩 진행하고, 남았으면 다음 메시지에서 이어서 실행)
+pub fn refresh_recommendation_model_service() {
+    match refresh_recommendation_model_step(ic_cdk::api::time()) {
+        Some(item_count) => {
+            ic_cdk::println!("Recommendation model refreshed: {} listings", item_count)
+        }
+        None => {
+            ic_cdk_timers::set_timer(
+                std::time::Duration::ZERO,
+                refresh_recommendation_model_service,
+            );
+        }
+    }
+}
+
+/// 즐겨찾기 키워드 기반 추천 (협업 필터링 결과가 부족할 때 보충용)
+fn get_content_based_recommendations(user: Principal, limit: u64) -> Vec<ListingSummary> {
     let user_favorites = get_user_favorites(user);
 
     if user_favorites.is_empty() {
-        // 즐겨찾기가 없으면 인기 판매글 반환
-        return update_trending_listings();
+        return Vec::new();
     }
 
     // 사용자가 즐겨찾기한 판매글들의 키워드 분석
@@ -473,7 +928,7 @@ pub fn get_recommended_listings_service(user: Principal, limit: u64) -> Vec<List
         .collect();
 
     if top_keywords.is_empty() {
-        return update_trending_listings();
+        return Vec::new();
     }
 
     // 키워드 기반 검색 실행
@@ -489,6 +944,8 @@ pub fn get_recommended_listings_service(user: Principal, limit: u64) -> Vec<List
         sort_by: Some(SortBy::ViewCountDesc), // 인기도 순
         page: Some(0),
         page_size: Some(limit),
+        cursor: None,
+        min_quality: None,
     };
 
     let search_result = search_listings(&search_request);
@@ -502,8 +959,8 @@ pub fn get_recommended_listings_service(user: Principal, limit: u64) -> Vec<List
         .collect()
 }
 
-/// 유사한 판매글 찾기 (개선된 검색 활용)
-pub fn get_similar_listings_service(listing_id: u64, limit: u64) -> Vec<ListingSummary> {
+/// 키워드/카테고리 기반 유사 판매글 (협업 필터링 결과가 부족할 때 보충용)
+fn get_content_similar_listings(listing_id: u64, limit: u64) -> Vec<ListingSummary> {
     let target_listing = match get_listing_readonly(listing_id) {
         Some(listing) => listing,
         None => return Vec::new(),
@@ -538,6 +995,8 @@ pub fn get_similar_listings_service(listing_id: u64, limit: u64) -> Vec<ListingS
         sort_by: None, // 관련성 점수 순으로 정렬됨
         page: Some(0),
         page_size: Some(limit + 1), // 자기 자신 제외를 위해 +1
+        cursor: None,
+        min_quality: None,
     };
 
     let search_result = search_listings(&search_request);
@@ -574,7 +1033,7 @@ pub fn update_trending_listings() -> Vec<ListingSummary> {
 /// 검색 기반 트렌딩 판매글
 pub fn get_trending_by_search_service() -> Vec<ListingSummary> {
     // 인기 키워드를 기반으로 트렌딩 판매글 찾기
-    let trending_keywords = get_trending_keywords(5);
+    let trending_keywords = get_trending_keywords_service(Some(5), None);
 
     if trending_keywords.is_empty() {
         return update_trending_listings();
@@ -597,6 +1056,8 @@ pub fn get_trending_by_search_service() -> Vec<ListingSummary> {
         sort_by: Some(SortBy::ViewCountDesc),
         page: Some(0),
         page_size: Some(10),
+        cursor: None,
+        min_quality: None,
     };
 
     let search_result = search_listings(&search_request);
@@ -629,6 +1090,8 @@ pub fn cleanup_inactive_listings_service() -> u64 {
                 tags: None,
                 preview_data: None,
                 status: Some(ListingStatus::Suspended),
+                follow_latest: None,
+                preview_options: None,
             };
 
             // 시스템에서 자동으로 업데이트 (권한 체크 우회)
@@ -647,3 +1110,118 @@ pub fn cleanup_inactive_listings_service() -> u64 {
 
     cleaned_count
 }
+
+/// 주기적 유지보수 타이머 시작 (init/post_upgrade에서 호출)
+///
+/// 추천 모델 재계산, 만료된 조회 기록 정리, 활동 로그 아카이브를 1시간마다 수행한다.
+pub fn start_maintenance_timers() {
+    let interval = std::time::Duration::from_secs(60 * 60); // 1시간
+
+    // 업그레이드 직후에도 모델이 비어 있지 않도록 즉시 한 번 계산
+    ic_cdk_timers::set_timer(std::time::Duration::ZERO, || {
+        refresh_recommendation_model_service();
+    });
+    ic_cdk_timers::set_timer_interval(interval, || {
+        refresh_recommendation_model_service();
+        prune_view_records(ic_cdk::api::time());
+        archive_activities(ic_cdk::api::time());
+    });
+}
+
+// =====================
+// 9) 저장된 검색 및 알림
+// =====================
+
+/// 검색 저장
+pub fn save_search_service(request: SaveSearchRequest) -> Result<u64, BackendError> {
+    request.validate()?;
+
+    let user = caller();
+
+    if user == Principal::anonymous() {
+        return Err(BackendError::unauthorized("save_search"));
+    }
+
+    save_search(user, request)
+}
+
+/// 내 저장된 검색 목록 조회
+pub fn get_my_saved_searches_service() -> Vec<SavedSearch> {
+    let user = caller();
+
+    if user == Principal::anonymous() {
+        return Vec::new();
+    }
+
+    get_saved_searches(user)
+}
+
+/// 저장된 검색 삭제
+pub fn delete_saved_search_service(saved_search_id: u64) -> Result<SuccessResponse, BackendError> {
+    let user = caller();
+
+    if user == Principal::anonymous() {
+        return Err(BackendError::unauthorized("delete_saved_search"));
+    }
+
+    delete_saved_search(user, saved_search_id)?;
+
+    Ok(SuccessResponse {
+        message: "저장된 검색이 삭제되었습니다".to_string(),
+    })
+}
+
+/// 내 알림 조회
+pub fn get_my_notifications_service(
+    unread_only: Option<bool>,
+    limit: Option<u64>,
+    cursor: Option<String>,
+) -> Result<NotificationPage, BackendError> {
+    let user = caller();
+    let limit = limit.unwrap_or(20).min(100);
+    let cursor = parse_cursor(cursor, PageCursor::NOTIFICATION_SCOPE)?;
+
+    if user == Principal::anonymous() {
+        return Ok(NotificationPage {
+            notifications: Vec::new(),
+            unread_count: 0,
+            next_cursor: None,
+        });
+    }
+
+    Ok(get_notifications(
+        user,
+        unread_only.unwrap_or(false),
+        limit,
+        cursor,
+    ))
+}
+
+/// 알림 읽음 처리
+pub fn mark_notifications_read_service(ids: Option<Vec<u64>>) -> Result<u64, BackendError> {
+    let user = caller();
+
+    if user == Principal::anonymous() {
+        return Err(BackendError::unauthorized("mark_notifications_read"));
+    }
+
+    Ok(mark_notifications_read(user, ids))
+}
+
+// =====================
+// 10) 조회수 집계
+// =====================
+
+/// 판매글 조회 기록 (클라이언트가 상세 화면 진입 시 호출)
+pub fn record_view_service(listing_id: u64) -> Result<bool, BackendError> {
+    record_view(caller(), listing_id, ic_cdk::api::time())
+}
+
+/// 판매글 조회수 통계 조회
+pub fn get_listing_view_stats_service(
+    listing_id: u64,
+    days: Option<u64>,
+) -> Option<ListingViewStats> {
+    let days = days.unwrap_or(30).clamp(1, 90);
+    get_listing_view_stats(listing_id, days, ic_cdk::api::time())
+}
@@ -1,12 +1,12 @@
 // backend/src/marketplace_storage.rs
 
+use crate::error::BackendError;
 use crate::marketplace_types::*;
 use candid::Principal;
 use ic_stable_structures::{
     memory_manager::{MemoryId, MemoryManager, VirtualMemory},
     DefaultMemoryImpl, StableBTreeMap, StableCell,
 };
-use sha2::{Digest, Sha256};
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 
@@ -17,20 +17,12 @@ type Memory = VirtualMemory<DefaultMemoryImpl>;
 // 0) 유틸리티 함수들
 // =====================
 
-/// 안전한 ID 생성 (타임스탬프 + 해시 기반)
-fn generate_unique_id(prefix: &str, additional_data: &str) -> u64 {
-    let timestamp = ic_cdk::api::time();
-    let mut hasher = Sha256::new();
-
-    hasher.update(prefix.as_bytes());
-    hasher.update(additional_data.as_bytes());
-    hasher.update(timestamp.to_le_bytes());
-
-    let hash = hasher.finalize();
-    let mut id_bytes = [0u8; 8];
-    id_bytes.copy_from_slice(&hash[0..8]);
-
-    u64::from_le_bytes(id_bytes)
+/// 다음 ID 발급 (단조 증가 카운터)
+fn next_counter_value(counter: &RefCell<StableCell<u64, Memory>>) -> u64 {
+    let mut counter = counter.borrow_mut();
+    let next_id = *counter.get() + 1;
+    counter.set(next_id).expect("카운터 업데이트 실패");
+    next_id
 }
 
 // 마켓플레이스 전용 메모리 관리자 (ID 50-99 사용)
@@ -56,6 +48,86 @@ fn get_listing_counter_memory() -> Memory {
     MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(53)))
 }
 
+fn get_search_term_counts_memory() -> Memory {
+    MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(55)))
+}
+
+fn get_saved_searches_memory() -> Memory {
+    MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(56)))
+}
+
+fn get_saved_search_counter_memory() -> Memory {
+    MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(57)))
+}
+
+fn get_notifications_memory() -> Memory {
+    MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(58)))
+}
+
+fn get_notification_counter_memory() -> Memory {
+    MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(59)))
+}
+
+fn get_recommendation_model_memory() -> Memory {
+    MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(60)))
+}
+
+fn get_recent_views_memory() -> Memory {
+    MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(61)))
+}
+
+fn get_daily_views_memory() -> Memory {
+    MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(62)))
+}
+
+fn get_user_favorites_memory() -> Memory {
+    MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(63)))
+}
+
+fn get_listing_favoriters_memory() -> Memory {
+    MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(64)))
+}
+
+fn get_activity_counter_memory() -> Memory {
+    MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(65)))
+}
+
+fn get_activities_memory() -> Memory {
+    MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(66)))
+}
+
+fn get_activity_retention_memory() -> Memory {
+    MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(67)))
+}
+
+fn get_archived_activities_memory() -> Memory {
+    MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(68)))
+}
+
+fn get_activities_by_listing_memory() -> Memory {
+    MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(69)))
+}
+
+fn get_activities_by_user_memory() -> Memory {
+    MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(70)))
+}
+
+fn get_search_quotas_memory() -> Memory {
+    MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(71)))
+}
+
+fn get_purchases_memory() -> Memory {
+    MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(72)))
+}
+
+fn get_data_listings_memory() -> Memory {
+    MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(73)))
+}
+
+fn get_following_listings_memory() -> Memory {
+    MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(74)))
+}
+
 // 저장소들 - RefCell<StableBTreeMap> 직접 초기화 방식 사용
 thread_local! {
     static LISTINGS: RefCell<StableBTreeMap<u64, Listing, Memory>> = RefCell::new(
@@ -64,19 +136,147 @@ thread_local! {
         )
     );
 
-    static FAVORITES: RefCell<StableBTreeMap<u64, Favorite, Memory>> = RefCell::new(
+    // 해시 ID 기반 이전 즐겨찾기 저장소 (init 시 USER_FAVORITES로 이전 후 비워짐)
+    static LEGACY_FAVORITES: RefCell<StableBTreeMap<u64, Favorite, Memory>> = RefCell::new(
         StableBTreeMap::init(
             MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(51)))
         )
     );
 
-    static ACTIVITY_LOGS: RefCell<StableBTreeMap<u64, ActivityLog, Memory>> = RefCell::new(
+    // 해시 ID 기반 이전 활동 로그 저장소 (init 시 ACTIVITY_LOGS로 이전 후 비워짐)
+    static LEGACY_ACTIVITY_LOGS: RefCell<StableBTreeMap<u64, ActivityLog, Memory>> = RefCell::new(
         StableBTreeMap::init(
             MARKETPLACE_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(52)))
         )
     );
+
+    // 검색 키워드별 시간 버킷 검색 횟수
+    static SEARCH_TERM_COUNTS: RefCell<StableBTreeMap<SearchTermKey, u32, Memory>> = RefCell::new(
+        StableBTreeMap::init(get_search_term_counts_memory())
+    );
+
+    static SAVED_SEARCHES: RefCell<StableBTreeMap<u64, SavedSearch, Memory>> = RefCell::new(
+        StableBTreeMap::init(get_saved_searches_memory())
+    );
+
+    // (수신자, 알림 ID) -> 알림 (사용자별 범위 조회용)
+    static NOTIFICATIONS: RefCell<StableBTreeMap<(Principal, u64), Notification, Memory>> = RefCell::new(
+        StableBTreeMap::init(get_notifications_memory())
+    );
+
+    static SAVED_SEARCH_COUNTER: RefCell<StableCell<u64, Memory>> = RefCell::new(
+        StableCell::init(get_saved_search_counter_memory(), 0)
+            .expect("저장된 검색 카운터 초기화 실패")
+    );
+
+    static NOTIFICATION_COUNTER: RefCell<StableCell<u64, Memory>> = RefCell::new(
+        StableCell::init(get_notification_counter_memory(), 0)
+            .expect("알림 카운터 초기화 실패")
+    );
+
+    // 판매글 ID -> 함께 상호작용된 판매글 (타이머로 주기적 재계산)
+    static RECOMMENDATION_MODEL: RefCell<StableBTreeMap<u64, SimilarListings, Memory>> = RefCell::new(
+        StableBTreeMap::init(get_recommendation_model_memory())
+    );
+
+    // (조회자, 판매글 ID) -> 마지막으로 집계된 조회 시각 (중복 조회 방지)
+    static RECENT_VIEWS: RefCell<StableBTreeMap<(Principal, u64), u64, Memory>> = RefCell::new(
+        StableBTreeMap::init(get_recent_views_memory())
+    );
+
+    // (판매글 ID, 일 번호) -> 해당 일 조회수
+    static DAILY_VIEWS: RefCell<StableBTreeMap<(u64, u64), u64, Memory>> = RefCell::new(
+        StableBTreeMap::init(get_daily_views_memory())
+    );
+
+    // (사용자, 판매글 ID) -> 즐겨찾기 시각
+    static USER_FAVORITES: RefCell<StableBTreeMap<(Principal, u64), u64, Memory>> = RefCell::new(
+        StableBTreeMap::init(get_user_favorites_memory())
+    );
+
+    // (판매글 ID, 사용자) -> 즐겨찾기 시각 (판매글별 조회용 보조 인덱스)
+    static LISTING_FAVORITERS: RefCell<StableBTreeMap<(u64, Principal), u64, Memory>> = RefCell::new(
+        StableBTreeMap::init(get_listing_favoriters_memory())
+    );
+
+    // 활동 순번 -> 활동 로그 (순번은 기록 시각 순으로 증가)
+    static ACTIVITY_LOGS: RefCell<StableBTreeMap<u64, ActivityLog, Memory>> = RefCell::new(
+        StableBTreeMap::init(get_activities_memory())
+    );
+
+    static ACTIVITY_COUNTER: RefCell<StableCell<u64, Memory>> = RefCell::new(
+        StableCell::init(get_activity_counter_memory(), 0)
+            .expect("활동 로그 카운터 초기화 실패")
+    );
+
+    static ACTIVITY_RETENTION: RefCell<StableCell<ActivityRetentionPolicy, Memory>> = RefCell::new(
+        StableCell::init(get_activity_retention_memory(), ActivityRetentionPolicy::default())
+            .expect("활동 로그 보존 정책 초기화 실패")
+    );
+
+    // 보존 정책을 넘어선 활동 로그 (순번 유지)
+    static ARCHIVED_ACTIVITY_LOGS: RefCell<StableBTreeMap<u64, ActivityLog, Memory>> = RefCell::new(
+        StableBTreeMap::init(get_archived_activities_memory())
+    );
+
+    // (판매글 ID, 활동 순번), (사용자, 활동 순번) 인덱스 (활성 로그만 대상)
+    static ACTIVITIES_BY_LISTING: RefCell<StableBTreeMap<(u64, u64), (), Memory>> = RefCell::new(
+        StableBTreeMap::init(get_activities_by_listing_memory())
+    );
+
+    static ACTIVITIES_BY_USER: RefCell<StableBTreeMap<(Principal, u64), (), Memory>> = RefCell::new(
+        StableBTreeMap::init(get_activities_by_user_memory())
+    );
+
+    // 사용자 -> (집계 구간 시작, 구간 내 기록한 검색어 수)
+    static SEARCH_QUOTAS: RefCell<StableBTreeMap<Principal, (u64, u32), Memory>> = RefCell::new(
+        StableBTreeMap::init(get_search_quotas_memory())
+    );
+
+    // (구매자, 판매글 ID) -> 구매 기록
+    static PURCHASES: RefCell<StableBTreeMap<(Principal, u64), Purchase, Memory>> = RefCell::new(
+        StableBTreeMap::init(get_purchases_memory())
+    );
+
+    // (데이터 ID, 판매글 ID) 인덱스 (삭제된 판매글도 구매 기록 확인용으로 유지)
+    static DATA_LISTINGS: RefCell<StableBTreeMap<(u64, u64), (), Memory>> = RefCell::new(
+        StableBTreeMap::init(get_data_listings_memory())
+    );
+
+    // (버전 계보 ID, 판매글 ID) 인덱스 (최신 버전을 따르는 판매글만)
+    static FOLLOWING_LISTINGS: RefCell<StableBTreeMap<(u64, u64), (), Memory>> = RefCell::new(
+        StableBTreeMap::init(get_following_listings_memory())
+    );
+
+    // 진행 중인 추천 모델 재계산 (힙에만 보관, 업그레이드 시 처음부터 다시 시작)
+    static MODEL_REBUILD: RefCell<Option<ModelRebuild>> = const { RefCell::new(None) };
 }
 
+// 저장된 검색 및 알림 설정
+const MAX_SAVED_SEARCHES_PER_USER: usize = 20;
+const MAX_NOTIFICATIONS_PER_USER: usize = 200;
+
+// 추천 모델 설정
+const MAX_ITEMS_PER_USER: usize = 100; // 사용자당 모델에 반영할 최대 판매글 수
+const MAX_NEIGHBORS_PER_ITEM: usize = 20; // 판매글당 저장할 유사 판매글 수
+const MAX_REBUILD_BATCH: usize = 5_000; // 재계산 한 단계에서 읽을 최대 항목 수
+
+// 조회수 집계 설정
+const VIEW_DEDUPE_WINDOW_NANOS: u64 = 30 * 60 * 1_000_000_000; // 같은 사용자의 재조회 무시 구간 (30분)
+const DAY_NANOS: u64 = 24 * 60 * 60 * 1_000_000_000;
+const DAILY_VIEW_RETENTION_DAYS: u64 = 90;
+
+// 활동 로그 설정
+const MAX_ARCHIVE_BATCH: usize = 5_000; // 한 번에 아카이브할 최대 활동 수
+
+// 검색어 통계 설정
+const SEARCH_BUCKET_NANOS: u64 = 10 * 60 * 1_000_000_000; // 10분 단위 버킷
+const SEARCH_RETENTION_NANOS: u64 = 7 * 24 * 60 * 60 * 1_000_000_000; // 7일 보관
+const MAX_TERMS_PER_BUCKET: usize = 200; // 버킷당 최대 키워드 수
+const MAX_SEARCH_QUERY_LEN: usize = 200; // 기록할 검색어 최대 길이
+const SEARCH_QUOTA_WINDOW_NANOS: u64 = 60 * 60 * 1_000_000_000; // 사용자별 기록 한도 구간 (1시간)
+const MAX_SEARCH_QUERIES_PER_WINDOW: u32 = 60; // 구간당 사용자별 최대 기록 검색어 수
+
 // 카운터는 별도로 초기화
 thread_local! {
     static LISTING_COUNTER: RefCell<Option<StableCell<u64, Memory>>> = const { RefCell::new(None) };
@@ -136,6 +336,26 @@ pub fn init_marketplace_storage() {
         }
     });
 
+    let migrated = migrate_legacy_favorites();
+    if migrated > 0 {
+        ic_cdk::println!("Migrated {} legacy favorites", migrated);
+    }
+
+    let indexed = rebuild_activity_indexes();
+    if indexed > 0 {
+        ic_cdk::println!("Indexed {} activity logs", indexed);
+    }
+
+    let migrated = migrate_legacy_activities();
+    if migrated > 0 {
+        ic_cdk::println!("Migrated {} legacy activity logs", migrated);
+    }
+
+    let indexed = rebuild_data_listing_index();
+    if indexed > 0 {
+        ic_cdk::println!("Indexed data of {} listings", indexed);
+    }
+
     ic_cdk::println!("Marketplace storage initialization completed");
 }
 
@@ -335,6 +555,16 @@ fn apply_basic_filters(listing: &Listing, request: &SearchListingsRequest) -> bo
         }
     }
 
+    // 품질 점수 필터 (점수가 없는 판매글은 제외)
+    if let Some(min_quality) = request.min_quality {
+        if listing
+            .quality_score
+            .is_none_or(|score| score < min_quality)
+        {
+            return false;
+        }
+    }
+
     // 통화 필터
     if let Some(ref currency) = request.currency {
         if &listing.currency != currency {
@@ -370,65 +600,132 @@ pub fn smart_search_listings(request: &SearchListingsRequest) -> SearchResult {
     });
 
     // 1단계: 기본 필터 적용 (가격, 카테고리, 상태 등)
-    let mut filtered_listings: Vec<Listing> = all_listings
+    let filtered_listings: Vec<Listing> = all_listings
         .into_iter()
         .filter(|listing| apply_basic_filters(listing, request))
         .collect();
 
-    // 2단계: 검색어가 있으면 관련성 점수 계산
-    if let Some(ref query) = request.query {
-        let query_tokens = tokenize_query(query);
-
-        if !query_tokens.is_empty() {
-            // 관련성 점수와 함께 저장
-            let mut scored_listings: Vec<(Listing, f64)> = filtered_listings
-                .into_iter()
-                .map(|listing| {
-                    let score = calculate_relevance_score(&listing, &query_tokens);
-                    (listing, score)
-                })
-                .filter(|(_, score)| *score > 0.0) // 관련성 있는 것만
-                .collect();
-
-            // 관련성 점수순 정렬
-            scored_listings
-                .sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    // 2단계: 정렬 키 계산 (검색어가 있으면 관련성 점수, 없으면 정렬 기준)
+    let query_tokens = request
+        .query
+        .as_deref()
+        .map(tokenize_query)
+        .unwrap_or_default();
 
-            filtered_listings = scored_listings
-                .into_iter()
-                .map(|(listing, _)| listing)
-                .collect();
-        }
+    let scope = request.cursor_scope();
+    let (keyed, descending): (Vec<(PageCursor, Listing)>, bool) = if !query_tokens.is_empty() {
+        let keyed = filtered_listings
+            .into_iter()
+            .filter_map(|listing| {
+                let score = calculate_relevance_score(&listing, &query_tokens);
+                // 관련성 있는 것만 (양수 f64는 비트 순서가 크기 순서와 같음)
+                (score > 0.0).then(|| {
+                    let cursor = PageCursor {
+                        key: score.to_bits(),
+                        id: listing.id,
+                        scope,
+                    };
+                    (cursor, listing)
+                })
+            })
+            .collect();
+        (keyed, true)
     } else {
-        // 검색어가 없으면 기본 정렬
-        sort_listings(&mut filtered_listings, &request.get_sort_by());
-    }
+        let sort_by = request.get_sort_by();
+        let keyed = filtered_listings
+            .into_iter()
+            .map(|listing| {
+                let cursor = PageCursor {
+                    key: listing_sort_key(&listing, &sort_by),
+                    id: listing.id,
+                    scope,
+                };
+                (cursor, listing)
+            })
+            .collect();
+        (keyed, is_descending(&sort_by))
+    };
 
-    // 3단계: 페이징 적용
-    let total_count = filtered_listings.len() as u64;
+    // 3단계: 페이징 적용 (커서가 있으면 커서 이후부터, 없으면 페이지 번호 기준)
+    let total_count = keyed.len() as u64;
     let page = request.get_page();
     let page_size = request.get_page_size();
     let total_pages = total_count.div_ceil(page_size);
 
-    let start = (page * page_size) as usize;
-    let end = ((page + 1) * page_size).min(total_count) as usize;
-
-    let listings = if start < filtered_listings.len() {
-        filtered_listings[start..end.min(filtered_listings.len())]
-            .iter()
-            .map(|listing| to_listing_summary(listing.clone()))
-            .collect()
+    // 다른 정렬 기준/검색 조건의 커서는 무시 (외부 요청은 validate에서 거부됨)
+    let cursor = request
+        .cursor
+        .as_deref()
+        .and_then(|cursor| PageCursor::decode_scoped(cursor, scope).ok());
+    let skip = if cursor.is_some() {
+        0
     } else {
-        Vec::new()
+        (page * page_size) as usize
     };
 
+    let (listings, next_cursor) = paginate(keyed, cursor, descending, skip, page_size as usize);
+
     SearchResult {
-        listings,
+        listings: listings.into_iter().map(to_listing_summary).collect(),
         total_count,
         page,
         page_size,
         total_pages,
+        next_cursor,
+    }
+}
+
+/// 정렬 기준에 해당하는 판매글의 정렬 키
+fn listing_sort_key(listing: &Listing, sort_by: &SortBy) -> u64 {
+    match sort_by {
+        SortBy::CreatedAtDesc | SortBy::CreatedAtAsc => listing.created_at,
+        SortBy::PriceDesc | SortBy::PriceAsc => listing.price,
+        SortBy::ViewCountDesc => listing.view_count,
+        SortBy::FavoriteCountDesc => listing.favorite_count,
+        SortBy::UpdatedAtDesc => listing.updated_at,
+    }
+}
+
+/// 내림차순 정렬 기준인지 확인
+fn is_descending(sort_by: &SortBy) -> bool {
+    !matches!(sort_by, SortBy::CreatedAtAsc | SortBy::PriceAsc)
+}
+
+/// (커서, 항목) 목록을 정렬하고 커서 이후의 한 페이지와 다음 커서를 반환
+///
+/// 오프셋 대신 정렬 키 + ID를 기준으로 자르므로 페이지 사이에 항목이
+/// 추가되거나 삭제되어도 결과가 밀리거나 중복되지 않는다.
+/// 조회수/즐겨찾기 수처럼 바뀌는 정렬 키는 커서 이후 값이 바뀐 항목만
+/// 위치가 달라질 수 있다 (커서 자체는 발급한 목록의 범위로 묶임).
+fn paginate<T>(
+    mut keyed: Vec<(PageCursor, T)>,
+    cursor: Option<PageCursor>,
+    descending: bool,
+    skip: usize,
+    limit: usize,
+) -> (Vec<T>, Option<String>) {
+    if let Some(cursor) = cursor {
+        keyed.retain(|(key, _)| cursor.precedes(key, descending));
     }
+
+    if descending {
+        keyed.sort_by_key(|(key, _)| std::cmp::Reverse(*key));
+    } else {
+        keyed.sort_by_key(|(key, _)| *key);
+    }
+
+    let has_more = keyed.len() > skip.saturating_add(limit);
+    let page: Vec<(PageCursor, T)> = keyed.into_iter().skip(skip).take(limit).collect();
+    let next_cursor = if has_more {
+        page.last().map(|(key, _)| key.encode())
+    } else {
+        None
+    };
+
+    (
+        page.into_iter().map(|(_, item)| item).collect(),
+        next_cursor,
+    )
 }
 
 // =====================
@@ -436,7 +733,13 @@ pub fn smart_search_listings(request: &SearchListingsRequest) -> SearchResult {
 // =====================
 
 /// 판매글 생성
-pub fn create_listing(request: CreateListingRequest, seller: Principal) -> Result<u64, String> {
+/// data_ids는 dataset_ids의 레코드 ID를 펼친 목록
+pub fn create_listing(
+    request: CreateListingRequest,
+    data_ids: Vec<u64>,
+    preview: crate::types::DataPreview,
+    seller: Principal,
+) -> Result<u64, BackendError> {
     // 판매글 ID 생성
     let listing_id = with_listing_counter(|counter| {
         let current = *counter.get();
@@ -461,10 +764,15 @@ pub fn create_listing(request: CreateListingRequest, seller: Principal) -> Resul
         description: request.description,
         price: request.price,
         currency: request.currency,
-        data_ids: request.data_ids,
+        data_ids,
+        dataset_ids: request.dataset_ids.clone(),
+        follow_latest: request.follow_latest.unwrap_or(false),
+        quality_score: crate::storage::datasets_quality_score(&request.dataset_ids),
         category: request.category,
         tags: request.tags,
         preview_data: request.preview_data,
+        preview: Some(preview),
+        preview_options: request.preview_options,
         status: ListingStatus::Active,
         created_at: now,
         updated_at: now,
@@ -473,9 +781,10 @@ pub fn create_listing(request: CreateListingRequest, seller: Principal) -> Resul
     };
 
     // 판매글 저장
-    LISTINGS.with(|storage| {
-        storage.borrow_mut().insert(listing_id, listing);
-    });
+    save_listing(&listing);
+
+    // 저장된 검색과 일치하면 알림 생성
+    notify_saved_search_matches(&listing, None);
 
     // 활동 로그 기록
     log_activity(
@@ -488,41 +797,195 @@ pub fn create_listing(request: CreateListingRequest, seller: Principal) -> Resul
     Ok(listing_id)
 }
 
-/// 판매글 조회 (조회수 증가)
-pub fn get_listing(listing_id: u64) -> Option<Listing> {
-    let mut listing = LISTINGS.with(|storage| storage.borrow().get(&listing_id))?;
+/// 판매글 저장 (데이터/계보 인덱스와 인증 트리도 함께 갱신)
+fn save_listing(listing: &Listing) {
+    let previous =
+        LISTINGS.with(|storage| storage.borrow_mut().insert(listing.id, listing.clone()));
+    if let Some(previous) = &previous {
+        unindex_listing(previous);
+    }
+    index_listing(listing);
+    crate::certification::certify_listing(listing);
+}
 
-    // 조회수 증가
-    listing.view_count += 1;
+fn index_listing(listing: &Listing) {
+    DATA_LISTINGS.with(|index| {
+        let mut index = index.borrow_mut();
+        for &data_id in &listing.data_ids {
+            index.insert((data_id, listing.id), ());
+        }
+    });
+    FOLLOWING_LISTINGS.with(|index| {
+        let mut index = index.borrow_mut();
+        for lineage in followed_lineages(listing) {
+            index.insert((lineage, listing.id), ());
+        }
+    });
+}
+
+fn unindex_listing(listing: &Listing) {
+    DATA_LISTINGS.with(|index| {
+        let mut index = index.borrow_mut();
+        for &data_id in &listing.data_ids {
+            index.remove(&(data_id, listing.id));
+        }
+    });
+    FOLLOWING_LISTINGS.with(|index| {
+        let mut index = index.borrow_mut();
+        for lineage in followed_lineages(listing) {
+            index.remove(&(lineage, listing.id));
+        }
+    });
+}
+
+/// 최신 버전을 따르는 판매글이 포함한 데이터셋들의 버전 계보 ID
+fn followed_lineages(listing: &Listing) -> Vec<u64> {
+    if !listing.follow_latest {
+        return Vec::new();
+    }
+    listing
+        .dataset_ids
+        .iter()
+        .filter_map(|&dataset_id| crate::storage::get_dataset(dataset_id))
+        .map(|dataset| dataset.lineage())
+        .collect()
+}
+
+/// 인덱스가 비어 있으면 기존 판매글로 데이터/계보 인덱스 구성
+fn rebuild_data_listing_index() -> u64 {
+    let has_index = DATA_LISTINGS.with(|index| !index.borrow().is_empty());
+    if has_index {
+        return 0;
+    }
+
+    let listings: Vec<Listing> =
+        LISTINGS.with(|storage| storage.borrow().iter().map(|(_, l)| l).collect());
+    for listing in &listings {
+        index_listing(listing);
+    }
+
+    listings.len() as u64
+}
+
+/// 판매글이 제공하는 데이터셋들의 레코드 ID
+fn resolved_data_ids(listing: &Listing) -> Vec<u64> {
+    resolved_dataset_ids(listing)
+        .into_iter()
+        .filter_map(crate::storage::get_dataset)
+        .flat_map(|dataset| dataset.record_ids)
+        .collect()
+}
+
+/// 제공 데이터셋이 바뀐 판매글의 데이터 ID, 품질 점수, 미리보기를 다시 계산
+///
+/// 민팅 확인, 데이터 접근 확인, 검색 필터, HTTP 미리보기가 모두 저장된 값을 쓰므로
+/// 최신 버전이 바뀌는 시점에 맞춘다. 저장된 옵션이 새 버전과 맞지 않으면
+/// (공개/마스킹 컬럼이 사라진 경우) 이전 버전 미리보기를 남기지 않고 비운다.
+fn refresh_listing_datasets(listing: &mut Listing) {
+    let dataset_ids = resolved_dataset_ids(listing);
+    listing.data_ids = resolved_data_ids(listing);
+    listing.quality_score = crate::storage::datasets_quality_score(&dataset_ids);
+    listing.preview = crate::preview::preview_datasets(
+        &dataset_ids,
+        &listing.preview_options.clone().unwrap_or_default(),
+    )
+    .ok();
+}
+
+/// 새 버전이 게시된 계보를 따르는 판매글을 최신 버전 기준으로 갱신
+///
+/// 새 버전이 판매글 생성과 같은 검증(소유권, 민팅 여부, 유사 데이터셋)을 통과하지 못하면
+/// 최신 버전 따르기를 끄고 처음 등록한 버전으로 되돌린 뒤, 판매 중이면 판매를 중단한다.
+pub fn refresh_following_listings(lineage_id: u64) -> u64 {
+    let listing_ids: Vec<u64> = FOLLOWING_LISTINGS.with(|index| {
+        index
+            .borrow()
+            .range((lineage_id, 0)..=(lineage_id, u64::MAX))
+            .map(|((_, listing_id), _)| listing_id)
+            .collect()
+    });
+
+    let mut refreshed = 0;
+    for listing_id in listing_ids {
+        let Some(mut listing) = get_listing_readonly(listing_id) else {
+            continue;
+        };
+        if let Err(error) = crate::marketplace::validate_listing_datasets(
+            &resolved_dataset_ids(&listing),
+            listing.seller,
+        ) {
+            suspend_following_listing(&mut listing, &error);
+            continue;
+        }
+        refresh_listing_datasets(&mut listing);
+        save_listing(&listing);
+        refreshed += 1;
+    }
+    refreshed
+}
+
+/// 새 버전 검증에 실패한 판매글을 처음 등록한 버전으로 되돌리고 판매 중단
+fn suspend_following_listing(listing: &mut Listing, error: &BackendError) {
+    listing.follow_latest = false;
+    if listing.status == ListingStatus::Active {
+        listing.status = ListingStatus::Suspended;
+    }
+    refresh_listing_datasets(listing);
     listing.updated_at = ic_cdk::api::time();
+    save_listing(listing);
+
+    crate::storage::record_audit(
+        listing.seller,
+        crate::types::AuditAction::ListingUpdated,
+        format!(
+            "listing_id={} suspended: new version rejected ({})",
+            listing.id,
+            error.code()
+        ),
+    );
+}
+
+/// 데이터를 포함한 판매글 ID들
+pub fn listings_containing(data_id: u64) -> Vec<u64> {
+    DATA_LISTINGS.with(|index| {
+        index
+            .borrow()
+            .range((data_id, 0)..=(data_id, u64::MAX))
+            .map(|((_, listing_id), _)| listing_id)
+            .collect()
+    })
+}
 
-    // 업데이트된 정보 저장
+/// 판매글 전체를 인증 트리에 등록
+pub fn certify_all_listings(tree: &mut crate::certification::CertifiedTree) {
     LISTINGS.with(|storage| {
-        storage.borrow_mut().insert(listing_id, listing.clone());
+        for (_, listing) in storage.borrow().iter() {
+            crate::certification::insert_listing(tree, &listing);
+        }
     });
-
-    Some(listing)
 }
 
-/// 판매글 조회 (조회수 증가 없음)
+/// 판매글 조회 (조회수는 record_view로 별도 집계)
 pub fn get_listing_readonly(listing_id: u64) -> Option<Listing> {
     LISTINGS.with(|storage| storage.borrow().get(&listing_id))
 }
 
 /// 판매글 업데이트
-pub fn update_listing(request: UpdateListingRequest, user: Principal) -> Result<(), String> {
+pub fn update_listing(request: UpdateListingRequest, user: Principal) -> Result<(), BackendError> {
     let mut listing = LISTINGS.with(|storage| {
         storage
             .borrow()
             .get(&request.listing_id)
-            .ok_or_else(|| "판매글을 찾을 수 없습니다".to_string())
+            .ok_or_else(|| BackendError::not_found("listing", request.listing_id))
     })?;
 
     // 권한 확인
     if listing.seller != user {
-        return Err("판매글을 수정할 권한이 없습니다".to_string());
+        return Err(BackendError::unauthorized("update_listing"));
     }
 
+    let previous = listing.clone();
+
     // 업데이트
     if let Some(title) = request.title {
         listing.title = title;
@@ -548,13 +1011,31 @@ pub fn update_listing(request: UpdateListingRequest, user: Principal) -> Result<
     if let Some(status) = request.status {
         listing.status = status;
     }
+    if let Some(follow_latest) = request.follow_latest {
+        listing.follow_latest = follow_latest;
+        if follow_latest {
+            crate::marketplace::validate_listing_datasets(
+                &resolved_dataset_ids(&listing),
+                listing.seller,
+            )?;
+        }
+        refresh_listing_datasets(&mut listing);
+    }
+    if let Some(options) = request.preview_options {
+        listing.preview = Some(crate::preview::preview_datasets(
+            &resolved_dataset_ids(&listing),
+            &options,
+        )?);
+        listing.preview_options = Some(options);
+    }
 
     listing.updated_at = ic_cdk::api::time();
 
     // 저장
-    LISTINGS.with(|storage| {
-        storage.borrow_mut().insert(request.listing_id, listing);
-    });
+    save_listing(&listing);
+
+    // 수정으로 새로 일치하게 된 저장된 검색에 알림 생성
+    notify_saved_search_matches(&listing, Some(&previous));
 
     // 활동 로그 기록
     log_activity(
@@ -568,17 +1049,17 @@ pub fn update_listing(request: UpdateListingRequest, user: Principal) -> Result<
 }
 
 /// 판매글 삭제
-pub fn delete_listing(listing_id: u64, user: Principal) -> Result<(), String> {
+pub fn delete_listing(listing_id: u64, user: Principal) -> Result<(), BackendError> {
     let listing = LISTINGS.with(|storage| {
         storage
             .borrow()
             .get(&listing_id)
-            .ok_or_else(|| "판매글을 찾을 수 없습니다".to_string())
+            .ok_or_else(|| BackendError::not_found("listing", listing_id))
     })?;
 
     // 권한 확인
     if listing.seller != user {
-        return Err("판매글을 삭제할 권한이 없습니다".to_string());
+        return Err(BackendError::unauthorized("delete_listing"));
     }
 
     // 상태를 삭제됨으로 변경 (실제로는 삭제하지 않고 상태만 변경)
@@ -586,9 +1067,7 @@ pub fn delete_listing(listing_id: u64, user: Principal) -> Result<(), String> {
     updated_listing.status = ListingStatus::Deleted;
     updated_listing.updated_at = ic_cdk::api::time();
 
-    LISTINGS.with(|storage| {
-        storage.borrow_mut().insert(listing_id, updated_listing);
-    });
+    save_listing(&updated_listing);
 
     // 활동 로그 기록
     log_activity(
@@ -601,10 +1080,34 @@ pub fn delete_listing(listing_id: u64, user: Principal) -> Result<(), String> {
     Ok(())
 }
 
+/// 판매글 상태 변경 (관리자용, 소유자 확인 없음)
+pub fn set_listing_status(listing_id: u64, status: ListingStatus) -> Result<(), BackendError> {
+    let mut listing = LISTINGS
+        .with(|storage| storage.borrow().get(&listing_id))
+        .ok_or_else(|| BackendError::not_found("listing", listing_id))?;
+
+    listing.status = status;
+    listing.updated_at = ic_cdk::api::time();
+
+    save_listing(&listing);
+
+    Ok(())
+}
+
 /// 판매글 목록 조회 (기본)
 pub fn list_listings(status: Option<ListingStatus>, limit: Option<u64>) -> Vec<ListingSummary> {
-    LISTINGS.with(|storage| {
-        let mut listings: Vec<_> = storage
+    list_listings_page(status, limit.unwrap_or(u64::MAX), None).listings
+}
+
+/// 판매글 목록 조회 (최신순, 커서 기반)
+pub fn list_listings_page(
+    status: Option<ListingStatus>,
+    limit: u64,
+    cursor: Option<PageCursor>,
+) -> ListingPage {
+    let scope = listing_page_scope(status.as_ref());
+    let keyed: Vec<(PageCursor, Listing)> = LISTINGS.with(|storage| {
+        storage
             .borrow()
             .iter()
             .filter(|(_, listing)| {
@@ -614,36 +1117,71 @@ pub fn list_listings(status: Option<ListingStatus>, limit: Option<u64>) -> Vec<L
                     listing.status != ListingStatus::Deleted
                 }
             })
-            .map(|(_, listing)| to_listing_summary(listing))
-            .collect();
+            .map(|(id, listing)| {
+                let key = PageCursor {
+                    key: listing.created_at,
+                    id,
+                    scope,
+                };
+                (key, listing)
+            })
+            .collect()
+    });
 
-        // 최신순 정렬
-        listings.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    let (listings, next_cursor) = paginate(keyed, cursor, true, 0, limit as usize);
 
-        // 제한 적용
-        if let Some(limit) = limit {
-            listings.truncate(limit as usize);
-        }
+    ListingPage {
+        listings: listings.into_iter().map(to_listing_summary).collect(),
+        next_cursor,
+    }
+}
 
-        listings
-    })
+/// 최신순 판매글 목록의 커서 범위 (정렬 기준과 상태 필터 반영)
+pub fn listing_page_scope(status: Option<&ListingStatus>) -> u64 {
+    PageCursor::scope_of(&(SortBy::CreatedAtDesc, "status", status))
+}
+
+/// 판매자별 최신순 판매글 목록의 커서 범위 (정렬 기준과 판매자 반영)
+pub fn seller_listing_page_scope(seller: Principal) -> u64 {
+    PageCursor::scope_of(&(SortBy::CreatedAtDesc, "seller", seller))
 }
 
 /// 판매자별 판매글 조회
 pub fn get_listings_by_seller(seller: Principal) -> Vec<ListingSummary> {
-    LISTINGS.with(|storage| {
-        let mut listings: Vec<_> = storage
+    get_listings_by_seller_page(seller, u64::MAX, None).listings
+}
+
+/// 판매자별 판매글 조회 (최신순, 커서 기반)
+pub fn get_listings_by_seller_page(
+    seller: Principal,
+    limit: u64,
+    cursor: Option<PageCursor>,
+) -> ListingPage {
+    let scope = seller_listing_page_scope(seller);
+    let keyed: Vec<(PageCursor, Listing)> = LISTINGS.with(|storage| {
+        storage
             .borrow()
             .iter()
             .filter(|(_, listing)| {
                 listing.seller == seller && listing.status != ListingStatus::Deleted
             })
-            .map(|(_, listing)| to_listing_summary(listing))
-            .collect();
+            .map(|(id, listing)| {
+                let key = PageCursor {
+                    key: listing.created_at,
+                    id,
+                    scope,
+                };
+                (key, listing)
+            })
+            .collect()
+    });
 
-        listings.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-        listings
-    })
+    let (listings, next_cursor) = paginate(keyed, cursor, true, 0, limit as usize);
+
+    ListingPage {
+        listings: listings.into_iter().map(to_listing_summary).collect(),
+        next_cursor,
+    }
 }
 
 // =====================
@@ -655,32 +1193,6 @@ pub fn search_listings(request: &SearchListingsRequest) -> SearchResult {
     smart_search_listings(request)
 }
 
-fn sort_listings(listings: &mut Vec<Listing>, sort_by: &SortBy) {
-    match sort_by {
-        SortBy::CreatedAtDesc => {
-            listings.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-        }
-        SortBy::CreatedAtAsc => {
-            listings.sort_by(|a, b| a.created_at.cmp(&b.created_at));
-        }
-        SortBy::PriceDesc => {
-            listings.sort_by(|a, b| b.price.cmp(&a.price));
-        }
-        SortBy::PriceAsc => {
-            listings.sort_by(|a, b| a.price.cmp(&b.price));
-        }
-        SortBy::ViewCountDesc => {
-            listings.sort_by(|a, b| b.view_count.cmp(&a.view_count));
-        }
-        SortBy::FavoriteCountDesc => {
-            listings.sort_by(|a, b| b.favorite_count.cmp(&a.favorite_count));
-        }
-        SortBy::UpdatedAtDesc => {
-            listings.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
-        }
-    }
-}
-
 // =====================
 // 6) 자동완성 및 제안 기능
 // =====================
@@ -722,21 +1234,130 @@ pub fn get_search_suggestions(partial_query: &str, limit: usize) -> Vec<String>
     result
 }
 
-/// 인기 검색어 추출
-pub fn get_trending_keywords(limit: usize) -> Vec<(String, u32)> {
-    let mut keyword_counts: HashMap<String, u32> = HashMap::new();
-
-    LISTINGS.with(|storage| {
-        for (_, listing) in storage.borrow().iter() {
-            if listing.status == ListingStatus::Active {
-                // 제목에서 키워드 추출
-                let title_keywords = extract_keywords(&listing.title);
-                for keyword in title_keywords {
-                    if keyword.len() >= 2 {
-                        let count = keyword_counts.entry(keyword).or_insert(0);
-                        *count += listing.view_count as u32;
-                    }
-                }
+/// 검색어 기록 (정규화된 토큰 단위로 시간 버킷에 집계)
+///
+/// 버킷당 키워드 수와 보관 기간이 제한되어 있어 저장소가 무한히 커지지 않는다.
+/// 기록된 키워드가 하나라도 있으면 true를 반환한다.
+pub fn record_search_query(query: &str, now: u64) -> bool {
+    if query.len() > MAX_SEARCH_QUERY_LEN {
+        return false;
+    }
+
+    let terms: HashSet<String> = tokenize_query(query)
+        .into_iter()
+        .filter(|term| term.len() <= 50)
+        .collect();
+
+    if terms.is_empty() {
+        return false;
+    }
+
+    let bucket = now - now % SEARCH_BUCKET_NANOS;
+
+    SEARCH_TERM_COUNTS.with(|storage| {
+        let mut storage = storage.borrow_mut();
+
+        // 보관 기간이 지난 버킷 정리
+        let cutoff = now.saturating_sub(SEARCH_RETENTION_NANOS);
+        let expired: Vec<SearchTermKey> = storage
+            .keys_range(..SearchTermKey::bucket_start(cutoff))
+            .collect();
+        for key in expired {
+            storage.remove(&key);
+        }
+
+        let mut bucket_size = storage
+            .keys_range(
+                SearchTermKey::bucket_start(bucket)..SearchTermKey::bucket_start(bucket + 1),
+            )
+            .count();
+        let mut recorded = false;
+
+        for term in terms {
+            let key = SearchTermKey { bucket, term };
+            match storage.get(&key) {
+                Some(count) => {
+                    storage.insert(key, count.saturating_add(1));
+                }
+                None if bucket_size < MAX_TERMS_PER_BUCKET => {
+                    storage.insert(key, 1);
+                    bucket_size += 1;
+                }
+                None => continue,
+            }
+            recorded = true;
+        }
+
+        recorded
+    })
+}
+
+/// 사용자의 검색어 기록 한도에서 requested개를 차감하고 허용된 개수 반환
+///
+/// 한 사용자가 인기 검색어를 좌우하지 못하도록 1시간 구간마다 기록 수를 제한한다.
+/// 구간 한도를 이미 모두 썼으면 QuotaExceeded를 반환한다.
+pub fn take_search_quota(
+    user: Principal,
+    requested: usize,
+    now: u64,
+) -> Result<usize, BackendError> {
+    let window = now - now % SEARCH_QUOTA_WINDOW_NANOS;
+
+    SEARCH_QUOTAS.with(|quotas| {
+        let mut quotas = quotas.borrow_mut();
+        let used = match quotas.get(&user) {
+            Some((start, used)) if start == window => used,
+            _ => 0,
+        };
+
+        let remaining = MAX_SEARCH_QUERIES_PER_WINDOW.saturating_sub(used);
+        if remaining == 0 && requested > 0 {
+            return Err(BackendError::quota_exceeded(
+                "search_rate",
+                MAX_SEARCH_QUERIES_PER_WINDOW as u64,
+            ));
+        }
+
+        let allowed = requested.min(remaining as usize);
+        quotas.insert(user, (window, used + allowed as u32));
+        Ok(allowed)
+    })
+}
+
+/// 인기 검색어 추출 (실제 검색 기록 기반, 슬라이딩 윈도우)
+pub fn get_trending_keywords(window_nanos: u64, limit: usize, now: u64) -> Vec<(String, u32)> {
+    let since = now.saturating_sub(window_nanos);
+    let mut keyword_counts: HashMap<String, u32> = HashMap::new();
+
+    SEARCH_TERM_COUNTS.with(|storage| {
+        for (key, count) in storage.borrow().range(SearchTermKey::bucket_start(since)..) {
+            let total = keyword_counts.entry(key.term).or_insert(0);
+            *total = total.saturating_add(count);
+        }
+    });
+
+    let mut result: Vec<(String, u32)> = keyword_counts.into_iter().collect();
+    // 빈도수 내림차순, 같으면 사전순
+    result.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    result.truncate(limit);
+    result
+}
+
+/// 판매글 기반 인기 키워드 추출 (검색 기록이 없을 때의 대체 수단)
+pub fn get_listing_keywords_by_popularity(limit: usize) -> Vec<(String, u32)> {
+    let mut keyword_counts: HashMap<String, u32> = HashMap::new();
+
+    LISTINGS.with(|storage| {
+        for (_, listing) in storage.borrow().iter() {
+            if listing.status == ListingStatus::Active {
+                // 제목에서 키워드 추출
+                let title_keywords = extract_keywords(&listing.title);
+                for keyword in title_keywords {
+                    if keyword.len() >= 2 {
+                        let count = keyword_counts.entry(keyword).or_insert(0);
+                        *count += listing.view_count as u32;
+                    }
+                }
 
                 // 태그에서 키워드 추출
                 for tag in &listing.tags {
@@ -757,49 +1378,37 @@ pub fn get_trending_keywords(limit: usize) -> Vec<(String, u32)> {
 // 7) 즐겨찾기 관리
 // =====================
 
+/// 즐겨찾기 저장 (기본 키와 보조 인덱스를 함께 갱신)
+fn insert_favorite(user: Principal, listing_id: u64, created_at: u64) {
+    USER_FAVORITES.with(|storage| {
+        storage.borrow_mut().insert((user, listing_id), created_at);
+    });
+    LISTING_FAVORITERS.with(|index| {
+        index.borrow_mut().insert((listing_id, user), created_at);
+    });
+}
+
 /// 즐겨찾기 추가
-pub fn add_favorite(user: Principal, listing_id: u64) -> Result<(), String> {
+pub fn add_favorite(user: Principal, listing_id: u64) -> Result<(), BackendError> {
     // 판매글 존재 확인
     let mut listing = LISTINGS.with(|storage| {
         storage
             .borrow()
             .get(&listing_id)
-            .ok_or_else(|| "판매글을 찾을 수 없습니다".to_string())
+            .ok_or_else(|| BackendError::not_found("listing", listing_id))
     })?;
 
     // 이미 즐겨찾기에 추가되어 있는지 확인
-    let already_favorited = FAVORITES.with(|storage| {
-        storage
-            .borrow()
-            .iter()
-            .any(|(_, fav)| fav.user == user && fav.listing_id == listing_id)
-    });
-
-    if already_favorited {
-        return Err("이미 즐겨찾기에 추가된 판매글입니다".to_string());
+    if is_favorited(user, listing_id) {
+        return Err(BackendError::already_exists("favorite"));
     }
 
-    // 즐겨찾기 ID 생성 (해시 기반으로 고유성 보장)
-    let favorite_id = generate_unique_id("favorite", &format!("{}_{}", user.to_text(), listing_id));
-
-    let favorite = Favorite {
-        user,
-        listing_id,
-        created_at: ic_cdk::api::time(),
-    };
-
-    // 즐겨찾기 저장
-    FAVORITES.with(|storage| {
-        storage.borrow_mut().insert(favorite_id, favorite);
-    });
+    insert_favorite(user, listing_id, ic_cdk::api::time());
 
-    // 판매글의 즐겨찾기 수 증가
+    // 판매글의 즐겨찾기 수 증가 (판매자 수정이 아니므로 updated_at은 유지)
     listing.favorite_count += 1;
-    listing.updated_at = ic_cdk::api::time();
 
-    LISTINGS.with(|storage| {
-        storage.borrow_mut().insert(listing_id, listing);
-    });
+    save_listing(&listing);
 
     // 활동 로그 기록
     log_activity(
@@ -813,33 +1422,20 @@ pub fn add_favorite(user: Principal, listing_id: u64) -> Result<(), String> {
 }
 
 /// 즐겨찾기 제거
-pub fn remove_favorite(user: Principal, listing_id: u64) -> Result<(), String> {
-    // 즐겨찾기 찾기
-    let favorite_id = FAVORITES
-        .with(|storage| {
-            let storage = storage.borrow();
-            for (id, fav) in storage.iter() {
-                if fav.user == user && fav.listing_id == listing_id {
-                    return Some(id);
-                }
-            }
-            None
-        })
-        .ok_or_else(|| "즐겨찾기를 찾을 수 없습니다".to_string())?;
+pub fn remove_favorite(user: Principal, listing_id: u64) -> Result<(), BackendError> {
+    USER_FAVORITES
+        .with(|storage| storage.borrow_mut().remove(&(user, listing_id)))
+        .ok_or_else(|| BackendError::not_found("favorite", listing_id))?;
 
-    // 즐겨찾기 삭제
-    FAVORITES.with(|storage| {
-        storage.borrow_mut().remove(&favorite_id);
+    LISTING_FAVORITERS.with(|index| {
+        index.borrow_mut().remove(&(listing_id, user));
     });
 
     // 판매글의 즐겨찾기 수 감소
     if let Some(mut listing) = LISTINGS.with(|storage| storage.borrow().get(&listing_id)) {
         listing.favorite_count = listing.favorite_count.saturating_sub(1);
-        listing.updated_at = ic_cdk::api::time();
 
-        LISTINGS.with(|storage| {
-            storage.borrow_mut().insert(listing_id, listing);
-        });
+        save_listing(&listing);
     }
 
     Ok(())
@@ -847,12 +1443,11 @@ pub fn remove_favorite(user: Principal, listing_id: u64) -> Result<(), String> {
 
 /// 사용자의 즐겨찾기 목록 조회
 pub fn get_user_favorites(user: Principal) -> Vec<ListingSummary> {
-    let favorite_listing_ids: Vec<u64> = FAVORITES.with(|storage| {
+    let favorite_listing_ids: Vec<u64> = USER_FAVORITES.with(|storage| {
         storage
             .borrow()
-            .iter()
-            .filter(|(_, fav)| fav.user == user)
-            .map(|(_, fav)| fav.listing_id)
+            .range((user, 0)..=(user, u64::MAX))
+            .map(|((_, listing_id), _)| listing_id)
             .collect()
     });
 
@@ -868,12 +1463,61 @@ pub fn get_user_favorites(user: Principal) -> Vec<ListingSummary> {
 
 /// 즐겨찾기 여부 확인
 pub fn is_favorited(user: Principal, listing_id: u64) -> bool {
-    FAVORITES.with(|storage| {
+    USER_FAVORITES.with(|storage| storage.borrow().contains_key(&(user, listing_id)))
+}
+
+/// 판매글을 즐겨찾기한 사용자 목록 (최근 순)
+pub fn get_listing_favoriters(listing_id: u64) -> Vec<Favorite> {
+    let mut favorites: Vec<Favorite> = LISTING_FAVORITERS.with(|index| {
+        index
+            .borrow()
+            .range((listing_id, Principal::management_canister())..)
+            .take_while(|((id, _), _)| *id == listing_id)
+            .map(|((_, user), created_at)| Favorite {
+                user,
+                listing_id,
+                created_at,
+            })
+            .collect()
+    });
+
+    favorites.sort_by_key(|favorite| std::cmp::Reverse(favorite.created_at));
+    favorites
+}
+
+/// 해시 ID 기반 이전 즐겨찾기를 복합 키 저장소로 이전
+///
+/// 이전 저장소는 비워지므로 여러 번 호출해도 안전하다. 중복 항목이 있었을 수
+/// 있으므로 이전된 판매글의 즐겨찾기 수는 인덱스 기준으로 다시 계산한다.
+fn migrate_legacy_favorites() -> u64 {
+    let legacy: Vec<Favorite> = LEGACY_FAVORITES.with(|storage| {
         storage
             .borrow()
             .iter()
-            .any(|(_, fav)| fav.user == user && fav.listing_id == listing_id)
-    })
+            .map(|(_, favorite)| favorite)
+            .collect()
+    });
+
+    if legacy.is_empty() {
+        return 0;
+    }
+
+    let mut touched_listings = HashSet::new();
+    for favorite in &legacy {
+        insert_favorite(favorite.user, favorite.listing_id, favorite.created_at);
+        touched_listings.insert(favorite.listing_id);
+    }
+
+    for listing_id in touched_listings {
+        if let Some(mut listing) = LISTINGS.with(|storage| storage.borrow().get(&listing_id)) {
+            listing.favorite_count = get_listing_favoriters(listing_id).len() as u64;
+            save_listing(&listing);
+        }
+    }
+
+    LEGACY_FAVORITES.with(|storage| storage.borrow_mut().clear_new());
+
+    legacy.len() as u64
 }
 
 // =====================
@@ -887,43 +1531,292 @@ pub fn log_activity(
     listing_id: Option<u64>,
     details: String,
 ) {
-    // 활동 ID 생성 (해시 기반)
-    let activity_data = format!(
-        "{}_{}_{}_{}",
-        user.to_text(),
-        listing_id.unwrap_or(0),
-        details,
-        ic_cdk::api::time()
-    );
-    let activity_id = generate_unique_id("activity", &activity_data);
-
-    let activity = ActivityLog {
+    insert_activity(ActivityLog {
         timestamp: ic_cdk::api::time(),
         activity_type,
         user,
         listing_id,
         details,
-    };
+    });
+}
 
+/// 활동 로그 저장 (순번 발급 후 저장, 인덱스 갱신)
+fn insert_activity(activity: ActivityLog) -> u64 {
+    let activity_id = ACTIVITY_COUNTER.with(next_counter_value);
+
+    index_activity(activity_id, &activity);
     ACTIVITY_LOGS.with(|storage| {
         storage.borrow_mut().insert(activity_id, activity);
     });
+
+    activity_id
+}
+
+fn index_activity(activity_id: u64, activity: &ActivityLog) {
+    if let Some(listing_id) = activity.listing_id {
+        ACTIVITIES_BY_LISTING.with(|index| {
+            index.borrow_mut().insert((listing_id, activity_id), ());
+        });
+    }
+    ACTIVITIES_BY_USER.with(|index| {
+        index.borrow_mut().insert((activity.user, activity_id), ());
+    });
+}
+
+fn unindex_activity(activity_id: u64, activity: &ActivityLog) {
+    if let Some(listing_id) = activity.listing_id {
+        ACTIVITIES_BY_LISTING.with(|index| {
+            index.borrow_mut().remove(&(listing_id, activity_id));
+        });
+    }
+    ACTIVITIES_BY_USER.with(|index| {
+        index.borrow_mut().remove(&(activity.user, activity_id));
+    });
 }
 
 /// 최근 활동 로그 조회
 pub fn get_recent_activities(limit: u64) -> Vec<ActivityLog> {
+    get_recent_activities_page(limit, None).activities
+}
+
+/// 최근 활동 로그 조회 (최신순, 커서 기반)
+///
+/// 순번이 기록 시각 순이므로 커서 위치부터 역순으로 limit개만 읽는다.
+pub fn get_recent_activities_page(limit: u64, cursor: Option<PageCursor>) -> ActivityPage {
+    let upper = cursor.map_or(u64::MAX, |cursor| cursor.id);
+
     ACTIVITY_LOGS.with(|storage| {
-        let mut activities: Vec<_> = storage
-            .borrow()
+        let storage = storage.borrow();
+        activity_page(storage.range(..upper).rev(), limit as usize)
+    })
+}
+
+/// 판매글별 활동 로그 조회 (최신순, 커서 기반)
+pub fn get_listing_activities(
+    listing_id: u64,
+    activity_type: Option<ActivityType>,
+    limit: u64,
+    cursor: Option<PageCursor>,
+) -> ActivityPage {
+    let upper = cursor.map_or(u64::MAX, |cursor| cursor.id);
+
+    ACTIVITIES_BY_LISTING.with(|index| {
+        let index = index.borrow();
+        let ids = index
+            .range((listing_id, 0)..(listing_id, upper))
+            .rev()
+            .map(|((_, activity_id), _)| activity_id);
+        activity_page(indexed_activities(ids, activity_type), limit as usize)
+    })
+}
+
+/// 사용자별 활동 로그 조회 (최신순, 커서 기반)
+pub fn get_user_activities(
+    user: Principal,
+    activity_type: Option<ActivityType>,
+    limit: u64,
+    cursor: Option<PageCursor>,
+) -> ActivityPage {
+    let upper = cursor.map_or(u64::MAX, |cursor| cursor.id);
+
+    ACTIVITIES_BY_USER.with(|index| {
+        let index = index.borrow();
+        let ids = index
+            .range((user, 0)..(user, upper))
+            .rev()
+            .map(|((_, activity_id), _)| activity_id);
+        activity_page(indexed_activities(ids, activity_type), limit as usize)
+    })
+}
+
+/// 인덱스의 순번으로 활동 로그를 읽고 타입으로 거름
+fn indexed_activities(
+    ids: impl Iterator<Item = u64>,
+    activity_type: Option<ActivityType>,
+) -> impl Iterator<Item = (u64, ActivityLog)> {
+    ids.filter_map(|activity_id| {
+        ACTIVITY_LOGS
+            .with(|storage| storage.borrow().get(&activity_id))
+            .map(|activity| (activity_id, activity))
+    })
+    .filter(move |(_, activity)| {
+        activity_type
+            .as_ref()
+            .is_none_or(|activity_type| &activity.activity_type == activity_type)
+    })
+}
+
+/// 최신순 활동 로그에서 limit개를 잘라 페이지 구성
+fn activity_page(entries: impl Iterator<Item = (u64, ActivityLog)>, limit: usize) -> ActivityPage {
+    let mut page: Vec<(u64, ActivityLog)> = entries.take(limit + 1).collect();
+
+    let has_more = page.len() > limit;
+    page.truncate(limit);
+
+    let next_cursor = if has_more {
+        page.last().map(|(id, activity)| {
+            PageCursor {
+                key: activity.timestamp,
+                id: *id,
+                scope: PageCursor::ACTIVITY_SCOPE,
+            }
+            .encode()
+        })
+    } else {
+        None
+    };
+
+    ActivityPage {
+        activities: page.into_iter().map(|(_, activity)| activity).collect(),
+        next_cursor,
+    }
+}
+
+/// 활동 로그 보존 정책 조회
+pub fn get_activity_retention_policy() -> ActivityRetentionPolicy {
+    ACTIVITY_RETENTION.with(|policy| policy.borrow().get().clone())
+}
+
+/// 활동 로그 보존 정책 변경
+pub fn set_activity_retention_policy(policy: ActivityRetentionPolicy) -> Result<(), BackendError> {
+    policy.validate()?;
+    ACTIVITY_RETENTION.with(|cell| {
+        cell.borrow_mut()
+            .set(policy)
+            .map(|_| ())
+            .map_err(|e| BackendError::internal(format!("{:?}", e)))
+    })
+}
+
+/// 보존 정책을 넘어선 오래된 활동 로그를 아카이브로 이동
+///
+/// 한 번에 MAX_ARCHIVE_BATCH개까지만 옮기므로 남은 항목은 다음 실행에서 처리된다.
+pub fn archive_activities(now: u64) -> u64 {
+    let policy = get_activity_retention_policy();
+    let cutoff = now.saturating_sub(policy.max_age_days.saturating_mul(DAY_NANOS));
+
+    let expired: Vec<(u64, ActivityLog)> = ACTIVITY_LOGS.with(|storage| {
+        let storage = storage.borrow();
+        let overflow = storage.len().saturating_sub(policy.max_entries) as usize;
+        storage
             .iter()
-            .map(|(_, activity)| activity)
-            .collect();
-        activities.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-        activities.truncate(limit as usize);
-        activities
+            .enumerate()
+            .take_while(|(position, (_, activity))| {
+                *position < overflow || activity.timestamp < cutoff
+            })
+            .take(MAX_ARCHIVE_BATCH)
+            .map(|(_, entry)| entry)
+            .collect()
+    });
+
+    for (activity_id, activity) in &expired {
+        unindex_activity(*activity_id, activity);
+        ACTIVITY_LOGS.with(|storage| {
+            storage.borrow_mut().remove(activity_id);
+        });
+        ARCHIVED_ACTIVITY_LOGS.with(|archive| {
+            archive.borrow_mut().insert(*activity_id, activity.clone());
+        });
+    }
+
+    expired.len() as u64
+}
+
+/// 활동 로그 저장 현황
+pub fn get_activity_log_status() -> ActivityLogStatus {
+    ActivityLogStatus {
+        live_count: ACTIVITY_LOGS.with(|storage| storage.borrow().len()),
+        archived_count: ARCHIVED_ACTIVITY_LOGS.with(|archive| archive.borrow().len()),
+        policy: get_activity_retention_policy(),
+    }
+}
+
+/// 활동 로그 내보내기 (아카이브 포함, 순번 오름차순)
+///
+/// 아카이브의 순번은 항상 활성 로그보다 작으므로 아카이브 다음에 활성 로그를 이어 읽는다.
+pub fn export_activities(
+    format: ExportFormat,
+    from_id: Option<u64>,
+    limit: u64,
+) -> Result<ActivityExport, BackendError> {
+    let from_id = from_id.unwrap_or(0);
+    let limit = limit as usize;
+
+    let mut entries: Vec<(u64, ActivityLog)> = ARCHIVED_ACTIVITY_LOGS
+        .with(|archive| archive.borrow().range(from_id..).take(limit + 1).collect());
+    if entries.len() <= limit {
+        let remaining = limit + 1 - entries.len();
+        ACTIVITY_LOGS.with(|storage| {
+            entries.extend(storage.borrow().range(from_id..).take(remaining));
+        });
+    }
+
+    let next_from_id = if entries.len() > limit {
+        entries.pop().map(|(activity_id, _)| activity_id)
+    } else {
+        None
+    };
+    let activities: Vec<ActivityLog> = entries.into_iter().map(|(_, activity)| activity).collect();
+
+    let data = match format {
+        ExportFormat::Cbor => serde_cbor::to_vec(&activities).map_err(BackendError::internal)?,
+        ExportFormat::JsonLines => {
+            let mut data = Vec::new();
+            for activity in &activities {
+                serde_json::to_writer(&mut data, activity).map_err(BackendError::internal)?;
+                data.push(b'\n');
+            }
+            data
+        }
+    };
+
+    Ok(ActivityExport {
+        format,
+        data,
+        count: activities.len() as u64,
+        next_from_id,
     })
 }
 
+/// 인덱스가 비어 있으면 활성 로그 전체로 다시 구성 (인덱스 도입 이전 데이터용)
+fn rebuild_activity_indexes() -> u64 {
+    let has_index = ACTIVITIES_BY_USER.with(|index| !index.borrow().is_empty());
+    if has_index {
+        return 0;
+    }
+
+    let entries: Vec<(u64, ActivityLog)> =
+        ACTIVITY_LOGS.with(|storage| storage.borrow().iter().collect());
+    for (activity_id, activity) in &entries {
+        index_activity(*activity_id, activity);
+    }
+
+    entries.len() as u64
+}
+
+/// 해시 ID 기반 이전 활동 로그를 시각 순 순번 키로 이전
+///
+/// 이전 저장소는 비워지므로 여러 번 호출해도 안전하다.
+fn migrate_legacy_activities() -> u64 {
+    let mut legacy: Vec<(u64, ActivityLog)> =
+        LEGACY_ACTIVITY_LOGS.with(|storage| storage.borrow().iter().collect());
+
+    if legacy.is_empty() {
+        return 0;
+    }
+
+    legacy.sort_by_key(|(legacy_id, activity)| (activity.timestamp, *legacy_id));
+    let migrated = legacy.len() as u64;
+
+    for (_, activity) in legacy {
+        insert_activity(activity);
+    }
+
+    LEGACY_ACTIVITY_LOGS.with(|storage| storage.borrow_mut().clear_new());
+
+    migrated
+}
+
 // =====================
 // 9) 통계 정보
 // =====================
@@ -1015,18 +1908,45 @@ fn to_listing_summary(listing: Listing) -> ListingSummary {
         view_count: listing.view_count,
         favorite_count: listing.favorite_count,
         data_count: listing.data_ids.len() as u64,
+        dataset_count: listing.dataset_ids.len() as u64,
+        quality_score: listing.quality_score,
     }
 }
 
 /// 판매글 상세 정보 가져오기 (데이터 정보 포함)
 pub fn get_listing_detail(listing_id: u64) -> Option<ListingDetail> {
-    let listing = get_listing(listing_id)?;
+    let listing = get_listing_readonly(listing_id)?;
+
+    // 최신 버전을 따르는 판매글은 각 데이터셋의 최신 버전으로 해석
+    let dataset_ids = resolved_dataset_ids(&listing);
+    let quality_score = listing.quality_score;
+    let datasets: Vec<crate::types::DatasetInfo> = dataset_ids
+        .iter()
+        .filter_map(|&dataset_id| crate::storage::get_dataset_info(dataset_id))
+        .collect();
 
-    // 데이터 정보 가져오기 (storage.rs의 새로운 공개 함수 사용)
-    let data_info: Vec<crate::types::DataInfo> =
-        crate::storage::get_multiple_data_info(&listing.data_ids);
+    // 데이터 정보 가져오기 (data_ids는 새 버전 게시 때 최신 버전으로 갱신됨)
+    let data_info = crate::storage::get_multiple_data_info(&listing.data_ids);
 
-    Some(ListingDetail { listing, data_info })
+    Some(ListingDetail {
+        listing,
+        data_info,
+        datasets,
+        quality_score,
+    })
+}
+
+/// 판매글이 제공하는 데이터셋 ID (최신 버전을 따르면 각 데이터셋의 최신 버전)
+fn resolved_dataset_ids(listing: &Listing) -> Vec<u64> {
+    if listing.follow_latest {
+        listing
+            .dataset_ids
+            .iter()
+            .map(|&dataset_id| crate::storage::latest_dataset_id(dataset_id))
+            .collect()
+    } else {
+        listing.dataset_ids.clone()
+    }
 }
 
 /// 카테고리별 판매글 수 조회
@@ -1065,3 +1985,1287 @@ pub fn get_popular_tags(limit: u64) -> Vec<(String, u64)> {
         result
     })
 }
+
+// =====================
+// 11) 저장된 검색 및 알림
+// =====================
+
+/// 판매글이 저장된 검색 조건과 일치하는지 확인
+pub fn listing_matches_search(listing: &Listing, search: &SearchListingsRequest) -> bool {
+    if listing.status != ListingStatus::Active || !apply_basic_filters(listing, search) {
+        return false;
+    }
+
+    match search.query.as_deref().map(tokenize_query) {
+        Some(tokens) if !tokens.is_empty() => calculate_relevance_score(listing, &tokens) > 0.0,
+        _ => true,
+    }
+}
+
+/// 검색 저장
+pub fn save_search(owner: Principal, request: SaveSearchRequest) -> Result<u64, BackendError> {
+    let saved_count = get_saved_searches(owner).len();
+    if saved_count >= MAX_SAVED_SEARCHES_PER_USER {
+        return Err(BackendError::quota_exceeded(
+            "saved_searches",
+            MAX_SAVED_SEARCHES_PER_USER as u64,
+        ));
+    }
+
+    let id = SAVED_SEARCH_COUNTER.with(next_counter_value);
+    let saved_search = SavedSearch {
+        id,
+        owner,
+        name: request.name,
+        search: request.search,
+        created_at: ic_cdk::api::time(),
+    };
+
+    SAVED_SEARCHES.with(|storage| {
+        storage.borrow_mut().insert(id, saved_search);
+    });
+
+    Ok(id)
+}
+
+/// 사용자의 저장된 검색 목록 조회
+pub fn get_saved_searches(owner: Principal) -> Vec<SavedSearch> {
+    SAVED_SEARCHES.with(|storage| {
+        storage
+            .borrow()
+            .iter()
+            .map(|(_, saved_search)| saved_search)
+            .filter(|saved_search| saved_search.owner == owner)
+            .collect()
+    })
+}
+
+/// 저장된 검색 삭제
+pub fn delete_saved_search(owner: Principal, saved_search_id: u64) -> Result<(), BackendError> {
+    SAVED_SEARCHES.with(|storage| {
+        let mut storage = storage.borrow_mut();
+        match storage.get(&saved_search_id) {
+            Some(saved_search) if saved_search.owner == owner => {
+                storage.remove(&saved_search_id);
+                Ok(())
+            }
+            Some(_) => Err(BackendError::unauthorized("delete_saved_search")),
+            None => Err(BackendError::not_found("saved_search", saved_search_id)),
+        }
+    })
+}
+
+/// 판매글 생성/수정 시 일치하는 저장된 검색의 소유자에게 알림 생성
+///
+/// 수정의 경우 이전 상태에서 이미 일치하던 검색에는 다시 알리지 않는다.
+fn notify_saved_search_matches(listing: &Listing, previous: Option<&Listing>) {
+    let kind = if previous.is_some() {
+        NotificationKind::UpdatedListingMatch
+    } else {
+        NotificationKind::NewListingMatch
+    };
+
+    let matches: Vec<SavedSearch> = SAVED_SEARCHES.with(|storage| {
+        storage
+            .borrow()
+            .iter()
+            .map(|(_, saved_search)| saved_search)
+            .filter(|saved_search| saved_search.owner != listing.seller)
+            .filter(|saved_search| listing_matches_search(listing, &saved_search.search))
+            .filter(|saved_search| {
+                previous
+                    .is_none_or(|previous| !listing_matches_search(previous, &saved_search.search))
+            })
+            .collect()
+    });
+
+    for saved_search in matches {
+        let id = NOTIFICATION_COUNTER.with(next_counter_value);
+        let notification = Notification {
+            id,
+            kind: kind.clone(),
+            saved_search_id: saved_search.id,
+            saved_search_name: saved_search.name,
+            listing_id: listing.id,
+            listing_title: listing.title.clone(),
+            created_at: ic_cdk::api::time(),
+            read: false,
+        };
+
+        push_notification(saved_search.owner, notification);
+    }
+}
+
+/// 알림함에 알림 추가 (최대 개수를 넘으면 오래된 알림부터 제거)
+fn push_notification(user: Principal, notification: Notification) {
+    NOTIFICATIONS.with(|storage| {
+        let mut storage = storage.borrow_mut();
+        storage.insert((user, notification.id), notification);
+
+        let user_keys: Vec<(Principal, u64)> =
+            storage.keys_range((user, 0)..=(user, u64::MAX)).collect();
+        let overflow = user_keys.len().saturating_sub(MAX_NOTIFICATIONS_PER_USER);
+        for key in user_keys.into_iter().take(overflow) {
+            storage.remove(&key);
+        }
+    });
+}
+
+/// 사용자 알림 조회 (최신순, 커서 기반)
+pub fn get_notifications(
+    user: Principal,
+    unread_only: bool,
+    limit: u64,
+    cursor: Option<PageCursor>,
+) -> NotificationPage {
+    NOTIFICATIONS.with(|storage| {
+        let storage = storage.borrow();
+        let upper = cursor.map_or(u64::MAX, |cursor| cursor.id.saturating_sub(1));
+
+        let unread_count = storage
+            .range((user, 0)..=(user, u64::MAX))
+            .filter(|(_, notification)| !notification.read)
+            .count() as u64;
+
+        let mut matching = storage
+            .range((user, 0)..=(user, upper))
+            .rev()
+            .map(|(_, notification)| notification)
+            .filter(|notification| !unread_only || !notification.read);
+
+        let notifications: Vec<Notification> = matching.by_ref().take(limit as usize).collect();
+        let next_cursor = match (notifications.last(), matching.next()) {
+            (Some(last), Some(_)) => Some(
+                PageCursor {
+                    key: last.id,
+                    id: last.id,
+                    scope: PageCursor::NOTIFICATION_SCOPE,
+                }
+                .encode(),
+            ),
+            _ => None,
+        };
+
+        NotificationPage {
+            notifications,
+            unread_count,
+            next_cursor,
+        }
+    })
+}
+
+/// 알림 읽음 처리 (ids가 없으면 전체), 변경된 알림 수 반환
+pub fn mark_notifications_read(user: Principal, ids: Option<Vec<u64>>) -> u64 {
+    NOTIFICATIONS.with(|storage| {
+        let mut storage = storage.borrow_mut();
+
+        let keys: Vec<(Principal, u64)> = match ids {
+            Some(ids) => ids.into_iter().map(|id| (user, id)).collect(),
+            None => storage.keys_range((user, 0)..=(user, u64::MAX)).collect(),
+        };
+
+        let mut marked = 0u64;
+        for key in keys {
+            if let Some(mut notification) = storage.get(&key) {
+                if !notification.read {
+                    notification.read = true;
+                    storage.insert(key, notification);
+                    marked += 1;
+                }
+            }
+        }
+        marked
+    })
+}
+
+// =====================
+// 12) 협업 필터링 추천 모델
+// =====================
+
+/// 추천 모델에 반영되는 사용자 상호작용 종류
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum InteractionKind {
+    View,
+    Favorite,
+    Purchase,
+}
+
+impl InteractionKind {
+    /// 상호작용 가중치 (구매 > 즐겨찾기 > 조회)
+    fn weight(self) -> f64 {
+        match self {
+            InteractionKind::View => 1.0,
+            InteractionKind::Favorite => 3.0,
+            InteractionKind::Purchase => 5.0,
+        }
+    }
+}
+
+/// 사용자가 한 판매글에 남긴 상호작용 요약
+#[derive(Default, Clone, Copy)]
+struct UserItemSignal {
+    weight: f64,
+    viewed: bool,
+    favorited: bool,
+    purchased: bool,
+}
+
+impl UserItemSignal {
+    fn add(&mut self, kind: InteractionKind) {
+        self.weight = self.weight.max(kind.weight());
+        match kind {
+            InteractionKind::View => self.viewed = true,
+            InteractionKind::Favorite => self.favorited = true,
+            InteractionKind::Purchase => self.purchased = true,
+        }
+    }
+}
+
+/// 모델에 반영할 사용자인지 확인 (익명/시스템 주체 제외)
+fn is_interacting_user(user: &Principal) -> bool {
+    *user != Principal::anonymous() && *user != Principal::management_canister()
+}
+
+/// 활동 로그의 상호작용 (조회만 사용, 구매는 구매 기록에서 읽음)
+fn activity_interaction(activity: &ActivityLog) -> Option<(Principal, u64, InteractionKind)> {
+    match (&activity.activity_type, activity.listing_id) {
+        (ActivityType::ListingViewed, Some(listing_id)) if is_interacting_user(&activity.user) => {
+            Some((activity.user, listing_id, InteractionKind::View))
+        }
+        _ => None,
+    }
+}
+
+/// 한 사용자의 상호작용 수집 (즐겨찾기 + 구매 기록 + 조회 활동 로그, 사용자 인덱스 사용)
+fn collect_user_interactions(user: Principal) -> Vec<(Principal, u64, InteractionKind)> {
+    if !is_interacting_user(&user) {
+        return Vec::new();
+    }
+
+    let mut interactions: Vec<(Principal, u64, InteractionKind)> = USER_FAVORITES.with(|storage| {
+        storage
+            .borrow()
+            .range((user, 0)..=(user, u64::MAX))
+            .map(|((_, listing_id), _)| (user, listing_id, InteractionKind::Favorite))
+            .collect()
+    });
+
+    PURCHASES.with(|storage| {
+        interactions.extend(
+            storage
+                .borrow()
+                .range((user, 0)..=(user, u64::MAX))
+                .map(|((_, listing_id), _)| (user, listing_id, InteractionKind::Purchase)),
+        );
+    });
+
+    ACTIVITIES_BY_USER.with(|index| {
+        let ids = index
+            .borrow()
+            .range((user, 0)..=(user, u64::MAX))
+            .map(|((_, activity_id), _)| activity_id)
+            .collect::<Vec<_>>();
+        interactions.extend(
+            indexed_activities(ids.into_iter(), Some(ActivityType::ListingViewed))
+                .filter_map(|(_, activity)| activity_interaction(&activity)),
+        );
+    });
+
+    interactions
+}
+
+/// 사용자별 상호작용을 판매글 단위로 집계
+fn group_interactions(
+    interactions: &[(Principal, u64, InteractionKind)],
+) -> HashMap<Principal, HashMap<u64, UserItemSignal>> {
+    let mut per_user: HashMap<Principal, HashMap<u64, UserItemSignal>> = HashMap::new();
+    for &(user, listing_id, kind) in interactions {
+        per_user
+            .entry(user)
+            .or_default()
+            .entry(listing_id)
+            .or_default()
+            .add(kind);
+    }
+    per_user
+}
+
+/// 판매글 간 공동 출현(co-occurrence) 모델 계산
+///
+/// 같은 사용자가 상호작용한 판매글 쌍마다 두 가중치 중 작은 값을 누적하고,
+/// 각 판매글의 전체 가중치로 코사인 정규화한다.
+pub fn build_cooccurrence_model(
+    interactions: &[(Principal, u64, InteractionKind)],
+) -> HashMap<u64, Vec<ItemNeighbor>> {
+    let per_user = group_interactions(interactions);
+
+    let mut item_weights: HashMap<u64, f64> = HashMap::new();
+    let mut pairs: HashMap<(u64, u64), ItemNeighbor> = HashMap::new();
+
+    for items in per_user.values() {
+        // 가중치가 큰 상호작용 위주로 사용자당 판매글 수 제한
+        let mut items: Vec<(u64, UserItemSignal)> =
+            items.iter().map(|(id, signal)| (*id, *signal)).collect();
+        items.sort_by(|a, b| b.1.weight.total_cmp(&a.1.weight).then(a.0.cmp(&b.0)));
+        items.truncate(MAX_ITEMS_PER_USER);
+
+        for (listing_id, signal) in &items {
+            *item_weights.entry(*listing_id).or_insert(0.0) += signal.weight;
+        }
+
+        for (a, signal_a) in &items {
+            for (b, signal_b) in &items {
+                if a == b {
+                    continue;
+                }
+
+                let neighbor = pairs.entry((*a, *b)).or_insert_with(|| ItemNeighbor {
+                    listing_id: *b,
+                    score: 0.0,
+                    shared_purchases: 0,
+                    shared_favorites: 0,
+                    shared_views: 0,
+                });
+                neighbor.score += signal_a.weight.min(signal_b.weight);
+                if signal_a.purchased && signal_b.purchased {
+                    neighbor.shared_purchases += 1;
+                }
+                if signal_a.favorited && signal_b.favorited {
+                    neighbor.shared_favorites += 1;
+                }
+                if signal_a.viewed && signal_b.viewed {
+                    neighbor.shared_views += 1;
+                }
+            }
+        }
+    }
+
+    let mut model: HashMap<u64, Vec<ItemNeighbor>> = HashMap::new();
+    for ((a, b), mut neighbor) in pairs {
+        let norm = (item_weights[&a] * item_weights[&b]).sqrt();
+        if norm > 0.0 {
+            neighbor.score /= norm;
+        }
+        model.entry(a).or_default().push(neighbor);
+    }
+
+    for neighbors in model.values_mut() {
+        neighbors.sort_by(|x, y| {
+            y.score
+                .total_cmp(&x.score)
+                .then(x.listing_id.cmp(&y.listing_id))
+        });
+        neighbors.truncate(MAX_NEIGHBORS_PER_ITEM);
+    }
+
+    model
+}
+
+/// 추천 모델 재계산 진행 상태
+///
+/// 즐겨찾기 -> 구매 기록 -> 활동 로그 순으로 단계마다 최대 MAX_REBUILD_BATCH개씩 읽고,
+/// 모두 읽으면 모델을 계산해 저장한다.
+#[derive(Default)]
+struct ModelRebuild {
+    phase: RebuildPhase,
+    next_favorite: Option<(Principal, u64)>,
+    next_purchase: Option<(Principal, u64)>,
+    next_activity: u64,
+    interactions: Vec<(Principal, u64, InteractionKind)>,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+enum RebuildPhase {
+    #[default]
+    Favorites,
+    Purchases,
+    Activities,
+    Build,
+}
+
+/// (사용자, 판매글 ID) 키 저장소에서 start부터 한 배치 읽기 (다음 시작 키 반환)
+fn read_user_item_batch<V: ic_stable_structures::Storable>(
+    storage: &StableBTreeMap<(Principal, u64), V, Memory>,
+    start: Option<(Principal, u64)>,
+    kind: InteractionKind,
+    interactions: &mut Vec<(Principal, u64, InteractionKind)>,
+) -> Option<(Principal, u64)> {
+    let mut keys = match start {
+        Some(start) => storage.range(start..),
+        None => storage.range(..),
+    }
+    .map(|(key, _)| key);
+
+    for (user, listing_id) in keys.by_ref().take(MAX_REBUILD_BATCH) {
+        if is_interacting_user(&user) {
+            interactions.push((user, listing_id, kind));
+        }
+    }
+    keys.next()
+}
+
+/// 추천 모델 재계산을 한 단계 진행
+///
+/// 모델까지 저장했으면 모델에 포함된 판매글 수를, 아직 남았으면 None을 반환한다.
+pub fn refresh_recommendation_model_step(now: u64) -> Option<u64> {
+    let mut rebuild = MODEL_REBUILD.with(|state| state.borrow_mut().take().unwrap_or_default());
+
+    match rebuild.phase {
+        RebuildPhase::Favorites => {
+            rebuild.next_favorite = USER_FAVORITES.with(|storage| {
+                read_user_item_batch(
+                    &storage.borrow(),
+                    rebuild.next_favorite,
+                    InteractionKind::Favorite,
+                    &mut rebuild.interactions,
+                )
+            });
+            if rebuild.next_favorite.is_none() {
+                rebuild.phase = RebuildPhase::Purchases;
+            }
+        }
+        RebuildPhase::Purchases => {
+            rebuild.next_purchase = PURCHASES.with(|storage| {
+                read_user_item_batch(
+                    &storage.borrow(),
+                    rebuild.next_purchase,
+                    InteractionKind::Purchase,
+                    &mut rebuild.interactions,
+                )
+            });
+            if rebuild.next_purchase.is_none() {
+                rebuild.phase = RebuildPhase::Activities;
+            }
+        }
+        RebuildPhase::Activities => {
+            let mut read = 0;
+            ACTIVITY_LOGS.with(|storage| {
+                for (activity_id, activity) in storage
+                    .borrow()
+                    .range(rebuild.next_activity..)
+                    .take(MAX_REBUILD_BATCH)
+                {
+                    rebuild.next_activity = activity_id + 1;
+                    rebuild.interactions.extend(activity_interaction(&activity));
+                    read += 1;
+                }
+            });
+            if read < MAX_REBUILD_BATCH {
+                rebuild.phase = RebuildPhase::Build;
+            }
+        }
+        RebuildPhase::Build => return Some(store_recommendation_model(&rebuild.interactions, now)),
+    }
+
+    MODEL_REBUILD.with(|state| *state.borrow_mut() = Some(rebuild));
+    None
+}
+
+/// 상호작용으로 모델을 계산해 안정 메모리에 저장, 모델에 포함된 판매글 수 반환
+fn store_recommendation_model(
+    interactions: &[(Principal, u64, InteractionKind)],
+    updated_at: u64,
+) -> u64 {
+    let model = build_cooccurrence_model(interactions);
+
+    RECOMMENDATION_MODEL.with(|storage| {
+        let mut storage = storage.borrow_mut();
+        storage.clear_new();
+        for (listing_id, neighbors) in &model {
+            storage.insert(
+                *listing_id,
+                SimilarListings {
+                    neighbors: neighbors.clone(),
+                    updated_at,
+                },
+            );
+        }
+    });
+
+    model.len() as u64
+}
+
+/// 추천 설명 문구 생성
+fn explain_recommendation(reason: &RecommendationReason, source_title: &str) -> String {
+    match reason {
+        RecommendationReason::CoPurchased => {
+            format!(
+                "'{}'을(를) 구매한 사용자들이 함께 구매했습니다",
+                source_title
+            )
+        }
+        RecommendationReason::CoFavorited => format!(
+            "'{}'을(를) 즐겨찾기한 사용자들이 함께 즐겨찾기했습니다",
+            source_title
+        ),
+        RecommendationReason::CoViewed => {
+            format!("'{}'을(를) 본 사용자들이 함께 봤습니다", source_title)
+        }
+        RecommendationReason::SimilarContent if source_title.is_empty() => {
+            "즐겨찾기한 판매글과 키워드가 비슷합니다".to_string()
+        }
+        RecommendationReason::SimilarContent => {
+            format!("'{}'와(과) 키워드가 비슷합니다", source_title)
+        }
+        RecommendationReason::Trending => "지금 인기 있는 판매글입니다".to_string(),
+    }
+}
+
+/// 추천 가능한 판매글인지 확인 (활성 상태 + 본인 판매글 제외)
+fn recommendable_listing(listing_id: u64, user: Option<Principal>) -> Option<Listing> {
+    get_listing_readonly(listing_id).filter(|listing| {
+        listing.status == ListingStatus::Active && user.is_none_or(|user| listing.seller != user)
+    })
+}
+
+/// 특정 판매글과 함께 상호작용된 판매글 ("이 판매글을 즐겨찾기한 사용자들이 함께 즐겨찾기한…")
+pub fn get_cooccurring_listings(listing_id: u64, limit: usize) -> Vec<Recommendation> {
+    let Some(source) = get_listing_readonly(listing_id) else {
+        return Vec::new();
+    };
+
+    let neighbors = RECOMMENDATION_MODEL
+        .with(|storage| storage.borrow().get(&listing_id))
+        .map(|similar| similar.neighbors)
+        .unwrap_or_default();
+
+    neighbors
+        .into_iter()
+        .filter_map(|neighbor| {
+            let listing = recommendable_listing(neighbor.listing_id, None)?;
+            let reason = neighbor.dominant_reason();
+            Some(Recommendation {
+                listing: to_listing_summary(listing),
+                score: neighbor.score,
+                explanation: explain_recommendation(&reason, &source.title),
+                reason,
+                source_listing_id: Some(listing_id),
+            })
+        })
+        .take(limit)
+        .collect()
+}
+
+/// 사용자 상호작용 기반 협업 필터링 추천
+pub fn get_collaborative_recommendations(user: Principal, limit: usize) -> Vec<Recommendation> {
+    let interactions = collect_user_interactions(user);
+    let user_items = group_interactions(&interactions)
+        .remove(&user)
+        .unwrap_or_default();
+
+    // 후보 판매글별 (누적 점수, 가장 크게 기여한 근거 판매글, 근거 신호)
+    let mut candidates: HashMap<u64, (f64, u64, f64, ItemNeighbor)> = HashMap::new();
+
+    RECOMMENDATION_MODEL.with(|storage| {
+        let storage = storage.borrow();
+        for (source_id, signal) in &user_items {
+            let Some(similar) = storage.get(source_id) else {
+                continue;
+            };
+            for neighbor in similar.neighbors {
+                if user_items.contains_key(&neighbor.listing_id) {
+                    continue;
+                }
+                let contribution = signal.weight * neighbor.score;
+                let entry = candidates.entry(neighbor.listing_id).or_insert((
+                    0.0,
+                    *source_id,
+                    0.0,
+                    neighbor.clone(),
+                ));
+                entry.0 += contribution;
+                if contribution > entry.2 {
+                    entry.1 = *source_id;
+                    entry.2 = contribution;
+                    entry.3 = neighbor;
+                }
+            }
+        }
+    });
+
+    let mut ranked: Vec<(u64, (f64, u64, f64, ItemNeighbor))> = candidates.into_iter().collect();
+    ranked.sort_by(|a, b| b.1 .0.total_cmp(&a.1 .0).then(a.0.cmp(&b.0)));
+
+    ranked
+        .into_iter()
+        .filter_map(|(listing_id, (score, source_id, _, neighbor))| {
+            let listing = recommendable_listing(listing_id, Some(user))?;
+            let source_title = get_listing_readonly(source_id)
+                .map(|source| source.title)
+                .unwrap_or_default();
+            let reason = neighbor.dominant_reason();
+            Some(Recommendation {
+                listing: to_listing_summary(listing),
+                score,
+                explanation: explain_recommendation(&reason, &source_title),
+                reason,
+                source_listing_id: Some(source_id),
+            })
+        })
+        .take(limit)
+        .collect()
+}
+
+/// 판매글 요약을 추천 결과로 변환 (모델 밖의 대체 추천용)
+pub fn to_recommendation(
+    listing: ListingSummary,
+    score: f64,
+    reason: RecommendationReason,
+    source: Option<(u64, &str)>,
+) -> Recommendation {
+    Recommendation {
+        explanation: explain_recommendation(&reason, source.map_or("", |(_, title)| title)),
+        listing,
+        score,
+        reason,
+        source_listing_id: source.map(|(id, _)| id),
+    }
+}
+
+// =====================
+// 13) 조회수 집계
+// =====================
+
+/// 중복 조회 여부 (마지막 집계 후 구간 내 재조회)
+fn is_duplicate_view(last_counted_at: Option<u64>, now: u64) -> bool {
+    last_counted_at.is_some_and(|last| now.saturating_sub(last) < VIEW_DEDUPE_WINDOW_NANOS)
+}
+
+/// 판매글 조회 기록
+///
+/// 같은 조회자의 구간 내 재조회와 판매자 본인의 조회는 집계하지 않는다.
+/// 익명 호출자는 서로 구분할 수 없으므로 중복을 거를 수도, 반복 호출을 막을 수도 없어
+/// 집계하지 않는다. 조회수만 올리고 updated_at은 건드리지 않는다.
+/// 집계되었으면 true를 반환한다.
+pub fn record_view(viewer: Principal, listing_id: u64, now: u64) -> Result<bool, BackendError> {
+    let mut listing = LISTINGS
+        .with(|storage| storage.borrow().get(&listing_id))
+        .ok_or_else(|| BackendError::not_found("listing", listing_id))?;
+
+    if listing.seller == viewer || viewer == Principal::anonymous() {
+        return Ok(false);
+    }
+
+    let last_counted_at = RECENT_VIEWS.with(|views| views.borrow().get(&(viewer, listing_id)));
+    if is_duplicate_view(last_counted_at, now) {
+        return Ok(false);
+    }
+
+    RECENT_VIEWS.with(|views| {
+        views.borrow_mut().insert((viewer, listing_id), now);
+    });
+
+    listing.view_count += 1;
+    save_listing(&listing);
+
+    let day = now / DAY_NANOS;
+    DAILY_VIEWS.with(|daily| {
+        let mut daily = daily.borrow_mut();
+        let count = daily.get(&(listing_id, day)).unwrap_or(0);
+        daily.insert((listing_id, day), count + 1);
+    });
+
+    // 추천 모델 입력으로도 쓰임
+    insert_activity(ActivityLog {
+        timestamp: now,
+        activity_type: ActivityType::ListingViewed,
+        user: viewer,
+        listing_id: Some(listing_id),
+        details: "판매글 조회".to_string(),
+    });
+
+    Ok(true)
+}
+
+/// 판매글 조회수 통계 (최근 days일)
+pub fn get_listing_view_stats(listing_id: u64, days: u64, now: u64) -> Option<ListingViewStats> {
+    let listing = get_listing_readonly(listing_id)?;
+
+    let today = now / DAY_NANOS;
+    let first_day = today.saturating_sub(days.saturating_sub(1));
+
+    let mut daily_views: Vec<DailyViewCount> = DAILY_VIEWS.with(|daily| {
+        daily
+            .borrow()
+            .range((listing_id, first_day)..=(listing_id, today))
+            .map(|((_, day), views)| DailyViewCount {
+                day_start: day * DAY_NANOS,
+                views,
+            })
+            .collect()
+    });
+    daily_views.reverse();
+
+    Some(ListingViewStats {
+        listing_id,
+        total_views: listing.view_count,
+        daily_views,
+    })
+}
+
+/// 만료된 중복 방지 기록과 보존 기간이 지난 일별 조회수 정리
+pub fn prune_view_records(now: u64) -> u64 {
+    let expired_views: Vec<(Principal, u64)> = RECENT_VIEWS.with(|views| {
+        views
+            .borrow()
+            .iter()
+            .filter(|(_, last)| !is_duplicate_view(Some(*last), now))
+            .map(|(key, _)| key)
+            .collect()
+    });
+
+    let oldest_day = (now / DAY_NANOS).saturating_sub(DAILY_VIEW_RETENTION_DAYS);
+    let expired_days: Vec<(u64, u64)> = DAILY_VIEWS.with(|daily| {
+        daily
+            .borrow()
+            .iter()
+            .filter(|((_, day), _)| *day < oldest_day)
+            .map(|(key, _)| key)
+            .collect()
+    });
+
+    RECENT_VIEWS.with(|views| {
+        let mut views = views.borrow_mut();
+        for key in &expired_views {
+            views.remove(key);
+        }
+    });
+    DAILY_VIEWS.with(|daily| {
+        let mut daily = daily.borrow_mut();
+        for key in &expired_days {
+            daily.remove(key);
+        }
+    });
+
+    (expired_views.len() + expired_days.len()) as u64
+}
+
+// =====================
+// 14) 구매 기록
+// =====================
+
+/// 판매 완료 기록 (판매자 본인만, 같은 구매자는 판매글당 한 번)
+///
+/// 구매 시점에 판매글이 제공하는 데이터셋(버전)을 함께 저장하고,
+/// 구매자 기준으로 ListingSold 활동을 남긴다.
+pub fn record_sale(
+    listing_id: u64,
+    seller: Principal,
+    buyer: Principal,
+    now: u64,
+) -> Result<Purchase, BackendError> {
+    let listing = get_listing_readonly(listing_id)
+        .ok_or_else(|| BackendError::not_found("listing", listing_id))?;
+
+    if listing.seller != seller {
+        return Err(BackendError::unauthorized("complete_sale"));
+    }
+    if listing.status != ListingStatus::Active {
+        return Err(BackendError::invalid_state("listing_not_active"));
+    }
+    if buyer == seller || buyer == Principal::anonymous() {
+        return Err(BackendError::validation("buyer", "invalid_format"));
+    }
+    if PURCHASES.with(|storage| storage.borrow().contains_key(&(buyer, listing_id))) {
+        return Err(BackendError::already_exists("purchase"));
+    }
+
+    let purchase = Purchase {
+        listing_id,
+        buyer,
+        seller,
+        dataset_ids: resolved_dataset_ids(&listing),
+        price: listing.price,
+        currency: listing.currency.clone(),
+        purchased_at: now,
+    };
+    PURCHASES.with(|storage| {
+        storage
+            .borrow_mut()
+            .insert((buyer, listing_id), purchase.clone());
+    });
+
+    insert_activity(ActivityLog {
+        timestamp: now,
+        activity_type: ActivityType::ListingSold,
+        user: buyer,
+        listing_id: Some(listing_id),
+        details: format!("판매 완료 (판매자 {})", seller),
+    });
+
+    Ok(purchase)
+}
+
+/// 구매자의 구매 기록 (판매글 ID 순)
+pub fn get_purchases_by_buyer(buyer: Principal) -> Vec<Purchase> {
+    PURCHASES.with(|storage| {
+        storage
+            .borrow()
+            .range((buyer, 0)..=(buyer, u64::MAX))
+            .map(|(_, purchase)| purchase)
+            .collect()
+    })
+}
+
+/// 구매자가 데이터셋을 받을 수 있는지 확인
+///
+/// 구매 당시의 데이터셋 버전과, 최신 버전을 따르는 판매글이면 현재 최신 버전까지 포함한다.
+pub fn has_purchased_dataset(buyer: Principal, dataset_id: u64) -> bool {
+    !purchases_granting_dataset(buyer, dataset_id).is_empty()
+}
+
+/// 구매자에게 데이터셋을 제공한 구매 목록
+fn purchases_granting_dataset(buyer: Principal, dataset_id: u64) -> Vec<Purchase> {
+    get_purchases_by_buyer(buyer)
+        .into_iter()
+        .filter(|purchase| {
+            purchase.dataset_ids.contains(&dataset_id)
+                || get_listing_readonly(purchase.listing_id).is_some_and(|listing| {
+                    listing.follow_latest && resolved_dataset_ids(&listing).contains(&dataset_id)
+                })
+        })
+        .collect()
+}
+
+/// 데이터셋 소유자이거나 데이터셋을 구매했는지 확인
+pub fn can_read_dataset(user: Principal, dataset: &crate::types::Dataset) -> bool {
+    user != Principal::anonymous()
+        && (dataset.owner == user || has_purchased_dataset(user, dataset.id))
+}
+
+/// 데이터셋 쿼리 시 가릴 컬럼 (데이터셋을 읽을 수 없으면 None)
+///
+/// 소유자는 가리지 않고, 구매자는 구매한 판매글이 모두 미리보기에서 가린 컬럼만 가린다.
+pub fn query_masked_columns(
+    user: Principal,
+    dataset: &crate::types::Dataset,
+) -> Option<Vec<String>> {
+    if user == Principal::anonymous() {
+        return None;
+    }
+    if dataset.owner == user {
+        return Some(Vec::new());
+    }
+
+    let masked = purchases_granting_dataset(user, dataset.id)
+        .iter()
+        .map(|purchase| {
+            get_listing_readonly(purchase.listing_id)
+                .map(|listing| listing_masked_columns(&listing))
+                .unwrap_or_default()
+        })
+        .collect();
+    common_masked_columns(masked)
+}
+
+/// 업로드 데이터를 읽을 때 가릴 컬럼 (읽을 수 없으면 None)
+///
+/// 데이터를 포함한 데이터셋의 소유자나 구매자, 또는 데이터를 포함한 판매글의
+/// 판매자나 구매자만 읽을 수 있다 (데이터셋 도입 전 판매글 포함).
+/// 읽기 권한을 준 경로가 여럿이면 모든 경로에서 가린 컬럼만 가린다.
+pub fn data_masked_columns(user: Principal, data_id: u64) -> Option<Vec<String>> {
+    if user == Principal::anonymous() {
+        return None;
+    }
+
+    let mut masked: Vec<Vec<String>> = crate::storage::datasets_containing(data_id)
+        .iter()
+        .filter_map(|dataset| query_masked_columns(user, dataset))
+        .collect();
+    for listing_id in listings_containing(data_id) {
+        let Some(listing) = get_listing_readonly(listing_id) else {
+            continue;
+        };
+        if listing.seller == user {
+            masked.push(Vec::new());
+        } else if PURCHASES.with(|storage| storage.borrow().contains_key(&(user, listing_id))) {
+            masked.push(listing_masked_columns(&listing));
+        }
+    }
+    common_masked_columns(masked)
+}
+
+/// 판매글 미리보기에서 판매자가 가린 컬럼
+fn listing_masked_columns(listing: &Listing) -> Vec<String> {
+    listing
+        .preview_options
+        .as_ref()
+        .and_then(|options| options.masked_columns.clone())
+        .unwrap_or_default()
+}
+
+/// 모든 목록에 공통으로 있는 컬럼 (목록이 없으면 None)
+fn common_masked_columns(masked: Vec<Vec<String>>) -> Option<Vec<String>> {
+    let (first, rest) = masked.split_first()?;
+    Some(
+        first
+            .iter()
+            .filter(|column| rest.iter().all(|columns| columns.contains(column)))
+            .cloned()
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: u64 = 60 * 1_000_000_000;
+    const HOUR: u64 = 60 * MINUTE;
+
+    #[test]
+    fn test_listing_page_scopes_differ_by_filter() {
+        assert_ne!(
+            listing_page_scope(Some(&ListingStatus::Active)),
+            listing_page_scope(Some(&ListingStatus::Sold))
+        );
+        assert_ne!(
+            listing_page_scope(None),
+            listing_page_scope(Some(&ListingStatus::Active))
+        );
+        assert_ne!(
+            seller_listing_page_scope(Principal::from_slice(&[1])),
+            seller_listing_page_scope(Principal::from_slice(&[2]))
+        );
+    }
+
+    #[test]
+    fn test_trending_keywords_respect_window() {
+        let now = 30 * 24 * HOUR;
+
+        // 3시간 전 검색 2회, 최근 검색 1회
+        assert!(record_search_query("Weather data", now - 3 * HOUR));
+        assert!(record_search_query("weather", now - 3 * HOUR));
+        assert!(record_search_query("traffic", now - 5 * MINUTE));
+
+        let last_hour = get_trending_keywords(HOUR, 10, now);
+        assert_eq!(last_hour, vec![("traffic".to_string(), 1)]);
+
+        let last_day = get_trending_keywords(24 * HOUR, 10, now);
+        assert_eq!(last_day[0], ("weather".to_string(), 2));
+        assert_eq!(last_day.len(), 3);
+    }
+
+    #[test]
+    fn test_recommendation_model_rebuilds_in_batches() {
+        let users: Vec<Principal> = (1..=3u8).map(|n| Principal::from_slice(&[n; 29])).collect();
+        USER_FAVORITES.with(|storage| {
+            let mut storage = storage.borrow_mut();
+            for user in &users {
+                storage.insert((*user, 10), 0);
+                storage.insert((*user, 20), 0);
+            }
+        });
+        // 한 배치보다 많은 활동 로그
+        for n in 0..MAX_REBUILD_BATCH as u64 + 1 {
+            insert_activity(ActivityLog {
+                timestamp: n,
+                activity_type: ActivityType::ListingViewed,
+                user: users[0],
+                listing_id: Some(30),
+                details: String::new(),
+            });
+        }
+
+        // 즐겨찾기, 구매 기록, 활동 로그 2배치를 읽은 뒤 모델 저장
+        let steps: Vec<Option<u64>> = (0..5)
+            .map(|_| refresh_recommendation_model_step(1))
+            .collect();
+        assert_eq!(steps, vec![None, None, None, None, Some(3)]);
+
+        let neighbors = RECOMMENDATION_MODEL
+            .with(|storage| storage.borrow().get(&10))
+            .unwrap()
+            .neighbors;
+        assert_eq!(neighbors[0].listing_id, 20);
+        assert_eq!(neighbors[0].shared_favorites, 3);
+    }
+
+    #[test]
+    fn test_search_quota_limits_each_user_per_window() {
+        let user = Principal::from_slice(&[7; 29]);
+        let other = Principal::from_slice(&[8; 29]);
+        let now = 30 * 24 * HOUR;
+
+        assert_eq!(take_search_quota(user, 50, now), Ok(50));
+        assert_eq!(take_search_quota(user, 50, now + MINUTE), Ok(10));
+        assert!(take_search_quota(user, 1, now + 2 * MINUTE).is_err());
+        assert_eq!(take_search_quota(other, 5, now), Ok(5));
+
+        // 다음 구간에서는 다시 기록 가능
+        assert_eq!(take_search_quota(user, 5, now + HOUR), Ok(5));
+    }
+
+    #[test]
+    fn test_search_terms_expire_after_retention() {
+        let start = 30 * 24 * HOUR;
+        assert!(record_search_query("expired", start));
+
+        let later = start + SEARCH_RETENTION_NANOS + HOUR;
+        assert!(record_search_query("fresh", later));
+
+        let keywords = get_trending_keywords(SEARCH_RETENTION_NANOS * 2, 10, later);
+        assert_eq!(keywords, vec![("fresh".to_string(), 1)]);
+    }
+
+    fn sample_listing() -> Listing {
+        Listing {
+            id: 1,
+            seller: Principal::anonymous(),
+            title: "Seoul weather dataset".to_string(),
+            description: "Hourly temperature readings".to_string(),
+            price: 1_000,
+            currency: "ICP".to_string(),
+            data_ids: vec![1],
+            dataset_ids: vec![1],
+            follow_latest: false,
+            quality_score: Some(80),
+            category: "climate".to_string(),
+            tags: vec!["weather".to_string()],
+            preview_data: None,
+            preview: None,
+            preview_options: None,
+            status: ListingStatus::Active,
+            created_at: 0,
+            updated_at: 0,
+            view_count: 0,
+            favorite_count: 0,
+        }
+    }
+
+    fn search(query: Option<&str>, max_price: Option<u64>) -> SearchListingsRequest {
+        SearchListingsRequest {
+            query: query.map(str::to_string),
+            category: None,
+            tags: None,
+            min_price: None,
+            max_price,
+            currency: None,
+            seller: None,
+            status: None,
+            sort_by: None,
+            page: None,
+            page_size: None,
+            cursor: None,
+            min_quality: None,
+        }
+    }
+
+    #[test]
+    fn test_listing_matches_saved_search() {
+        let listing = sample_listing();
+
+        assert!(listing_matches_search(
+            &listing,
+            &search(Some("weather"), None)
+        ));
+        assert!(listing_matches_search(&listing, &search(None, Some(2_000))));
+        assert!(!listing_matches_search(&listing, &search(None, Some(500))));
+        assert!(!listing_matches_search(
+            &listing,
+            &search(Some("traffic"), None)
+        ));
+        let quality = |min_quality| SearchListingsRequest {
+            min_quality: Some(min_quality),
+            ..search(None, None)
+        };
+        assert!(listing_matches_search(&listing, &quality(80)));
+        assert!(!listing_matches_search(&listing, &quality(81)));
+
+        let mut suspended = listing;
+        suspended.status = ListingStatus::Suspended;
+        assert!(!listing_matches_search(
+            &suspended,
+            &search(Some("weather"), None)
+        ));
+    }
+
+    #[test]
+    fn test_cooccurrence_model_ranks_shared_favorites() {
+        let alice = Principal::from_slice(&[1]);
+        let bob = Principal::from_slice(&[2]);
+        let carol = Principal::from_slice(&[3]);
+
+        let interactions = vec![
+            (alice, 1, InteractionKind::Favorite),
+            (alice, 2, InteractionKind::Favorite),
+            (bob, 1, InteractionKind::Favorite),
+            (bob, 2, InteractionKind::Favorite),
+            (carol, 1, InteractionKind::View),
+            (carol, 3, InteractionKind::View),
+        ];
+
+        let model = build_cooccurrence_model(&interactions);
+        let neighbors = &model[&1];
+
+        assert_eq!(neighbors[0].listing_id, 2);
+        assert_eq!(neighbors[0].shared_favorites, 2);
+        assert_eq!(
+            neighbors[0].dominant_reason(),
+            RecommendationReason::CoFavorited
+        );
+        assert_eq!(neighbors[1].listing_id, 3);
+        assert_eq!(
+            neighbors[1].dominant_reason(),
+            RecommendationReason::CoViewed
+        );
+        assert!(neighbors[0].score > neighbors[1].score);
+        assert!(!model.contains_key(&4));
+    }
+
+    #[test]
+    fn test_record_search_query_ignores_empty_queries() {
+        assert!(!record_search_query("  ", 0));
+        assert!(!record_search_query("!", 0));
+    }
+
+    #[test]
+    fn test_record_view_dedupes_within_window() {
+        let seller = Principal::from_slice(&[7]);
+        let listing = Listing {
+            seller,
+            ..sample_listing()
+        };
+        LISTINGS.with(|storage| storage.borrow_mut().insert(listing.id, listing.clone()));
+
+        let viewer = Principal::from_slice(&[8]);
+        let now = 100 * DAY_NANOS;
+        assert_eq!(
+            record_view(Principal::anonymous(), listing.id, now),
+            Ok(false)
+        );
+        assert_eq!(record_view(viewer, listing.id, now), Ok(true));
+        assert_eq!(record_view(viewer, listing.id, now + 60), Ok(false));
+        assert_eq!(record_view(seller, listing.id, now), Ok(false));
+        assert_eq!(
+            record_view(viewer, listing.id, now + VIEW_DEDUPE_WINDOW_NANOS),
+            Ok(true)
+        );
+
+        let stats = get_listing_view_stats(listing.id, 7, now).unwrap();
+        assert_eq!(stats.total_views, 2);
+        assert_eq!(stats.daily_views[0].views, 2);
+        assert_eq!(get_listing_readonly(listing.id).unwrap().updated_at, 0);
+    }
+
+    #[test]
+    fn test_migrate_legacy_favorites() {
+        let alice = Principal::from_slice(&[1]);
+        let bob = Principal::from_slice(&[2]);
+        let listing = Listing {
+            id: 77,
+            favorite_count: 5,
+            ..sample_listing()
+        };
+        LISTINGS.with(|storage| storage.borrow_mut().insert(listing.id, listing.clone()));
+
+        LEGACY_FAVORITES.with(|storage| {
+            let mut storage = storage.borrow_mut();
+            for (legacy_id, user, created_at) in [(10, alice, 1), (11, bob, 2), (12, alice, 3)] {
+                storage.insert(
+                    legacy_id,
+                    Favorite {
+                        user,
+                        listing_id: listing.id,
+                        created_at,
+                    },
+                );
+            }
+        });
+
+        assert_eq!(migrate_legacy_favorites(), 3);
+        assert_eq!(migrate_legacy_favorites(), 0);
+
+        assert!(is_favorited(alice, listing.id));
+        assert!(is_favorited(bob, listing.id));
+        let favoriters = get_listing_favoriters(listing.id);
+        assert_eq!(favoriters.len(), 2);
+        assert_eq!(favoriters[0].user, alice);
+        assert_eq!(get_listing_readonly(listing.id).unwrap().favorite_count, 2);
+    }
+
+    #[test]
+    fn test_migrated_activities_page_newest_first() {
+        let user = Principal::from_slice(&[3]);
+        LEGACY_ACTIVITY_LOGS.with(|storage| {
+            let mut storage = storage.borrow_mut();
+            // 해시 키 순서와 시각 순서가 다른 이전 데이터
+            for (legacy_id, timestamp) in [(900, 10), (5, 30), (42, 20)] {
+                storage.insert(
+                    legacy_id,
+                    ActivityLog {
+                        timestamp,
+                        activity_type: ActivityType::ListingViewed,
+                        user,
+                        listing_id: Some(1),
+                        details: String::new(),
+                    },
+                );
+            }
+        });
+
+        assert_eq!(migrate_legacy_activities(), 3);
+
+        let first = get_recent_activities_page(2, None);
+        let timestamps: Vec<u64> = first.activities.iter().map(|a| a.timestamp).collect();
+        assert_eq!(timestamps, vec![30, 20]);
+
+        let cursor =
+            PageCursor::decode_scoped(&first.next_cursor.unwrap(), PageCursor::ACTIVITY_SCOPE)
+                .unwrap();
+        let second = get_recent_activities_page(2, Some(cursor));
+        assert_eq!(second.activities.len(), 1);
+        assert_eq!(second.activities[0].timestamp, 10);
+        assert!(second.next_cursor.is_none());
+    }
+
+    #[test]
+    fn test_archive_keeps_indexes_and_export_in_order() {
+        let user = Principal::from_slice(&[4]);
+        for timestamp in 1..=5 {
+            insert_activity(ActivityLog {
+                timestamp,
+                activity_type: if timestamp % 2 == 0 {
+                    ActivityType::ListingViewed
+                } else {
+                    ActivityType::ListingFavorited
+                },
+                user,
+                listing_id: Some(9),
+                details: String::new(),
+            });
+        }
+
+        set_activity_retention_policy(ActivityRetentionPolicy {
+            max_entries: 2,
+            max_age_days: 1,
+        })
+        .unwrap();
+        assert_eq!(archive_activities(5), 3);
+
+        let status = get_activity_log_status();
+        assert_eq!((status.live_count, status.archived_count), (2, 3));
+
+        let listing_page = get_listing_activities(9, None, 10, None);
+        let timestamps: Vec<u64> = listing_page
+            .activities
+            .iter()
+            .map(|a| a.timestamp)
+            .collect();
+        assert_eq!(timestamps, vec![5, 4]);
+
+        let viewed = get_user_activities(user, Some(ActivityType::ListingViewed), 10, None);
+        assert_eq!(viewed.activities.len(), 1);
+        assert_eq!(viewed.activities[0].timestamp, 4);
+
+        let export = export_activities(ExportFormat::Cbor, None, 4).unwrap();
+        let exported: Vec<ActivityLog> = serde_cbor::from_slice(&export.data).unwrap();
+        let timestamps: Vec<u64> = exported.iter().map(|a| a.timestamp).collect();
+        assert_eq!(timestamps, vec![1, 2, 3, 4]);
+
+        let rest = export_activities(ExportFormat::JsonLines, export.next_from_id, 4).unwrap();
+        assert_eq!(rest.count, 1);
+        assert!(rest.next_from_id.is_none());
+        assert_eq!(String::from_utf8(rest.data).unwrap().lines().count(), 1);
+    }
+}
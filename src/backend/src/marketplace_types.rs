@@ -1,8 +1,10 @@
 // backend/src/marketplace_types.rs
 
+use crate::error::BackendError;
 use candid::{CandidType, Deserialize, Principal};
 use ic_stable_structures::Storable;
 use serde::Serialize;
+use sha2::Digest;
 use std::borrow::Cow;
 
 // =====================
@@ -14,12 +16,14 @@ use std::borrow::Cow;
 pub struct CreateListingRequest {
     pub title: String,
     pub description: String,
-    pub price: u64,                   // ICP 단위 (e8s)
-    pub currency: String,             // "ICP", "USD" 등
-    pub data_ids: Vec<u64>,           // 판매할 데이터 ID들
-    pub category: String,             // 카테고리
-    pub tags: Vec<String>,            // 태그들
-    pub preview_data: Option<String>, // 미리보기 데이터 (JSON 문자열)
+    pub price: u64,                                            // ICP 단위 (e8s)
+    pub currency: String,                                      // "ICP", "USD" 등
+    pub dataset_ids: Vec<u64>,                                 // 판매할 데이터셋 ID들 (판매자 소유)
+    pub follow_latest: Option<bool>, // true면 데이터셋의 최신 버전을 판매 (기본: 지정 버전 고정)
+    pub category: String,            // 카테고리
+    pub tags: Vec<String>,           // 태그들
+    pub preview_data: Option<String>, // 판매자 메모 (자동 생성 미리보기와 함께 표시)
+    pub preview_options: Option<crate::types::PreviewOptions>, // 자동 미리보기 옵션 (기본: 전체 컬럼 5행)
 }
 
 /// 판매글 업데이트 요청
@@ -34,6 +38,8 @@ pub struct UpdateListingRequest {
     pub tags: Option<Vec<String>>,
     pub preview_data: Option<String>,
     pub status: Option<ListingStatus>,
+    pub follow_latest: Option<bool>,
+    pub preview_options: Option<crate::types::PreviewOptions>, // 지정 시 자동 미리보기 재생성
 }
 
 /// 판매글 상태
@@ -54,10 +60,20 @@ pub struct Listing {
     pub description: String,
     pub price: u64,
     pub currency: String,
-    pub data_ids: Vec<u64>,
+    pub data_ids: Vec<u64>, // 제공 중인 데이터셋들의 레코드 ID (최신 버전을 따르면 새 버전 게시 때 갱신)
+    #[serde(default)]
+    pub dataset_ids: Vec<u64>, // 판매하는 데이터셋 (데이터셋 도입 전 판매글은 비어 있음)
+    #[serde(default)]
+    pub follow_latest: bool, // true면 dataset_ids 대신 각 데이터셋의 최신 버전을 제공
+    #[serde(default)]
+    pub quality_score: Option<u8>, // 제공 중인 데이터셋 품질 점수의 레코드 수 가중 평균 (0-100, 새 버전 게시 때 갱신)
     pub category: String,
     pub tags: Vec<String>,
     pub preview_data: Option<String>,
+    #[serde(default)]
+    pub preview: Option<crate::types::DataPreview>, // 데이터셋 레코드에서 자동 생성한 미리보기
+    #[serde(default)]
+    pub preview_options: Option<crate::types::PreviewOptions>, // 새 버전 게시 때 미리보기 재생성용
     pub status: ListingStatus,
     pub created_at: u64,
     pub updated_at: u64,
@@ -81,7 +97,9 @@ pub struct ListingSummary {
     pub updated_at: u64,
     pub view_count: u64,
     pub favorite_count: u64,
-    pub data_count: u64, // 포함된 데이터 개수
+    pub data_count: u64,    // 포함된 데이터 개수
+    pub dataset_count: u64, // 포함된 데이터셋 개수
+    pub quality_score: Option<u8>,
 }
 
 /// 판매글 상세 정보 (조회용)
@@ -89,6 +107,8 @@ pub struct ListingSummary {
 pub struct ListingDetail {
     pub listing: Listing,
     pub data_info: Vec<crate::types::DataInfo>, // 기존 types.rs의 DataInfo 사용
+    pub datasets: Vec<crate::types::DatasetInfo>, // 데이터셋별 품질 보고서 포함
+    pub quality_score: Option<u8>,              // 제공 중인 데이터셋 기준 품질 점수
 }
 
 // =====================
@@ -96,7 +116,7 @@ pub struct ListingDetail {
 // =====================
 
 /// 판매글 검색 요청
-#[derive(CandidType, Deserialize, Clone)]
+#[derive(CandidType, Deserialize, Serialize, Clone)]
 pub struct SearchListingsRequest {
     pub query: Option<String>,         // 검색어 (제목, 설명에서 검색)
     pub category: Option<String>,      // 카테고리 필터
@@ -109,10 +129,13 @@ pub struct SearchListingsRequest {
     pub sort_by: Option<SortBy>,       // 정렬 기준
     pub page: Option<u64>,             // 페이지 번호 (0부터 시작)
     pub page_size: Option<u64>,        // 페이지 크기 (기본 20)
+    pub cursor: Option<String>,        // 이어보기 커서 (지정 시 page 무시)
+    #[serde(default)]
+    pub min_quality: Option<u8>, // 최소 품질 점수 (0-100)
 }
 
 /// 정렬 기준
-#[derive(CandidType, Deserialize, Clone)]
+#[derive(CandidType, Deserialize, Serialize, Clone)]
 pub enum SortBy {
     CreatedAtDesc,     // 생성일 내림차순 (최신순)
     CreatedAtAsc,      // 생성일 오름차순
@@ -123,6 +146,26 @@ pub enum SortBy {
     UpdatedAtDesc,     // 수정일 내림차순
 }
 
+/// 인기 검색어 집계 구간 (현재 시각 기준 최근 1시간/24시간/7일)
+#[derive(CandidType, Deserialize, Clone)]
+pub enum TrendingWindow {
+    Hour,
+    Day,
+    Week,
+}
+
+impl TrendingWindow {
+    /// 구간 길이 (나노초)
+    pub fn as_nanos(&self) -> u64 {
+        const HOUR: u64 = 60 * 60 * 1_000_000_000;
+        match self {
+            TrendingWindow::Hour => HOUR,
+            TrendingWindow::Day => 24 * HOUR,
+            TrendingWindow::Week => 7 * 24 * HOUR,
+        }
+    }
+}
+
 /// 검색 결과
 #[derive(CandidType, Deserialize)]
 pub struct SearchResult {
@@ -131,6 +174,25 @@ pub struct SearchResult {
     pub page: u64,
     pub page_size: u64,
     pub total_pages: u64,
+    pub next_cursor: Option<String>, // 다음 페이지 커서 (마지막 페이지면 None)
+}
+
+/// 판매글 페이지 (커서 기반 목록 조회용)
+#[derive(CandidType, Deserialize)]
+pub struct ListingPage {
+    pub listings: Vec<ListingSummary>,
+    pub next_cursor: Option<String>,
+}
+
+/// 페이지 커서 (정렬 키 + ID, 외부에는 불투명한 문자열로 노출)
+///
+/// scope는 커서를 발급한 목록(정렬 기준과 검색 조건)을 나타내며,
+/// 다른 목록에서 발급된 커서는 받지 않는다.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct PageCursor {
+    pub key: u64,
+    pub id: u64,
+    pub scope: u64,
 }
 
 // =====================
@@ -196,8 +258,15 @@ pub struct ActivityLog {
     pub details: String,
 }
 
+/// 활동 로그 페이지 (커서 기반 조회용)
+#[derive(CandidType, Deserialize)]
+pub struct ActivityPage {
+    pub activities: Vec<ActivityLog>,
+    pub next_cursor: Option<String>,
+}
+
 /// 활동 타입
-#[derive(CandidType, Deserialize, Serialize, Clone)]
+#[derive(CandidType, Deserialize, Serialize, Clone, PartialEq, Debug)]
 pub enum ActivityType {
     ListingCreated,
     ListingUpdated,
@@ -215,6 +284,7 @@ pub enum ActivityType {
 #[derive(CandidType, Deserialize)]
 pub struct CreateListingResponse {
     pub listing_id: u64,
+    pub similar_datasets: Vec<crate::types::SimilarityMatch>, // 유사한 민팅 데이터셋 경고
 }
 
 /// 일반적인 성공 응답
@@ -266,51 +336,93 @@ impl Storable for ActivityLog {
         ic_stable_structures::storable::Bound::Unbounded;
 }
 
+/// 검색어 집계 키 (시간 버킷 시작 시각 + 키워드)
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct SearchTermKey {
+    pub bucket: u64,
+    pub term: String,
+}
+
+impl Storable for SearchTermKey {
+    // 버킷을 빅엔디언으로 앞에 두어 바이트 순서와 (bucket, term) 순서를 일치시킴
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        let mut bytes = self.bucket.to_be_bytes().to_vec();
+        bytes.extend_from_slice(self.term.as_bytes());
+        Cow::Owned(bytes)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        let mut bucket = [0u8; 8];
+        bucket.copy_from_slice(&bytes[..8]);
+        SearchTermKey {
+            bucket: u64::from_be_bytes(bucket),
+            term: String::from_utf8_lossy(&bytes[8..]).into_owned(),
+        }
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound =
+        ic_stable_structures::storable::Bound::Unbounded;
+}
+
+impl SearchTermKey {
+    /// 버킷 내 가장 앞선 키 (범위 조회용)
+    pub fn bucket_start(bucket: u64) -> Self {
+        SearchTermKey {
+            bucket,
+            term: String::new(),
+        }
+    }
+}
+
 // =====================
 // 7) 검증 함수들
 // =====================
 
 impl CreateListingRequest {
-    pub fn validate(&self) -> Result<(), String> {
+    pub fn validate(&self) -> Result<(), BackendError> {
         if self.title.trim().is_empty() {
-            return Err("제목은 필수입니다".to_string());
+            return Err(BackendError::validation("title", "required"));
         }
 
         if self.title.len() > 200 {
-            return Err("제목은 200자를 초과할 수 없습니다".to_string());
+            return Err(BackendError::too_long("title", self.title.len(), 200));
         }
 
         if self.description.trim().is_empty() {
-            return Err("설명은 필수입니다".to_string());
+            return Err(BackendError::validation("description", "required"));
         }
 
         if self.description.len() > 10000 {
-            return Err("설명은 10,000자를 초과할 수 없습니다".to_string());
+            return Err(BackendError::too_long(
+                "description",
+                self.description.len(),
+                10000,
+            ));
         }
 
         if self.price == 0 {
-            return Err("가격은 0보다 커야 합니다".to_string());
+            return Err(BackendError::validation("price", "must_be_positive"));
         }
 
-        if self.data_ids.is_empty() {
-            return Err("판매할 데이터가 최소 하나는 있어야 합니다".to_string());
+        if self.dataset_ids.is_empty() {
+            return Err(BackendError::validation("dataset_ids", "empty"));
         }
 
-        if self.data_ids.len() > 100 {
-            return Err("한 번에 판매할 수 있는 데이터는 최대 100개입니다".to_string());
+        if self.dataset_ids.len() > 100 {
+            return Err(BackendError::quota_exceeded("dataset_ids", 100));
         }
 
         if self.category.trim().is_empty() {
-            return Err("카테고리는 필수입니다".to_string());
+            return Err(BackendError::validation("category", "required"));
         }
 
         if self.tags.len() > 20 {
-            return Err("태그는 최대 20개까지 가능합니다".to_string());
+            return Err(BackendError::quota_exceeded("tags", 20));
         }
 
         for tag in &self.tags {
             if tag.len() > 50 {
-                return Err("각 태그는 50자를 초과할 수 없습니다".to_string());
+                return Err(BackendError::too_long("tags", tag.len(), 50));
             }
         }
 
@@ -319,38 +431,42 @@ impl CreateListingRequest {
 }
 
 impl UpdateListingRequest {
-    pub fn validate(&self) -> Result<(), String> {
+    pub fn validate(&self) -> Result<(), BackendError> {
         if let Some(ref title) = self.title {
             if title.trim().is_empty() {
-                return Err("제목은 비어있을 수 없습니다".to_string());
+                return Err(BackendError::validation("title", "empty"));
             }
             if title.len() > 200 {
-                return Err("제목은 200자를 초과할 수 없습니다".to_string());
+                return Err(BackendError::too_long("title", title.len(), 200));
             }
         }
 
         if let Some(ref description) = self.description {
             if description.trim().is_empty() {
-                return Err("설명은 비어있을 수 없습니다".to_string());
+                return Err(BackendError::validation("description", "empty"));
             }
             if description.len() > 10000 {
-                return Err("설명은 10,000자를 초과할 수 없습니다".to_string());
+                return Err(BackendError::too_long(
+                    "description",
+                    description.len(),
+                    10000,
+                ));
             }
         }
 
         if let Some(price) = self.price {
             if price == 0 {
-                return Err("가격은 0보다 커야 합니다".to_string());
+                return Err(BackendError::validation("price", "must_be_positive"));
             }
         }
 
         if let Some(ref tags) = self.tags {
             if tags.len() > 20 {
-                return Err("태그는 최대 20개까지 가능합니다".to_string());
+                return Err(BackendError::quota_exceeded("tags", 20));
             }
             for tag in tags {
                 if tag.len() > 50 {
-                    return Err("각 태그는 50자를 초과할 수 없습니다".to_string());
+                    return Err(BackendError::too_long("tags", tag.len(), 50));
                 }
             }
         }
@@ -360,21 +476,34 @@ impl UpdateListingRequest {
 }
 
 impl SearchListingsRequest {
-    pub fn validate(&self) -> Result<(), String> {
+    pub fn validate(&self) -> Result<(), BackendError> {
         if let Some(page_size) = self.page_size {
             if page_size == 0 || page_size > 100 {
-                return Err("페이지 크기는 1-100 사이여야 합니다".to_string());
+                return Err(BackendError::out_of_range("page_size", page_size, 1, 100));
             }
         }
 
         if let Some(min_price) = self.min_price {
             if let Some(max_price) = self.max_price {
                 if min_price > max_price {
-                    return Err("최소 가격은 최대 가격보다 클 수 없습니다".to_string());
+                    return Err(BackendError::validation("min_price", "exceeds_max_price"));
                 }
             }
         }
 
+        if let Some(ref cursor) = self.cursor {
+            PageCursor::decode_scoped(cursor, self.cursor_scope())?;
+        }
+
+        if let Some(min_quality) = self.min_quality.filter(|&min_quality| min_quality > 100) {
+            return Err(BackendError::out_of_range(
+                "min_quality",
+                min_quality,
+                0,
+                100,
+            ));
+        }
+
         Ok(())
     }
 
@@ -389,4 +518,374 @@ impl SearchListingsRequest {
     pub fn get_sort_by(&self) -> SortBy {
         self.sort_by.clone().unwrap_or(SortBy::CreatedAtDesc)
     }
+
+    /// 검색 커서 범위 (정렬 기준 + 검색 조건, 페이지 위치는 제외)
+    pub fn cursor_scope(&self) -> u64 {
+        let conditions = SearchListingsRequest {
+            sort_by: Some(self.get_sort_by()),
+            page: None,
+            page_size: None,
+            cursor: None,
+            ..self.clone()
+        };
+        PageCursor::scope_of(&conditions)
+    }
+}
+
+impl PageCursor {
+    /// 활동 로그 목록 커서 범위 (순번 순서라 조건과 무관)
+    pub const ACTIVITY_SCOPE: u64 = 1;
+    /// 알림 목록 커서 범위
+    pub const NOTIFICATION_SCOPE: u64 = 2;
+
+    /// 목록 조건을 커서 범위 값으로 요약 (CBOR 인코딩의 SHA-256 앞 8바이트)
+    pub fn scope_of<T: Serialize>(conditions: &T) -> u64 {
+        let encoded = serde_cbor::to_vec(conditions).unwrap_or_default();
+        let digest = sha2::Sha256::digest(&encoded);
+        u64::from_be_bytes(digest[..8].try_into().unwrap())
+    }
+
+    /// 커서를 디코딩하고 같은 목록에서 발급된 것인지 확인
+    pub fn decode_scoped(cursor: &str, scope: u64) -> Result<Self, BackendError> {
+        let cursor = Self::decode(cursor)?;
+        if cursor.scope != scope {
+            return Err(BackendError::validation("cursor", "scope_mismatch"));
+        }
+        Ok(cursor)
+    }
+
+    /// 커서를 불투명한 문자열로 인코딩 (CBOR + hex)
+    pub fn encode(&self) -> String {
+        hex::encode(serde_cbor::to_vec(self).unwrap())
+    }
+
+    /// 클라이언트가 전달한 커서 문자열 디코딩
+    pub fn decode(cursor: &str) -> Result<Self, BackendError> {
+        hex::decode(cursor)
+            .ok()
+            .and_then(|bytes| serde_cbor::from_slice(&bytes).ok())
+            .ok_or_else(|| BackendError::validation("cursor", "invalid_format"))
+    }
+
+    /// 정렬 순서상 이 커서 다음에 오는 항목인지 확인
+    pub fn precedes(&self, other: &PageCursor, descending: bool) -> bool {
+        if descending {
+            other < self
+        } else {
+            other > self
+        }
+    }
+}
+
+// =====================
+// 8) 저장된 검색 및 알림
+// =====================
+
+/// 검색 저장 요청
+#[derive(CandidType, Deserialize, Clone)]
+pub struct SaveSearchRequest {
+    pub name: String,
+    pub search: SearchListingsRequest,
+}
+
+/// 저장된 검색
+#[derive(CandidType, Deserialize, Serialize, Clone)]
+pub struct SavedSearch {
+    pub id: u64,
+    pub owner: Principal,
+    pub name: String,
+    pub search: SearchListingsRequest,
+    pub created_at: u64,
+}
+
+/// 알림 종류
+#[derive(CandidType, Deserialize, Serialize, Clone, PartialEq, Debug)]
+pub enum NotificationKind {
+    NewListingMatch,     // 새 판매글이 저장된 검색과 일치
+    UpdatedListingMatch, // 수정된 판매글이 새로 저장된 검색과 일치
+}
+
+/// 사용자 알림
+#[derive(CandidType, Deserialize, Serialize, Clone)]
+pub struct Notification {
+    pub id: u64,
+    pub kind: NotificationKind,
+    pub saved_search_id: u64,
+    pub saved_search_name: String,
+    pub listing_id: u64,
+    pub listing_title: String,
+    pub created_at: u64,
+    pub read: bool,
+}
+
+/// 알림 페이지 (커서 기반 조회용)
+#[derive(CandidType, Deserialize)]
+pub struct NotificationPage {
+    pub notifications: Vec<Notification>,
+    pub unread_count: u64,
+    pub next_cursor: Option<String>,
+}
+
+impl Storable for SavedSearch {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(serde_cbor::to_vec(self).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).unwrap()
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound =
+        ic_stable_structures::storable::Bound::Unbounded;
+}
+
+impl Storable for Notification {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(serde_cbor::to_vec(self).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).unwrap()
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound =
+        ic_stable_structures::storable::Bound::Unbounded;
+}
+
+impl SaveSearchRequest {
+    pub fn validate(&self) -> Result<(), BackendError> {
+        if self.name.trim().is_empty() {
+            return Err(BackendError::validation("name", "required"));
+        }
+
+        if self.name.len() > 100 {
+            return Err(BackendError::too_long("name", self.name.len(), 100));
+        }
+
+        self.search.validate()
+    }
+}
+
+// =====================
+// 9) 추천 관련 타입
+// =====================
+
+/// 추천 사유
+#[derive(CandidType, Deserialize, Clone, PartialEq, Debug)]
+pub enum RecommendationReason {
+    CoPurchased,    // 함께 구매된 판매글
+    CoFavorited,    // 함께 즐겨찾기된 판매글
+    CoViewed,       // 함께 조회된 판매글
+    SimilarContent, // 키워드/카테고리가 비슷한 판매글
+    Trending,       // 인기 판매글
+}
+
+/// 추천 결과
+#[derive(CandidType, Deserialize)]
+pub struct Recommendation {
+    pub listing: ListingSummary,
+    pub score: f64,
+    pub reason: RecommendationReason,
+    pub source_listing_id: Option<u64>, // 추천 근거가 된 판매글
+    pub explanation: String,
+}
+
+/// 함께 상호작용된 판매글 (추천 모델 내부 저장용)
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ItemNeighbor {
+    pub listing_id: u64,
+    pub score: f64,
+    pub shared_purchases: u32,
+    pub shared_favorites: u32,
+    pub shared_views: u32,
+}
+
+impl ItemNeighbor {
+    /// 가장 강한 공통 신호 (구매 > 즐겨찾기 > 조회 순으로 우선)
+    pub fn dominant_reason(&self) -> RecommendationReason {
+        if self.shared_purchases > 0 && self.shared_purchases >= self.shared_favorites {
+            RecommendationReason::CoPurchased
+        } else if self.shared_favorites > 0 && self.shared_favorites >= self.shared_views {
+            RecommendationReason::CoFavorited
+        } else {
+            RecommendationReason::CoViewed
+        }
+    }
+}
+
+/// 판매글별 유사 판매글 목록 (추천 모델 내부 저장용)
+#[derive(Deserialize, Serialize, Clone)]
+pub struct SimilarListings {
+    pub neighbors: Vec<ItemNeighbor>,
+    pub updated_at: u64,
+}
+
+impl Storable for SimilarListings {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(serde_cbor::to_vec(self).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).unwrap()
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound =
+        ic_stable_structures::storable::Bound::Unbounded;
+}
+
+// =====================
+// 10) 조회수 관련 타입
+// =====================
+
+/// 일별 조회수
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct DailyViewCount {
+    pub day_start: u64, // 해당 일의 시작 시각 (ns, UTC)
+    pub views: u64,
+}
+
+/// 판매글 조회수 통계
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ListingViewStats {
+    pub listing_id: u64,
+    pub total_views: u64,
+    pub daily_views: Vec<DailyViewCount>, // 최근 일자부터
+}
+
+// =====================
+// 11) 활동 로그 보존 및 내보내기
+// =====================
+
+/// 활동 로그 보존 정책 (초과분은 아카이브로 이동)
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct ActivityRetentionPolicy {
+    pub max_entries: u64,  // 활성 로그 최대 개수
+    pub max_age_days: u64, // 활성 로그 최대 보관 일수
+}
+
+impl Default for ActivityRetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_entries: 100_000,
+            max_age_days: 90,
+        }
+    }
+}
+
+impl Storable for ActivityRetentionPolicy {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(serde_cbor::to_vec(self).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).unwrap()
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound =
+        ic_stable_structures::storable::Bound::Unbounded;
+}
+
+impl ActivityRetentionPolicy {
+    pub fn validate(&self) -> Result<(), BackendError> {
+        if self.max_entries == 0 {
+            return Err(BackendError::validation("max_entries", "must_be_positive"));
+        }
+        if self.max_age_days == 0 {
+            return Err(BackendError::validation("max_age_days", "must_be_positive"));
+        }
+        Ok(())
+    }
+}
+
+/// 활동 로그 저장 현황
+#[derive(CandidType, Deserialize)]
+pub struct ActivityLogStatus {
+    pub live_count: u64,
+    pub archived_count: u64,
+    pub policy: ActivityRetentionPolicy,
+}
+
+/// 활동 로그 내보내기 형식
+#[derive(CandidType, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum ExportFormat {
+    Cbor,      // 활동 로그 배열의 CBOR 인코딩
+    JsonLines, // 한 줄에 활동 로그 하나씩 JSON
+}
+
+/// 활동 로그 내보내기 결과 (순번 기준으로 이어받기)
+#[derive(CandidType, Deserialize)]
+pub struct ActivityExport {
+    pub format: ExportFormat,
+    pub data: Vec<u8>,
+    pub count: u64,
+    pub next_from_id: Option<u64>,
+}
+
+// =====================
+// 12) 구매 기록
+// =====================
+
+/// 구매 기록 (판매자가 판매 완료를 기록할 때 생성)
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct Purchase {
+    pub listing_id: u64,
+    pub buyer: Principal,
+    pub seller: Principal,
+    pub dataset_ids: Vec<u64>, // 구매 시점에 판매글이 제공한 데이터셋 (버전)
+    pub price: u64,
+    pub currency: String,
+    pub purchased_at: u64,
+}
+
+impl Storable for Purchase {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(serde_cbor::to_vec(self).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).unwrap()
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound =
+        ic_stable_structures::storable::Bound::Unbounded;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(key: u64, id: u64) -> PageCursor {
+        PageCursor { key, id, scope: 7 }
+    }
+
+    #[test]
+    fn test_page_cursor_roundtrip() {
+        let cursor = at(1_700_000_000, 42);
+        let encoded = cursor.encode();
+        assert_eq!(PageCursor::decode(&encoded), Ok(cursor));
+        assert_eq!(PageCursor::decode_scoped(&encoded, 7), Ok(cursor));
+        assert_eq!(
+            PageCursor::decode_scoped(&encoded, 8),
+            Err(BackendError::validation("cursor", "scope_mismatch"))
+        );
+    }
+
+    #[test]
+    fn test_page_cursor_rejects_garbage() {
+        assert!(PageCursor::decode("not-a-cursor").is_err());
+        assert!(PageCursor::decode("").is_err());
+    }
+
+    #[test]
+    fn test_page_cursor_ordering() {
+        let cursor = at(100, 5);
+        // 내림차순: 같은 키에서는 더 작은 ID가 다음 항목
+        assert!(cursor.precedes(&at(100, 4), true));
+        assert!(cursor.precedes(&at(99, 9), true));
+        assert!(!cursor.precedes(&at(100, 5), true));
+        assert!(!cursor.precedes(&at(101, 1), true));
+        // 오름차순
+        assert!(cursor.precedes(&at(100, 6), false));
+        assert!(!cursor.precedes(&at(99, 9), false));
+    }
 }
@@ -0,0 +1,212 @@
+// backend/src/merkle.rs
+
+use crate::types::{ProofSide, ProofStep, RecordProof};
+use sha2::{Digest, Sha256};
+
+// =====================
+// 1) 머클 루트
+// =====================
+
+/// 레코드 해시(SHA-256) 목록의 머클 루트
+///
+/// 잎은 `SHA-256(0x00 || 레코드 해시)`, 내부 노드는 `SHA-256(0x01 || 왼쪽 || 오른쪽)`으로
+/// 구분해 내부 노드 값을 레코드로 내세운 위조를 막는다. 짝이 없는 마지막 노드는
+/// 복제하지 않고 그대로 다음 단계로 올린다. 레코드가 없으면 빈 입력의 SHA-256.
+pub fn merkle_root(record_hashes: &[[u8; 32]]) -> [u8; 32] {
+    if record_hashes.is_empty() {
+        return Sha256::digest([]).into();
+    }
+
+    let mut level = leaves(record_hashes);
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level[0]
+}
+
+// =====================
+// 2) 포함 증명
+// =====================
+
+/// index번째 잎의 포함 경로 (형제 해시와 위치, 잎에서 루트 방향)
+///
+/// 짝이 없어 그대로 올라가는 단계는 경로에 포함하지 않는다.
+pub fn merkle_path(record_hashes: &[[u8; 32]], mut index: usize) -> Vec<([u8; 32], ProofSide)> {
+    let mut path = Vec::new();
+    let mut level = leaves(record_hashes);
+    while level.len() > 1 {
+        let sibling = index ^ 1;
+        if let Some(hash) = level.get(sibling) {
+            let side = if sibling < index {
+                ProofSide::Left
+            } else {
+                ProofSide::Right
+            };
+            path.push((*hash, side));
+        }
+        level = next_level(&level);
+        index /= 2;
+    }
+    path
+}
+
+/// 레코드 바이트와 증명으로 포함 여부 확인 (캐니스터 상태를 읽지 않음)
+///
+/// 경로 길이와 방향이 잎 위치와 레코드 수로 정해지는 모양과 같아야 한다.
+/// 같은 계산을 클라이언트에서 그대로 재현할 수 있다.
+pub fn verify_record_proof(record: &[u8], proof: &RecordProof) -> bool {
+    let leaf = hash_leaf(&Sha256::digest(record).into());
+    if hex::encode(leaf) != proof.leaf_hash {
+        return false;
+    }
+
+    let Some(sides) = path_sides(proof.leaf_index, proof.record_count) else {
+        return false;
+    };
+    if sides.len() != proof.path.len()
+        || sides
+            .iter()
+            .zip(&proof.path)
+            .any(|(side, step)| *side != step.side)
+    {
+        return false;
+    }
+
+    let mut current = leaf;
+    for step in &proof.path {
+        let Some(sibling) = decode_hash(&step.hash) else {
+            return false;
+        };
+        current = match step.side {
+            ProofSide::Left => hash_node(&sibling, &current),
+            ProofSide::Right => hash_node(&current, &sibling),
+        };
+    }
+    hex::encode(current) == proof.root_hash
+}
+
+/// 경로를 응답용 단계 목록으로 변환
+pub fn to_proof_steps(path: Vec<([u8; 32], ProofSide)>) -> Vec<ProofStep> {
+    path.into_iter()
+        .map(|(hash, side)| ProofStep {
+            hash: hex::encode(hash),
+            side,
+        })
+        .collect()
+}
+
+/// 잎 위치와 레코드 수로 정해지는 경로의 형제 방향 (위치가 범위를 벗어나면 None)
+fn path_sides(leaf_index: u64, record_count: u64) -> Option<Vec<ProofSide>> {
+    if leaf_index >= record_count {
+        return None;
+    }
+
+    let (mut index, mut width) = (leaf_index, record_count);
+    let mut sides = Vec::new();
+    while width > 1 {
+        let sibling = index ^ 1;
+        if sibling < width {
+            sides.push(if sibling < index {
+                ProofSide::Left
+            } else {
+                ProofSide::Right
+            });
+        }
+        width = width.div_ceil(2);
+        index /= 2;
+    }
+    Some(sides)
+}
+
+fn decode_hash(value: &str) -> Option<[u8; 32]> {
+    hex::decode(value).ok()?.try_into().ok()
+}
+
+/// 한 단계 위 노드들 (짝이 없는 마지막 노드는 그대로 올림)
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => hash_node(left, right),
+            [single] => *single,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+/// 잎 해시
+pub fn hash_leaf(record_hash: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(record_hash);
+    hasher.finalize().into()
+}
+
+fn leaves(record_hashes: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    record_hashes.iter().map(hash_leaf).collect()
+}
+
+/// 내부 노드 해시
+fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x01]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merkle_root_promotes_odd_node() {
+        let hashes = [[1u8; 32], [2u8; 32], [3u8; 32]];
+        let leaves = leaves(&hashes);
+        let expected = hash_node(&hash_node(&leaves[0], &leaves[1]), &leaves[2]);
+        assert_eq!(merkle_root(&hashes), expected);
+        assert_eq!(merkle_root(&hashes[..1]), leaves[0]);
+    }
+
+    #[test]
+    fn test_record_proof_roundtrip() {
+        let records: Vec<Vec<u8>> = (0..5u8).map(|i| vec![i; 12]).collect();
+        let hashes: Vec<[u8; 32]> = records
+            .iter()
+            .map(|record| Sha256::digest(record).into())
+            .collect();
+        let root = hex::encode(merkle_root(&hashes));
+
+        for (index, record) in records.iter().enumerate() {
+            let proof = RecordProof {
+                dataset_id: 1,
+                record_id: index as u64 + 1,
+                leaf_index: index as u64,
+                record_count: 5,
+                leaf_hash: hex::encode(hash_leaf(&hashes[index])),
+                path: to_proof_steps(merkle_path(&hashes, index)),
+                root_hash: root.clone(),
+            };
+            assert!(verify_record_proof(record, &proof));
+            assert!(!verify_record_proof(&records[(index + 1) % 5], &proof));
+        }
+
+        // 내부 노드 (0x01 || 왼쪽 || 오른쪽)를 레코드로 내세운 짧은 경로는 거부
+        let leaves = leaves(&hashes);
+        let mut forged = vec![0x01];
+        forged.extend_from_slice(&leaves[0]);
+        forged.extend_from_slice(&leaves[1]);
+        let mut path = to_proof_steps(merkle_path(&hashes, 0));
+        path.remove(0);
+        let proof = RecordProof {
+            dataset_id: 1,
+            record_id: 1,
+            leaf_index: 0,
+            record_count: 3,
+            leaf_hash: hex::encode(hash_leaf(&Sha256::digest(&forged).into())),
+            path,
+            root_hash: root,
+        };
+        assert!(!verify_record_proof(&forged, &proof));
+    }
+}
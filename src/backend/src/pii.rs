@@ -0,0 +1,491 @@
+// backend/src/pii.rs
+
+use crate::schema;
+use crate::types::{PiiFinding, PiiKind, PiiMode, PiiReport};
+use serde_cbor::value::Value as CborValue;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::ops::Range;
+
+/// 보고서에 남기는 필드별 레코드 인덱스 수
+const MAX_SAMPLE_RECORDS: usize = 5;
+
+/// HMAC-SHA256 블록 크기
+const HMAC_BLOCK_SIZE: usize = 64;
+
+// =====================
+// 1) 레코드 검사
+// =====================
+
+/// 레코드의 값에서 개인정보를 찾아 보고서 작성 (Redact/Hash 모드면 일치한 부분만 치환)
+///
+/// 배열 레코드의 필드 이름은 스키마 추론과 같은 규칙(헤더 또는 "column_N")을 따른다.
+/// 정수 값은 카드 번호만 검사한다 (전화번호는 앞자리 0이 사라져 구분할 수 없음).
+/// Hash 모드는 `hash_key`로 HMAC을 계산하며, 키가 없으면 Redact와 같이 치환한다.
+pub fn scan_records(
+    records: &mut [CborValue],
+    mode: PiiMode,
+    hash_key: Option<&[u8; 32]>,
+) -> PiiReport {
+    let header = schema::detect_header(records);
+    let skip = usize::from(header.is_some());
+    let mut findings: BTreeMap<(String, PiiKind), Vec<u64>> = BTreeMap::new();
+    let mut records_affected = 0;
+
+    for (index, record) in records.iter_mut().enumerate().skip(skip) {
+        let mut found = Vec::new();
+        match record {
+            CborValue::Array(items) => {
+                for (column, item) in items.iter_mut().enumerate() {
+                    let name = schema::column_name(header.as_deref(), column);
+                    scan_value(item, &name, mode, hash_key, &mut found);
+                }
+            }
+            CborValue::Map(_) => scan_value(record, "", mode, hash_key, &mut found),
+            other => scan_value(other, "value", mode, hash_key, &mut found),
+        }
+
+        if found.is_empty() {
+            continue;
+        }
+        records_affected += 1;
+        found.sort();
+        found.dedup();
+        for key in found {
+            findings.entry(key).or_default().push(index as u64);
+        }
+    }
+
+    PiiReport {
+        mode,
+        records_affected,
+        findings: findings
+            .into_iter()
+            .map(|((field, kind), indices)| PiiFinding {
+                field,
+                kind,
+                record_count: indices.len() as u64,
+                sample_records: indices.into_iter().take(MAX_SAMPLE_RECORDS).collect(),
+            })
+            .collect(),
+    }
+}
+
+/// 값 하나를 재귀적으로 검사 (path는 맵 키를 "."으로 이은 경로)
+fn scan_value(
+    value: &mut CborValue,
+    path: &str,
+    mode: PiiMode,
+    hash_key: Option<&[u8; 32]>,
+    found: &mut Vec<(String, PiiKind)>,
+) {
+    match value {
+        CborValue::Map(fields) => {
+            for (key, child) in fields.iter_mut() {
+                let name = crate::http::csv_field(key.clone());
+                let child_path = if path.is_empty() {
+                    name
+                } else {
+                    format!("{}.{}", path, name)
+                };
+                scan_value(child, &child_path, mode, hash_key, found);
+            }
+        }
+        CborValue::Array(items) => {
+            for item in items {
+                scan_value(item, path, mode, hash_key, found);
+            }
+        }
+        CborValue::Text(text) => {
+            let matches = find_pii(text);
+            found.extend(matches.iter().map(|(kind, _)| (path.to_string(), *kind)));
+            if let Some(replaced) = replace_matches(text, &matches, mode, hash_key) {
+                *text = replaced;
+            }
+        }
+        CborValue::Integer(number) => {
+            let text = number.to_string();
+            if is_card_number(&text) {
+                found.push((path.to_string(), PiiKind::CardNumber));
+                let matches = [(PiiKind::CardNumber, 0..text.len())];
+                if let Some(replaced) = replace_matches(&text, &matches, mode, hash_key) {
+                    *value = CborValue::Text(replaced);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 일치한 부분 치환 (치환하지 않는 모드이거나 일치가 없으면 None)
+fn replace_matches(
+    text: &str,
+    matches: &[(PiiKind, Range<usize>)],
+    mode: PiiMode,
+    hash_key: Option<&[u8; 32]>,
+) -> Option<String> {
+    if matches.is_empty() || matches!(mode, PiiMode::Reject | PiiMode::Warn) {
+        return None;
+    }
+
+    let mut replaced = String::with_capacity(text.len());
+    let mut last = 0;
+    for (kind, range) in matches {
+        replaced.push_str(&text[last..range.start]);
+        match (mode, hash_key) {
+            (PiiMode::Hash, Some(key)) => {
+                let digest = hmac_sha256(key, text[range.clone()].as_bytes());
+                replaced.push_str(&format!("hmac:{}", hex::encode(&digest[..16])));
+            }
+            _ => replaced.push_str(placeholder(*kind)),
+        }
+        last = range.end;
+    }
+    replaced.push_str(&text[last..]);
+    Some(replaced)
+}
+
+/// HMAC-SHA256 (RFC 2104)
+///
+/// 키 없이 해시하면 전화번호·주민등록번호처럼 값의 범위가 좁은 개인정보는
+/// 전수 대입으로 원문을 복원할 수 있으므로, 캐니스터만 아는 키로 계산한다.
+fn hmac_sha256(key: &[u8; 32], message: &[u8]) -> [u8; 32] {
+    let mut ipad = [0x36u8; HMAC_BLOCK_SIZE];
+    let mut opad = [0x5cu8; HMAC_BLOCK_SIZE];
+    for (index, byte) in key.iter().enumerate() {
+        ipad[index] ^= byte;
+        opad[index] ^= byte;
+    }
+
+    let inner = Sha256::new()
+        .chain_update(ipad)
+        .chain_update(message)
+        .finalize();
+    Sha256::new()
+        .chain_update(opad)
+        .chain_update(inner)
+        .finalize()
+        .into()
+}
+
+fn placeholder(kind: PiiKind) -> &'static str {
+    match kind {
+        PiiKind::Email => "[EMAIL]",
+        PiiKind::Phone => "[PHONE]",
+        PiiKind::ResidentRegistrationNumber => "[RRN]",
+        PiiKind::CardNumber => "[CARD]",
+        PiiKind::IpAddress => "[IP]",
+    }
+}
+
+// =====================
+// 2) 패턴 탐지
+// =====================
+
+/// 텍스트에서 개인정보 위치 찾기 (겹치면 앞선/긴 쪽 우선, 시작 위치 순)
+pub fn find_pii(text: &str) -> Vec<(PiiKind, Range<usize>)> {
+    let mut candidates = Vec::new();
+
+    let email_char = |c: char| c.is_ascii_alphanumeric() || "._%+-@".contains(c);
+    for range in runs(text, email_char) {
+        let range = trim(text, range, |c| ".-+".contains(c), |c| ".-".contains(c));
+        if is_email(&text[range.clone()]) {
+            candidates.push((PiiKind::Email, range));
+        }
+    }
+
+    let ip_char = |c: char| c.is_ascii_hexdigit() || c == '.' || c == ':';
+    for range in runs(text, ip_char) {
+        let range = trim(text, range, |c| c == '.', |c| c == '.');
+        if bounded(text, &range) && is_ip_address(&text[range.clone()]) {
+            candidates.push((PiiKind::IpAddress, range));
+        }
+    }
+
+    let number_char = |c: char| c.is_ascii_digit() || "-+.() ".contains(c);
+    for range in runs(text, number_char) {
+        let range = trim_number(text, range);
+        if range.is_empty() || !bounded(text, &range) {
+            continue;
+        }
+        // 공백으로 나뉜 번호(카드/전화)를 먼저 보고, 아니면 공백 단위로 나눠 검사
+        if let Some(kind) = classify_number(&text[range.clone()]) {
+            candidates.push((kind, range));
+            continue;
+        }
+        let mut offset = range.start;
+        for token in text[range.clone()].split(' ') {
+            let token_range = trim_number(text, offset..offset + token.len());
+            offset += token.len() + 1;
+            if let Some(kind) = classify_number(&text[token_range.clone()]) {
+                candidates.push((kind, token_range));
+            }
+        }
+    }
+
+    candidates.sort_by_key(|(_, range)| (range.start, std::cmp::Reverse(range.end)));
+    let mut matches: Vec<(PiiKind, Range<usize>)> = Vec::new();
+    for (kind, range) in candidates {
+        if matches
+            .last()
+            .is_none_or(|(_, last)| range.start >= last.end)
+        {
+            matches.push((kind, range));
+        }
+    }
+    matches
+}
+
+/// 숫자 조각 분류 (주민등록번호 > 카드 번호 > 전화번호 순)
+fn classify_number(text: &str) -> Option<PiiKind> {
+    if is_resident_registration_number(text) {
+        Some(PiiKind::ResidentRegistrationNumber)
+    } else if is_card_number(text) {
+        Some(PiiKind::CardNumber)
+    } else if is_phone_number(text) {
+        Some(PiiKind::Phone)
+    } else {
+        None
+    }
+}
+
+fn is_email(text: &str) -> bool {
+    let Some((local, domain)) = text.split_once('@') else {
+        return false;
+    };
+    let labels: Vec<&str> = domain.split('.').collect();
+    !local.is_empty()
+        && !domain.contains('@')
+        && labels.len() >= 2
+        && labels.iter().all(|label| !label.is_empty())
+        && labels
+            .last()
+            .is_some_and(|tld| tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic()))
+}
+
+/// IPv4 또는 콜론이 2개 이상인 IPv6 (시각 "12:30" 등과 구분)
+fn is_ip_address(text: &str) -> bool {
+    text.parse::<Ipv4Addr>().is_ok()
+        || (text.matches(':').count() >= 2 && text.parse::<Ipv6Addr>().is_ok())
+}
+
+/// 주민등록번호 (YYMMDD-GNNNNNN, 하이픈 필수)
+fn is_resident_registration_number(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    if bytes.len() != 14 || bytes[6] != b'-' {
+        return false;
+    }
+    if !bytes
+        .iter()
+        .enumerate()
+        .all(|(i, b)| i == 6 || b.is_ascii_digit())
+    {
+        return false;
+    }
+    let two = |i: usize| (bytes[i] - b'0') * 10 + (bytes[i + 1] - b'0');
+    (1..=12).contains(&two(2)) && (1..=31).contains(&two(4)) && (b'1'..=b'8').contains(&bytes[7])
+}
+
+/// 카드 번호 (13-19자리, 공백/하이픈 구분 허용, 주요 브랜드 첫 자리, Luhn 검사)
+fn is_card_number(text: &str) -> bool {
+    if !text
+        .chars()
+        .all(|c| c.is_ascii_digit() || c == ' ' || c == '-')
+        || has_double_separator(text)
+    {
+        return false;
+    }
+    let digits: Vec<u32> = text.chars().filter_map(|c| c.to_digit(10)).collect();
+    if !(13..=19).contains(&digits.len()) || !(2..=6).contains(&digits[0]) {
+        return false;
+    }
+
+    let checksum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &digit)| match (i % 2, digit * 2) {
+            (1, doubled) if doubled > 9 => doubled - 9,
+            (1, doubled) => doubled,
+            _ => digit,
+        })
+        .sum();
+    checksum.is_multiple_of(10)
+}
+
+/// 전화번호 (국내 0으로 시작 9-11자리 또는 +국가번호 10-15자리)
+///
+/// 구분자가 없으면 휴대전화(01X, 10-11자리)만 인정한다.
+fn is_phone_number(text: &str) -> bool {
+    let rest = text.strip_prefix('+');
+    if rest.is_some_and(|rest| rest.contains('+')) || (rest.is_none() && text.contains('+')) {
+        return false;
+    }
+    if has_double_separator(text) {
+        return false;
+    }
+
+    let digits: String = text.chars().filter(char::is_ascii_digit).collect();
+    let separated = digits.len() != text.len() - usize::from(rest.is_some());
+    match rest {
+        Some(_) => (10..=15).contains(&digits.len()),
+        None if digits.starts_with('0') => {
+            (9..=11).contains(&digits.len())
+                && (separated || (digits.starts_with("01") && digits.len() >= 10))
+        }
+        None => false,
+    }
+}
+
+fn has_double_separator(text: &str) -> bool {
+    text.as_bytes().windows(2).any(|pair| {
+        !pair[0].is_ascii_digit() && !pair[1].is_ascii_digit() && pair != b") " && pair != b" ("
+    })
+}
+
+// =====================
+// 3) 구간 헬퍼
+// =====================
+
+/// 조건을 만족하는 문자가 이어진 구간들 (바이트 위치)
+fn runs(text: &str, accept: impl Fn(char) -> bool) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (accept(c), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                ranges.push(s..i);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        ranges.push(s..text.len());
+    }
+    ranges
+}
+
+/// 구간 앞뒤에서 조건에 맞는 (ASCII) 문자 제거
+fn trim(
+    text: &str,
+    mut range: Range<usize>,
+    leading: impl Fn(char) -> bool,
+    trailing: impl Fn(char) -> bool,
+) -> Range<usize> {
+    let bytes = text.as_bytes();
+    while range.start < range.end && leading(bytes[range.start] as char) {
+        range.start += 1;
+    }
+    while range.end > range.start && trailing(bytes[range.end - 1] as char) {
+        range.end -= 1;
+    }
+    range
+}
+
+/// 번호 구간 정리 (앞은 숫자/"+"/"(", 뒤는 숫자로 끝나게)
+fn trim_number(text: &str, range: Range<usize>) -> Range<usize> {
+    trim(
+        text,
+        range,
+        |c| !(c.is_ascii_digit() || c == '+' || c == '('),
+        |c| !c.is_ascii_digit(),
+    )
+}
+
+/// 구간 바로 앞뒤가 영숫자가 아닌지 (단어 일부가 아닌지)
+fn bounded(text: &str, range: &Range<usize>) -> bool {
+    let before = text[..range.start].chars().next_back();
+    let after = text[range.end..].chars().next();
+    !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(text: &str) -> Vec<(PiiKind, &str)> {
+        find_pii(text)
+            .into_iter()
+            .map(|(kind, range)| (kind, &text[range]))
+            .collect()
+    }
+
+    #[test]
+    fn test_find_pii_patterns() {
+        assert_eq!(
+            kinds("문의: Kim.J@example.co.kr, 010-1234-5678 또는 02) 123 4567"),
+            vec![
+                (PiiKind::Email, "Kim.J@example.co.kr"),
+                (PiiKind::Phone, "010-1234-5678"),
+                (PiiKind::Phone, "02) 123 4567"),
+            ]
+        );
+        assert_eq!(
+            kinds("900101-1234567 / 4111 1111 1111 1111 / +82 10 1234 5678"),
+            vec![
+                (PiiKind::ResidentRegistrationNumber, "900101-1234567"),
+                (PiiKind::CardNumber, "4111 1111 1111 1111"),
+                (PiiKind::Phone, "+82 10 1234 5678"),
+            ]
+        );
+        assert_eq!(
+            kinds("from 192.168.0.1 and fe80::1"),
+            vec![
+                (PiiKind::IpAddress, "192.168.0.1"),
+                (PiiKind::IpAddress, "fe80::1"),
+            ]
+        );
+        // 날짜, 시각, 금액, 버전 문자열은 개인정보가 아님
+        assert!(kinds("2024-01-15 12:30:45 1,234,567원 v1.2.3.4 4111111111111112").is_empty());
+    }
+
+    #[test]
+    fn test_scan_records_redacts_and_hashes() {
+        let mut records = vec![serde_cbor::value::to_value(serde_json::json!({
+            "contact": {"email": "a@b.io"},
+            "note": "call 010-9876-5432 today",
+            "score": 10
+        }))
+        .unwrap()];
+
+        let report = scan_records(&mut records.clone(), PiiMode::Warn, None);
+        assert_eq!(report.records_affected, 1);
+        assert_eq!(
+            report
+                .findings
+                .iter()
+                .map(|finding| (finding.field.as_str(), finding.kind))
+                .collect::<Vec<_>>(),
+            vec![("contact.email", PiiKind::Email), ("note", PiiKind::Phone)]
+        );
+
+        scan_records(&mut records, PiiMode::Redact, None);
+        let json = serde_json::to_value(&records[0]).unwrap();
+        assert_eq!(json["note"], "call [PHONE] today");
+        assert_eq!(json["contact"]["email"], "[EMAIL]");
+
+        let hash = |key: &[u8; 32]| {
+            let mut hashed = vec![CborValue::Text("a@b.io".to_string())];
+            scan_records(&mut hashed, PiiMode::Hash, Some(key));
+            hashed.remove(0)
+        };
+        let hashed = hash(&[1; 32]);
+        assert!(matches!(&hashed, CborValue::Text(text) if text.starts_with("hmac:")));
+        assert_eq!(hashed, hash(&[1; 32]));
+        assert_ne!(hashed, hash(&[2; 32]));
+    }
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231 테스트 케이스 2 (키는 블록 크기까지 0으로 채우므로 32바이트로 늘려도 같음)
+        let mut key = [0u8; 32];
+        key[..4].copy_from_slice(b"Jefe");
+        assert_eq!(
+            hex::encode(hmac_sha256(&key, b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
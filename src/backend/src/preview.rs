@@ -0,0 +1,366 @@
+// backend/src/preview.rs
+
+use crate::error::BackendError;
+use crate::http::csv_field;
+use crate::schema;
+use crate::storage;
+use crate::types::{ColumnSummary, ColumnType, DataPreview, DatasetSchema, PreviewOptions};
+use serde_cbor::value::Value as CborValue;
+use std::collections::HashSet;
+
+// =====================
+// 1) 미리보기 제한
+// =====================
+
+const DEFAULT_PREVIEW_ROWS: u32 = 5;
+const MAX_PREVIEW_ROWS: u32 = 20;
+
+/// 통계 계산에 읽는 최대 레코드 수 (데이터셋 전체 합계)
+const MAX_SAMPLED_ROWS: usize = 5_000;
+
+/// 미리보기 셀 최대 길이 (문자)
+const MAX_CELL_LENGTH: usize = 200;
+
+/// 스키마와 레코드 (헤더 행 제외)
+type Table = (DatasetSchema, Vec<CborValue>);
+
+// =====================
+// 2) 데이터셋 미리보기
+// =====================
+
+/// 데이터셋들의 저장된 레코드로 미리보기 생성
+///
+/// 바이너리 자산 데이터셋은 레코드가 없으므로 건너뛴다 (자산 썸네일/설명 사용).
+pub fn preview_datasets(
+    dataset_ids: &[u64],
+    options: &PreviewOptions,
+) -> Result<DataPreview, BackendError> {
+    let mut tables = Vec::new();
+    let mut total_rows = 0;
+    let mut remaining = MAX_SAMPLED_ROWS;
+
+    for &dataset_id in dataset_ids {
+        let dataset = storage::get_dataset(dataset_id)
+            .ok_or_else(|| BackendError::not_found("dataset", dataset_id))?;
+        let Some(schema) = dataset.schema_id.and_then(storage::get_schema) else {
+            continue;
+        };
+
+        // 헤더 행까지 포함해 읽은 뒤 제외
+        let records = storage::dataset_records(&dataset, remaining + 1);
+        let body = schema::skip_header(&schema, &records);
+        let header_rows = records.len() - body.len();
+        let body: Vec<CborValue> = body.iter().take(remaining).cloned().collect();
+
+        total_rows += (dataset.record_ids.len() - header_rows) as u64;
+        remaining -= body.len();
+        tables.push((schema, body));
+    }
+
+    build_preview(&tables, total_rows, options)
+}
+
+/// 스키마별 레코드로 미리보기 구성
+///
+/// 여러 데이터셋의 컬럼은 처음 나온 순서대로 합치고, 같은 이름의 타입이 다르면 합친 타입을 쓴다.
+fn build_preview(
+    tables: &[Table],
+    total_rows: u64,
+    options: &PreviewOptions,
+) -> Result<DataPreview, BackendError> {
+    let row_limit = options.row_limit.unwrap_or(DEFAULT_PREVIEW_ROWS);
+    if row_limit == 0 || row_limit > MAX_PREVIEW_ROWS {
+        return Err(BackendError::out_of_range(
+            "row_limit",
+            row_limit,
+            1,
+            u64::from(MAX_PREVIEW_ROWS),
+        ));
+    }
+
+    let mut all_columns: Vec<(String, ColumnType)> = Vec::new();
+    for column in tables.iter().flat_map(|(schema, _)| &schema.columns) {
+        match all_columns
+            .iter_mut()
+            .find(|(name, _)| *name == column.name)
+        {
+            Some((_, column_type)) => {
+                *column_type = schema::merge_types(*column_type, column.column_type)
+            }
+            None => all_columns.push((column.name.clone(), column.column_type)),
+        }
+    }
+
+    let known = |field: &str, names: &Option<Vec<String>>| -> Result<(), BackendError> {
+        match names {
+            Some(names)
+                if !names
+                    .iter()
+                    .all(|name| all_columns.iter().any(|(c, _)| c == name)) =>
+            {
+                Err(BackendError::validation(field, "unknown_column"))
+            }
+            _ => Ok(()),
+        }
+    };
+    known("visible_columns", &options.visible_columns)?;
+    known("masked_columns", &options.masked_columns)?;
+
+    let columns: Vec<(String, ColumnType)> = match &options.visible_columns {
+        Some(visible) => all_columns
+            .into_iter()
+            .filter(|(name, _)| visible.contains(name))
+            .collect(),
+        None => all_columns,
+    };
+    let masked: Vec<bool> = columns
+        .iter()
+        .map(|(name, _)| {
+            options
+                .masked_columns
+                .as_ref()
+                .is_some_and(|masked| masked.contains(name))
+        })
+        .collect();
+
+    // 레코드마다 공개 컬럼 값 (테이블마다 자기 스키마의 위치로 찾는다)
+    let rows: Vec<Vec<Option<&CborValue>>> = tables
+        .iter()
+        .flat_map(|(schema, records)| {
+            records.iter().map(|record| {
+                columns
+                    .iter()
+                    .map(|(name, _)| {
+                        let index = schema.columns.iter().position(|c| c.name == *name)?;
+                        schema::column_value(record, name, index)
+                    })
+                    .collect()
+            })
+        })
+        .collect();
+
+    let preview_rows = rows
+        .iter()
+        .take(row_limit as usize)
+        .map(|row| {
+            row.iter()
+                .zip(&masked)
+                .map(|(value, &masked)| {
+                    let text = value.map(|v| cell_text(v.clone())).unwrap_or_default();
+                    if masked {
+                        mask(&text)
+                    } else {
+                        text
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    let column_stats = columns
+        .iter()
+        .enumerate()
+        .map(|(index, (name, column_type))| {
+            let values: Vec<&CborValue> = rows.iter().filter_map(|row| row[index]).collect();
+            summarize(name, *column_type, &values, masked[index])
+        })
+        .collect();
+
+    Ok(DataPreview {
+        columns: columns.into_iter().map(|(name, _)| name).collect(),
+        rows: preview_rows,
+        column_stats,
+        total_rows,
+        sampled_rows: rows.len() as u64,
+    })
+}
+
+// =====================
+// 3) 컬럼 통계 및 마스킹
+// =====================
+
+/// 컬럼 요약 (가린 컬럼은 개수만)
+fn summarize(
+    name: &str,
+    column_type: ColumnType,
+    values: &[&CborValue],
+    masked: bool,
+) -> ColumnSummary {
+    let texts: Vec<String> = values
+        .iter()
+        .map(|value| cell_text((*value).clone()))
+        .filter(|text| !text.trim().is_empty())
+        .collect();
+    let distinct_count = texts.iter().collect::<HashSet<_>>().len() as u64;
+
+    let (min, max, mean) = match column_type {
+        _ if masked => (None, None, None),
+        ColumnType::Integer | ColumnType::Float => {
+            let numbers: Vec<f64> = texts
+                .iter()
+                .filter_map(|text| text.trim().parse::<f64>().ok())
+                .collect();
+            let min = numbers.iter().copied().reduce(f64::min);
+            let max = numbers.iter().copied().reduce(f64::max);
+            let mean =
+                (!numbers.is_empty()).then(|| numbers.iter().sum::<f64>() / numbers.len() as f64);
+            (min.map(format_number), max.map(format_number), mean)
+        }
+        ColumnType::Date => (
+            texts.iter().min().cloned(),
+            texts.iter().max().cloned(),
+            None,
+        ),
+        _ => (None, None, None),
+    };
+
+    ColumnSummary {
+        name: name.to_string(),
+        column_type,
+        filled_count: texts.len() as u64,
+        distinct_count,
+        min,
+        max,
+        mean,
+        masked,
+    }
+}
+
+/// 미리보기 셀 문자열 (길면 잘라냄)
+fn cell_text(value: CborValue) -> String {
+    let text = csv_field(value);
+    if text.chars().count() > MAX_CELL_LENGTH {
+        let truncated: String = text.chars().take(MAX_CELL_LENGTH - 3).collect();
+        format!("{}...", truncated)
+    } else {
+        text
+    }
+}
+
+/// 레코드에서 가린 컬럼의 값을 미리보기와 같이 가림 (CSV 헤더 행은 그대로)
+pub fn mask_record(record: &mut CborValue, schema: &DatasetSchema, masked_columns: &[String]) {
+    let is_masked = |name: &str| masked_columns.iter().any(|masked| masked == name);
+    match record {
+        CborValue::Map(fields) => {
+            for (key, value) in fields.iter_mut() {
+                if matches!(key, CborValue::Text(name) if is_masked(name)) {
+                    mask_value(value);
+                }
+            }
+        }
+        CborValue::Array(items) => {
+            let is_header = schema.has_header
+                && items.len() == schema.columns.len()
+                && items.iter().zip(&schema.columns).all(
+                    |(item, column)| matches!(item, CborValue::Text(text) if *text == column.name),
+                );
+            if is_header {
+                return;
+            }
+            for (item, column) in items.iter_mut().zip(&schema.columns) {
+                if is_masked(&column.name) {
+                    mask_value(item);
+                }
+            }
+        }
+        other => {
+            if schema.columns.iter().any(|column| is_masked(&column.name)) {
+                mask_value(other);
+            }
+        }
+    }
+}
+
+fn mask_value(value: &mut CborValue) {
+    if *value != CborValue::Null {
+        *value = CborValue::Text(mask(&csv_field(value.clone())));
+    }
+}
+
+/// 첫 글자만 남기고 가림
+pub fn mask(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => std::iter::once(first).chain(chars.map(|_| '*')).collect(),
+        None => String::new(),
+    }
+}
+
+fn format_number(number: f64) -> String {
+    if number.fract() == 0.0 && number.abs() < 1e15 {
+        (number as i64).to_string()
+    } else {
+        number.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_masks_and_hides_columns() {
+        let records: Vec<CborValue> = serde_json::from_str::<Vec<serde_json::Value>>(
+            r#"[
+                {"email": "alice@example.com", "age": 31, "city": "Seoul"},
+                {"email": "bob@example.com", "age": 45, "city": "Busan"},
+                {"email": "carol@example.com", "age": 28, "city": "Seoul"}
+            ]"#,
+        )
+        .unwrap()
+        .into_iter()
+        .map(|value| serde_cbor::value::to_value(value).unwrap())
+        .collect();
+        let tables = vec![(schema::infer_schema(&records), records)];
+
+        let options = PreviewOptions {
+            row_limit: Some(2),
+            visible_columns: Some(vec!["email".to_string(), "age".to_string()]),
+            masked_columns: Some(vec!["email".to_string()]),
+        };
+        let preview = build_preview(&tables, 3, &options).unwrap();
+        assert_eq!(preview.columns, vec!["age", "email"]);
+        assert_eq!(preview.rows[0], vec!["31", "a****************"]);
+        assert_eq!(preview.rows.len(), 2);
+
+        let age = &preview.column_stats[0];
+        assert_eq!(
+            (age.min.as_deref(), age.max.as_deref()),
+            (Some("28"), Some("45"))
+        );
+        assert_eq!(age.mean, Some(104.0 / 3.0));
+        assert_eq!(preview.column_stats[1].min, None);
+        assert_eq!(preview.column_stats[1].distinct_count, 3);
+
+        let unknown = PreviewOptions {
+            masked_columns: Some(vec!["phone".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            build_preview(&tables, 3, &unknown),
+            Err(BackendError::validation("masked_columns", "unknown_column"))
+        );
+    }
+
+    #[test]
+    fn test_mask_record_keeps_header() {
+        let text = |value: &str| CborValue::Text(value.to_string());
+        let records = vec![
+            CborValue::Array(vec![text("name"), text("email")]),
+            CborValue::Array(vec![text("Kim"), text("kim@example.com")]),
+        ];
+        let schema = schema::infer_schema(&records);
+        let masked = ["email".to_string()];
+
+        let mut header = records[0].clone();
+        mask_record(&mut header, &schema, &masked);
+        assert_eq!(header, records[0]);
+
+        let mut row = records[1].clone();
+        mask_record(&mut row, &schema, &masked);
+        assert_eq!(
+            row,
+            CborValue::Array(vec![text("Kim"), text("k**************")])
+        );
+    }
+}
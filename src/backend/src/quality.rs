@@ -0,0 +1,200 @@
+// backend/src/quality.rs
+
+use crate::schema;
+use crate::types::{ColumnQuality, ColumnType, DatasetSchema, QualityReport};
+use serde_cbor::value::Value as CborValue;
+use std::collections::{HashMap, HashSet};
+
+// =====================
+// 1) 점수 가중치
+// =====================
+
+/// 종합 점수 가중치 (합계 100)
+const COMPLETENESS_WEIGHT: u64 = 35;
+const CONSISTENCY_WEIGHT: u64 = 30;
+const UNIQUENESS_WEIGHT: u64 = 20;
+const OUTLIER_WEIGHT: u64 = 10;
+const SIZE_WEIGHT: u64 = 5;
+
+/// 이 행 수 이상이면 크기 점수 만점
+const FULL_SIZE_ROWS: u64 = 100;
+
+/// 이상치 판단에 필요한 최소 숫자 값 개수
+const MIN_OUTLIER_SAMPLES: usize = 4;
+
+// =====================
+// 2) 품질 평가
+// =====================
+
+/// 업로드 레코드의 품질 지표와 0-100 종합 점수 계산
+///
+/// - 완전성: 스키마 컬럼 중 비어 있지 않은 셀 비율
+/// - 타입 일관성: 컬럼마다 가장 많은 타입(정수/실수는 숫자로 묶음)에 속한 값 비율
+/// - 중복률: 공백/대소문자/컬럼 순서를 정규화했을 때 겹치는 행 비율
+/// - 이상치 비율: 숫자 컬럼에서 IQR 1.5배 범위를 벗어난 값 비율
+pub fn assess(records: &[CborValue], schema: &DatasetSchema) -> QualityReport {
+    // 배열 레코드의 헤더 행은 평가에서 제외 (스키마 추론과 같은 기준)
+    let body = schema::skip_header(schema, records);
+    let row_count = body.len() as u64;
+
+    let columns: Vec<ColumnQuality> = schema
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            let values: Vec<Option<&CborValue>> = body
+                .iter()
+                .map(|record| schema::column_value(record, &column.name, index))
+                .collect();
+            assess_column(&column.name, &values)
+        })
+        .collect();
+
+    let total_cells = row_count * columns.len() as u64;
+    let filled_cells: u64 = columns.iter().map(|column| column.filled_count).sum();
+    let typed_cells: u64 = columns.iter().map(|column| column.consistent_count).sum();
+    let numeric_cells: u64 = columns.iter().map(|column| column.numeric_count).sum();
+    let outliers: u64 = columns.iter().map(|column| column.outlier_count).sum();
+
+    let unique_rows = body
+        .iter()
+        .map(crate::fingerprint::normalize_record)
+        .collect::<HashSet<_>>()
+        .len() as u64;
+
+    let completeness_percent = percent(filled_cells, total_cells, 100);
+    let type_consistency_percent = percent(typed_cells, filled_cells, 100);
+    let duplicate_rate_percent = percent(row_count - unique_rows, row_count, 0);
+    let outlier_ratio_percent = percent(outliers, numeric_cells, 0);
+    let size_percent = percent(row_count.min(FULL_SIZE_ROWS), FULL_SIZE_ROWS, 0);
+
+    let weighted = u64::from(completeness_percent) * COMPLETENESS_WEIGHT
+        + u64::from(type_consistency_percent) * CONSISTENCY_WEIGHT
+        + u64::from(100 - duplicate_rate_percent) * UNIQUENESS_WEIGHT
+        + u64::from(100 - outlier_ratio_percent) * OUTLIER_WEIGHT
+        + u64::from(size_percent) * SIZE_WEIGHT;
+
+    QualityReport {
+        score: (weighted / 100) as u8,
+        row_count,
+        completeness_percent,
+        type_consistency_percent,
+        duplicate_rate_percent,
+        outlier_ratio_percent,
+        columns,
+    }
+}
+
+/// 컬럼 하나의 지표
+///
+/// 숫자 값이 가장 많은 컬럼을 숫자 컬럼으로 보고 이상치를 센다
+/// (일부 값이 문자열이라 스키마 타입이 String이 되어도 포함).
+fn assess_column(name: &str, values: &[Option<&CborValue>]) -> ColumnQuality {
+    let mut type_counts: HashMap<ColumnType, u64> = HashMap::new();
+    let mut numbers = Vec::new();
+
+    for value in values.iter().flatten() {
+        let Some(value_type) = schema::infer_value_type(value) else {
+            continue;
+        };
+        // 정수와 실수는 같은 숫자 타입으로 본다
+        let class = match value_type {
+            ColumnType::Integer => ColumnType::Float,
+            other => other,
+        };
+        *type_counts.entry(class).or_insert(0) += 1;
+        if class == ColumnType::Float {
+            numbers.extend(numeric_value(value));
+        }
+    }
+
+    let filled_count: u64 = type_counts.values().sum();
+    let consistent_count = type_counts.values().copied().max().unwrap_or(0);
+    let numeric = consistent_count > 0
+        && type_counts.get(&ColumnType::Float).copied() == Some(consistent_count);
+    let outlier_count = if numeric {
+        count_outliers(&mut numbers)
+    } else {
+        0
+    };
+
+    ColumnQuality {
+        name: name.to_string(),
+        completeness_percent: percent(filled_count, values.len() as u64, 100),
+        type_consistency_percent: percent(consistent_count, filled_count, 100),
+        filled_count,
+        consistent_count,
+        numeric_count: if numeric { numbers.len() as u64 } else { 0 },
+        outlier_count,
+    }
+}
+
+fn numeric_value(value: &CborValue) -> Option<f64> {
+    match value {
+        CborValue::Integer(number) => Some(*number as f64),
+        CborValue::Float(number) => Some(*number),
+        CborValue::Text(text) => text.trim().parse::<f64>().ok(),
+        _ => None,
+    }
+}
+
+/// IQR 1.5배 범위를 벗어난 값 개수
+fn count_outliers(numbers: &mut [f64]) -> u64 {
+    if numbers.len() < MIN_OUTLIER_SAMPLES {
+        return 0;
+    }
+    numbers.sort_by(f64::total_cmp);
+
+    let quantile = |q: f64| {
+        let position = q * (numbers.len() - 1) as f64;
+        let lower = numbers[position.floor() as usize];
+        let upper = numbers[position.ceil() as usize];
+        lower + (upper - lower) * position.fract()
+    };
+    let (q1, q3) = (quantile(0.25), quantile(0.75));
+    let margin = (q3 - q1) * 1.5;
+
+    numbers
+        .iter()
+        .filter(|&&value| value < q1 - margin || value > q3 + margin)
+        .count() as u64
+}
+
+/// 비율(%) 계산 (분모가 0이면 기본값)
+fn percent(part: u64, total: u64, default: u8) -> u8 {
+    (part.min(total) * 100)
+        .checked_div(total)
+        .map_or(default, |value| value as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quality_report_metrics() {
+        let records: Vec<CborValue> = serde_json::from_str::<Vec<serde_json::Value>>(
+            r#"[
+                {"name": "a", "value": 10},
+                {"name": "b", "value": 11},
+                {"name": "c", "value": 12},
+                {"name": "C ", "value": 12},
+                {"name": "", "value": 1000},
+                {"name": "e", "value": "n/a"}
+            ]"#,
+        )
+        .unwrap()
+        .into_iter()
+        .map(|value| serde_cbor::value::to_value(value).unwrap())
+        .collect();
+        let schema = schema::infer_schema(&records);
+
+        let report = assess(&records, &schema);
+        assert_eq!(report.row_count, 6);
+        assert_eq!(report.completeness_percent, 91); // 12칸 중 11칸
+        assert_eq!(report.duplicate_rate_percent, 16); // "c"와 "C "는 같은 행
+        assert_eq!(report.columns[1].outlier_count, 1); // 1000
+        assert_eq!(report.columns[1].type_consistency_percent, 83); // "n/a"
+        assert!(report.score > 50 && report.score < 100);
+    }
+}
@@ -0,0 +1,549 @@
+// backend/src/query.rs
+
+use crate::error::BackendError;
+use crate::http::csv_field;
+use crate::marketplace_storage;
+use crate::preview;
+use crate::schema;
+use crate::storage;
+use crate::types::{
+    AggregateFunction, DatasetQuery, DatasetSchema, FilterOp, QueryAggregate, QueryResult,
+    QueryValue,
+};
+use candid::Principal;
+use serde_cbor::value::Value as CborValue;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+// =====================
+// 1) 쿼리 제한
+// =====================
+
+const DEFAULT_LIMIT: u32 = 100;
+const MAX_LIMIT: u32 = 1_000;
+const MAX_FILTERS: usize = 20;
+
+// =====================
+// 2) 쿼리 실행
+// =====================
+
+/// 저장된 CBOR 레코드에 대해 데이터셋 쿼리 실행
+///
+/// 데이터셋 소유자나 구매자만 쿼리할 수 있고, 구매자에게는 판매글 미리보기에서
+/// 가린 컬럼을 가려서 반환한다. 바이너리 자산 데이터셋은 레코드가 없으므로 지원하지 않는다.
+pub fn run_query(query: &DatasetQuery, requester: Principal) -> Result<QueryResult, BackendError> {
+    let dataset = storage::get_dataset(query.dataset_id)
+        .ok_or_else(|| BackendError::not_found("dataset", query.dataset_id))?;
+    let masked_columns = marketplace_storage::query_masked_columns(requester, &dataset)
+        .ok_or_else(|| BackendError::unauthorized("query_dataset"))?;
+    let schema = dataset
+        .schema_id
+        .and_then(storage::get_schema)
+        .ok_or_else(|| BackendError::validation("dataset", "unsupported"))?;
+
+    // 레코드는 필요한 만큼만 디코딩 (집계가 없으면 offset+limit 행을 채우면 멈춤)
+    let mut records = dataset
+        .record_ids
+        .iter()
+        .filter_map(|&data_id| storage::dataset_record(data_id))
+        .peekable();
+    if schema.has_header && matches!(records.peek(), Some(CborValue::Array(_))) {
+        records.next();
+    }
+    evaluate(&schema, records, query, &masked_columns)
+}
+
+/// 스키마와 레코드로 쿼리 평가 (캐니스터 상태를 읽지 않음)
+///
+/// 가린 컬럼은 값을 가려서 반환하고, 필터·그룹·집계에는 쓸 수 없다.
+fn evaluate(
+    schema: &DatasetSchema,
+    records: impl Iterator<Item = CborValue>,
+    query: &DatasetQuery,
+    masked_columns: &[String],
+) -> Result<QueryResult, BackendError> {
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT);
+    if limit == 0 || limit > MAX_LIMIT {
+        return Err(BackendError::out_of_range(
+            "limit",
+            limit,
+            1,
+            u64::from(MAX_LIMIT),
+        ));
+    }
+    let filters = query.filters.as_deref().unwrap_or_default();
+    if filters.len() > MAX_FILTERS {
+        return Err(BackendError::quota_exceeded("filters", MAX_FILTERS as u64));
+    }
+
+    let conditions = filters
+        .iter()
+        .map(|filter| {
+            let index = unmasked_column_index(schema, masked_columns, "filters", &filter.column)?;
+            Ok((index, filter))
+        })
+        .collect::<Result<Vec<_>, BackendError>>()?;
+    let matched = records.filter(|record| {
+        conditions
+            .iter()
+            .all(|(index, filter)| matches(&cell(schema, record, *index), filter.op, &filter.value))
+    });
+
+    let offset = query.offset.unwrap_or(0) as usize;
+    let (columns, mut rows) = if query.group_by.is_some() || query.aggregates.is_some() {
+        let (columns, rows) =
+            aggregate_rows(schema, &matched.collect::<Vec<_>>(), query, masked_columns)?;
+        (
+            columns,
+            rows.into_iter()
+                .skip(offset)
+                .take(limit as usize + 1)
+                .collect(),
+        )
+    } else {
+        let projection = projection(schema, query, masked_columns)?;
+        let columns = projection
+            .iter()
+            .map(|&(index, _)| schema.columns[index].name.clone())
+            .collect();
+        let rows: Vec<_> = matched
+            .skip(offset)
+            .take(limit as usize + 1)
+            .map(|record| project(schema, &record, &projection))
+            .collect();
+        (columns, rows)
+    };
+
+    // 한 행을 더 읽어 다음 페이지가 있는지 판단
+    let has_more = rows.len() > limit as usize;
+    rows.truncate(limit as usize);
+    Ok(QueryResult {
+        columns,
+        rows,
+        has_more,
+    })
+}
+
+type Rows = (Vec<String>, Vec<Vec<QueryValue>>);
+
+/// 반환할 컬럼 위치와 가림 여부 (기본: 전체 컬럼)
+fn projection(
+    schema: &DatasetSchema,
+    query: &DatasetQuery,
+    masked_columns: &[String],
+) -> Result<Vec<(usize, bool)>, BackendError> {
+    let indices = match &query.columns {
+        Some(names) => names
+            .iter()
+            .map(|name| column_index(schema, "columns", name))
+            .collect::<Result<Vec<_>, BackendError>>()?,
+        None => (0..schema.columns.len()).collect(),
+    };
+    Ok(indices
+        .into_iter()
+        .map(|index| (index, masked_columns.contains(&schema.columns[index].name)))
+        .collect())
+}
+
+/// 선택한 컬럼만 반환 (가린 컬럼은 미리보기와 같이 첫 글자만 남김)
+fn project(
+    schema: &DatasetSchema,
+    record: &CborValue,
+    projection: &[(usize, bool)],
+) -> Vec<QueryValue> {
+    projection
+        .iter()
+        .map(|&(index, masked)| {
+            let name = &schema.columns[index].name;
+            match schema::column_value(record, name, index) {
+                Some(CborValue::Null) | None => QueryValue::Null,
+                Some(value) if masked => QueryValue::Text(preview::mask(&csv_field(value.clone()))),
+                Some(value) => to_query_value(value),
+            }
+        })
+        .collect()
+}
+
+/// 그룹별 집계 (그룹 컬럼 값 순으로 정렬)
+fn aggregate_rows(
+    schema: &DatasetSchema,
+    records: &[CborValue],
+    query: &DatasetQuery,
+    masked_columns: &[String],
+) -> Result<Rows, BackendError> {
+    if query.columns.is_some() {
+        return Err(BackendError::validation("columns", "unsupported"));
+    }
+    let group_columns = query
+        .group_by
+        .iter()
+        .flatten()
+        .map(|name| unmasked_column_index(schema, masked_columns, "group_by", name))
+        .collect::<Result<Vec<_>, BackendError>>()?;
+
+    let default_aggregates = [QueryAggregate {
+        function: AggregateFunction::Count,
+        column: None,
+    }];
+    let aggregates = match query.aggregates.as_deref() {
+        Some([]) => return Err(BackendError::validation("aggregates", "empty")),
+        Some(aggregates) => aggregates,
+        None => &default_aggregates,
+    };
+    let targets = aggregates
+        .iter()
+        .map(|aggregate| match (&aggregate.column, aggregate.function) {
+            (Some(name), _) => {
+                unmasked_column_index(schema, masked_columns, "aggregates", name).map(Some)
+            }
+            (None, AggregateFunction::Count) => Ok(None),
+            (None, _) => Err(BackendError::validation("aggregates", "required")),
+        })
+        .collect::<Result<Vec<_>, BackendError>>()?;
+
+    // 그룹 키(CBOR 인코딩)별 누적값
+    let mut groups: Vec<(Vec<QueryValue>, Vec<Accumulator>)> = Vec::new();
+    let mut positions: HashMap<Vec<u8>, usize> = HashMap::new();
+    for record in records {
+        let key: Vec<QueryValue> = group_columns
+            .iter()
+            .map(|&index| cell(schema, record, index))
+            .collect();
+        let encoded = serde_cbor::to_vec(&key).map_err(BackendError::internal)?;
+        let slot = *positions.entry(encoded).or_insert_with(|| {
+            groups.push((key, vec![Accumulator::default(); targets.len()]));
+            groups.len() - 1
+        });
+        for (accumulator, target) in groups[slot].1.iter_mut().zip(&targets) {
+            accumulator.add(target.map(|index| cell(schema, record, index)));
+        }
+    }
+    groups.sort_by(|(a, _), (b, _)| compare_keys(a, b));
+
+    let mut columns = query.group_by.clone().unwrap_or_default();
+    columns.extend(aggregates.iter().map(aggregate_label));
+    let rows = groups
+        .into_iter()
+        .map(|(mut row, accumulators)| {
+            row.extend(
+                accumulators
+                    .iter()
+                    .zip(aggregates)
+                    .map(|(accumulator, aggregate)| accumulator.result(aggregate.function)),
+            );
+            row
+        })
+        .collect();
+    Ok((columns, rows))
+}
+
+/// 컬럼 이름의 스키마 위치 (없으면 field 검증 실패)
+fn column_index(schema: &DatasetSchema, field: &str, name: &str) -> Result<usize, BackendError> {
+    schema
+        .columns
+        .iter()
+        .position(|column| column.name == name)
+        .ok_or_else(|| BackendError::validation(field, "unknown_column"))
+}
+
+/// 필터·그룹·집계에 쓸 컬럼 위치 (가린 컬럼은 값이 드러나므로 거부)
+fn unmasked_column_index(
+    schema: &DatasetSchema,
+    masked_columns: &[String],
+    field: &str,
+    name: &str,
+) -> Result<usize, BackendError> {
+    if masked_columns.iter().any(|masked| masked == name) {
+        return Err(BackendError::validation(field, "masked_column"));
+    }
+    column_index(schema, field, name)
+}
+
+/// 레코드의 index번째 스키마 컬럼 값 (없으면 Null)
+fn cell(schema: &DatasetSchema, record: &CborValue, index: usize) -> QueryValue {
+    schema::column_value(record, &schema.columns[index].name, index)
+        .map_or(QueryValue::Null, to_query_value)
+}
+
+// =====================
+// 3) 값 비교 및 집계
+// =====================
+
+fn to_query_value(value: &CborValue) -> QueryValue {
+    match value {
+        CborValue::Null => QueryValue::Null,
+        CborValue::Bool(flag) => QueryValue::Bool(*flag),
+        CborValue::Integer(number) => i64::try_from(*number)
+            .map(QueryValue::Integer)
+            .unwrap_or(QueryValue::Float(*number as f64)),
+        CborValue::Float(number) => QueryValue::Float(*number),
+        other => QueryValue::Text(crate::http::csv_field(other.clone())),
+    }
+}
+
+/// 숫자로 읽을 수 있는 값 (숫자 문자열 포함)
+fn numeric(value: &QueryValue) -> Option<f64> {
+    match value {
+        QueryValue::Integer(number) => Some(*number as f64),
+        QueryValue::Float(number) => Some(*number),
+        QueryValue::Text(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// 두 값 비교 (숫자끼리는 수치, 문자열끼리는 사전순, 그 외 타입이 다르면 None)
+fn compare(a: &QueryValue, b: &QueryValue) -> Option<Ordering> {
+    match (a, b) {
+        (QueryValue::Text(x), QueryValue::Text(y)) => Some(x.cmp(y)),
+        (QueryValue::Bool(x), QueryValue::Bool(y)) => Some(x.cmp(y)),
+        (QueryValue::Null, QueryValue::Null) => Some(Ordering::Equal),
+        (QueryValue::Null, _) | (_, QueryValue::Null) => None,
+        _ => numeric(a)?.partial_cmp(&numeric(b)?),
+    }
+}
+
+fn matches(value: &QueryValue, op: FilterOp, expected: &QueryValue) -> bool {
+    let ordering = compare(value, expected);
+    match op {
+        FilterOp::Eq => ordering == Some(Ordering::Equal),
+        FilterOp::Ne => ordering != Some(Ordering::Equal),
+        FilterOp::Lt => ordering == Some(Ordering::Less),
+        FilterOp::Lte => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        FilterOp::Gt => ordering == Some(Ordering::Greater),
+        FilterOp::Gte => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+    }
+}
+
+/// 그룹 정렬 (비교할 수 없는 값은 null이 앞, 나머지는 같은 것으로 취급)
+fn compare_keys(a: &[QueryValue], b: &[QueryValue]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| match (x, y) {
+            (QueryValue::Null, QueryValue::Null) => Ordering::Equal,
+            (QueryValue::Null, _) => Ordering::Less,
+            (_, QueryValue::Null) => Ordering::Greater,
+            _ => compare(x, y).unwrap_or(Ordering::Equal),
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+fn aggregate_label(aggregate: &QueryAggregate) -> String {
+    let function = match aggregate.function {
+        AggregateFunction::Count => "count",
+        AggregateFunction::Sum => "sum",
+        AggregateFunction::Avg => "avg",
+    };
+    match &aggregate.column {
+        Some(column) => format!("{}({})", function, column),
+        None => function.to_string(),
+    }
+}
+
+/// 그룹별 집계 누적값
+#[derive(Clone, Default)]
+struct Accumulator {
+    count: u64,               // 값이 있는 행 수 (컬럼 없는 Count는 전체 행 수)
+    numbers: u64,             // 숫자로 읽힌 값 수
+    integer_sum: Option<i64>, // 모두 정수이고 넘치지 않았을 때의 합
+    float_sum: f64,
+    non_integer: bool,
+}
+
+impl Accumulator {
+    fn add(&mut self, value: Option<QueryValue>) {
+        let Some(value) = value else {
+            self.count += 1;
+            return;
+        };
+        if value == QueryValue::Null {
+            return;
+        }
+        self.count += 1;
+
+        let Some(number) = numeric(&value) else {
+            return;
+        };
+        self.numbers += 1;
+        self.float_sum += number;
+        match value {
+            QueryValue::Integer(integer) if !self.non_integer => {
+                self.integer_sum = self.integer_sum.unwrap_or(0).checked_add(integer);
+                self.non_integer = self.integer_sum.is_none();
+            }
+            _ => {
+                self.integer_sum = None;
+                self.non_integer = true;
+            }
+        }
+    }
+
+    fn result(&self, function: AggregateFunction) -> QueryValue {
+        match function {
+            AggregateFunction::Count => QueryValue::Integer(self.count as i64),
+            _ if self.numbers == 0 => QueryValue::Null,
+            AggregateFunction::Sum => self
+                .integer_sum
+                .map_or(QueryValue::Float(self.float_sum), QueryValue::Integer),
+            AggregateFunction::Avg => QueryValue::Float(self.float_sum / self.numbers as f64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::QueryFilter;
+
+    fn query() -> DatasetQuery {
+        DatasetQuery {
+            dataset_id: 1,
+            columns: None,
+            filters: None,
+            group_by: None,
+            aggregates: None,
+            limit: None,
+            offset: None,
+        }
+    }
+
+    #[test]
+    fn test_filter_project_and_aggregate() {
+        let records: Vec<CborValue> = serde_json::from_str::<Vec<serde_json::Value>>(
+            r#"[
+                {"city": "Seoul", "date": "2024-01-01", "temp": 3},
+                {"city": "Busan", "date": "2024-01-01", "temp": 8},
+                {"city": "Seoul", "date": "2024-01-02", "temp": -2},
+                {"city": "Busan", "date": "2024-01-02", "temp": 9.5},
+                {"city": "Seoul", "date": "2024-01-03", "temp": null}
+            ]"#,
+        )
+        .unwrap()
+        .into_iter()
+        .map(|value| serde_cbor::value::to_value(value).unwrap())
+        .collect();
+        let schema = schema::infer_schema(&records);
+
+        let result = evaluate(
+            &schema,
+            records.iter().cloned(),
+            &DatasetQuery {
+                columns: Some(vec!["date".to_string(), "temp".to_string()]),
+                filters: Some(vec![
+                    QueryFilter {
+                        column: "city".to_string(),
+                        op: FilterOp::Eq,
+                        value: QueryValue::Text("Seoul".to_string()),
+                    },
+                    QueryFilter {
+                        column: "temp".to_string(),
+                        op: FilterOp::Lt,
+                        value: QueryValue::Float(5.0),
+                    },
+                ]),
+                offset: Some(1),
+                ..query()
+            },
+            &[],
+        )
+        .unwrap();
+        assert_eq!(result.columns, vec!["date", "temp"]);
+        assert!(!result.has_more);
+        assert_eq!(
+            result.rows,
+            vec![vec![
+                QueryValue::Text("2024-01-02".to_string()),
+                QueryValue::Integer(-2)
+            ]]
+        );
+
+        let result = evaluate(
+            &schema,
+            records.iter().cloned(),
+            &DatasetQuery {
+                group_by: Some(vec!["city".to_string()]),
+                aggregates: Some(vec![
+                    QueryAggregate {
+                        function: AggregateFunction::Count,
+                        column: None,
+                    },
+                    QueryAggregate {
+                        function: AggregateFunction::Sum,
+                        column: Some("temp".to_string()),
+                    },
+                    QueryAggregate {
+                        function: AggregateFunction::Avg,
+                        column: Some("temp".to_string()),
+                    },
+                ]),
+                ..query()
+            },
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            result.columns,
+            vec!["city", "count", "sum(temp)", "avg(temp)"]
+        );
+        assert_eq!(
+            result.rows,
+            vec![
+                vec![
+                    QueryValue::Text("Busan".to_string()),
+                    QueryValue::Integer(2),
+                    QueryValue::Float(17.5),
+                    QueryValue::Float(8.75),
+                ],
+                vec![
+                    QueryValue::Text("Seoul".to_string()),
+                    QueryValue::Integer(3),
+                    QueryValue::Integer(1),
+                    QueryValue::Float(0.5),
+                ],
+            ]
+        );
+
+        let unknown = DatasetQuery {
+            columns: Some(vec!["humidity".to_string()]),
+            ..query()
+        };
+        assert_eq!(
+            evaluate(&schema, records.iter().cloned(), &unknown, &[]),
+            Err(BackendError::validation("columns", "unknown_column"))
+        );
+
+        // 가린 컬럼은 값을 가리고, 필터에는 쓸 수 없음
+        let masked = ["city".to_string()];
+        let result = evaluate(
+            &schema,
+            records.iter().cloned(),
+            &DatasetQuery {
+                columns: Some(vec!["city".to_string()]),
+                limit: Some(2),
+                ..query()
+            },
+            &masked,
+        )
+        .unwrap();
+        assert_eq!(
+            result.rows,
+            vec![
+                vec![QueryValue::Text("S****".to_string())],
+                vec![QueryValue::Text("B****".to_string())],
+            ]
+        );
+        assert!(result.has_more);
+        let filtered = DatasetQuery {
+            filters: Some(vec![QueryFilter {
+                column: "city".to_string(),
+                op: FilterOp::Eq,
+                value: QueryValue::Text("Seoul".to_string()),
+            }]),
+            ..query()
+        };
+        assert_eq!(
+            evaluate(&schema, records.iter().cloned(), &filtered, &masked),
+            Err(BackendError::validation("filters", "masked_column"))
+        );
+    }
+}
@@ -0,0 +1,512 @@
+// backend/src/schema.rs
+
+use crate::error::BackendError;
+use crate::types::{ColumnSchema, ColumnType, DatasetSchema};
+use serde_cbor::value::Value as CborValue;
+use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet};
+
+/// 업로더가 지정할 수 있는 JSON Schema 최대 크기
+const MAX_JSON_SCHEMA_BYTES: usize = 64 * 1024;
+
+// =====================
+// 1) 스키마 추론
+// =====================
+
+/// 추론 중인 컬럼 상태
+struct ColumnState {
+    name: String,
+    column_type: Option<ColumnType>,
+    nullable: bool,
+}
+
+/// 레코드 목록에서 데이터셋 스키마 추론
+///
+/// - 객체 레코드: 필드 이름별로 타입을 모으고, 일부 레코드에 없는 필드는 nullable
+/// - 배열 레코드(CSV 행): 첫 행이 헤더로 보이면 컬럼 이름으로 사용, 아니면 column_N
+/// - 단일 값 레코드: "value" 컬럼 하나
+pub fn infer_schema(records: &[CborValue]) -> DatasetSchema {
+    let header = detect_header(records);
+    let body = if header.is_some() {
+        &records[1..]
+    } else {
+        records
+    };
+
+    let mut columns: Vec<ColumnState> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for (row, record) in body.iter().enumerate() {
+        let cells: Vec<(String, &CborValue)> = match record {
+            CborValue::Map(map) => map
+                .iter()
+                .filter_map(|(key, value)| match key {
+                    CborValue::Text(name) => Some((name.clone(), value)),
+                    _ => None,
+                })
+                .collect(),
+            CborValue::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(index, value)| (column_name(header.as_deref(), index), value))
+                .collect(),
+            other => vec![("value".to_string(), other)],
+        };
+
+        let mut seen = HashSet::new();
+        for (name, value) in cells {
+            let position = *positions.entry(name.clone()).or_insert_with(|| {
+                columns.push(ColumnState {
+                    name: name.clone(),
+                    column_type: None,
+                    // 앞선 레코드에는 없던 컬럼
+                    nullable: row > 0,
+                });
+                columns.len() - 1
+            });
+            seen.insert(position);
+
+            let column = &mut columns[position];
+            match infer_value_type(value) {
+                Some(value_type) => {
+                    column.column_type = Some(match column.column_type {
+                        Some(current) => merge_types(current, value_type),
+                        None => value_type,
+                    });
+                }
+                None => column.nullable = true,
+            }
+        }
+
+        for (position, column) in columns.iter_mut().enumerate() {
+            if !seen.contains(&position) {
+                column.nullable = true;
+            }
+        }
+    }
+
+    DatasetSchema {
+        columns: columns
+            .into_iter()
+            .map(|column| ColumnSchema {
+                name: column.name,
+                column_type: column.column_type.unwrap_or(ColumnType::String),
+                nullable: column.nullable,
+            })
+            .collect(),
+        record_count: body.len() as u64,
+        has_header: header.is_some(),
+        json_schema: None,
+    }
+}
+
+/// 배열 레코드의 컬럼 이름
+pub fn column_name(header: Option<&[String]>, index: usize) -> String {
+    header
+        .and_then(|names| names.get(index).cloned())
+        .unwrap_or_else(|| format!("column_{}", index + 1))
+}
+
+/// CSV 첫 행이 헤더인지 판단
+///
+/// 첫 행이 모두 비어 있지 않은 서로 다른 문자열이고, 나머지 행에 숫자/날짜 등
+/// 타입이 있는 컬럼이 있거나 헤더 값이 같은 컬럼에 다시 나오지 않으면 헤더로 본다.
+pub fn detect_header(records: &[CborValue]) -> Option<Vec<String>> {
+    let Some(CborValue::Array(cells)) = records.first() else {
+        return None;
+    };
+    let rest = &records[1..];
+    if cells.is_empty() || rest.is_empty() {
+        return None;
+    }
+
+    let mut names = Vec::with_capacity(cells.len());
+    for cell in cells {
+        match cell {
+            CborValue::Text(text) if infer_text_type(text) == Some(ColumnType::String) => {
+                names.push(text.trim().to_string());
+            }
+            _ => return None,
+        }
+    }
+    let unique: HashSet<&String> = names.iter().collect();
+    if unique.len() != names.len() {
+        return None;
+    }
+
+    let mut typed_column = false;
+    let mut repeated = false;
+    for record in rest {
+        if let CborValue::Array(items) = record {
+            for (index, item) in items.iter().enumerate() {
+                match infer_value_type(item) {
+                    Some(ColumnType::String) | None => {}
+                    Some(_) => typed_column = true,
+                }
+                if let (CborValue::Text(text), Some(name)) = (item, names.get(index)) {
+                    repeated |= text.trim() == name;
+                }
+            }
+        }
+    }
+
+    (typed_column || !repeated).then_some(names)
+}
+
+/// 값의 타입 추론 (null 또는 빈 문자열이면 None)
+pub fn infer_value_type(value: &CborValue) -> Option<ColumnType> {
+    match value {
+        CborValue::Null => None,
+        CborValue::Bool(_) => Some(ColumnType::Bool),
+        CborValue::Integer(_) => Some(ColumnType::Integer),
+        CborValue::Float(_) => Some(ColumnType::Float),
+        CborValue::Text(text) => infer_text_type(text),
+        CborValue::Array(_) => Some(ColumnType::Array),
+        CborValue::Map(_) => Some(ColumnType::Object),
+        _ => Some(ColumnType::String),
+    }
+}
+
+/// 문자열 값의 타입 추론 (CSV 필드는 모두 문자열로 들어온다)
+fn infer_text_type(text: &str) -> Option<ColumnType> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    // "007", "01012345678"처럼 앞자리 0이 의미 있는 값은 문자열로 유지
+    let unsigned = text.strip_prefix(['-', '+']).unwrap_or(text);
+    if unsigned.len() > 1 && unsigned.starts_with('0') && !unsigned.starts_with("0.") {
+        return Some(ColumnType::String);
+    }
+
+    if text.parse::<i64>().is_ok() {
+        Some(ColumnType::Integer)
+    } else if text.parse::<f64>().is_ok_and(f64::is_finite) {
+        Some(ColumnType::Float)
+    } else if text.eq_ignore_ascii_case("true") || text.eq_ignore_ascii_case("false") {
+        Some(ColumnType::Bool)
+    } else if is_date(text) {
+        Some(ColumnType::Date)
+    } else {
+        Some(ColumnType::String)
+    }
+}
+
+/// YYYY-MM-DD (구분자 -, /, .) 또는 그 뒤에 시각이 붙은 형식인지 확인
+fn is_date(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    if bytes.len() < 10 || !text.is_char_boundary(10) {
+        return false;
+    }
+    if bytes.len() > 10 && bytes[10] != b'T' && bytes[10] != b' ' {
+        return false;
+    }
+
+    let separator = bytes[4];
+    if !matches!(separator, b'-' | b'/' | b'.') || bytes[7] != separator {
+        return false;
+    }
+
+    let number = |range: std::ops::Range<usize>| text[range].parse::<u32>().ok();
+    match (number(0..4), number(5..7), number(8..10)) {
+        (Some(_), Some(month), Some(day)) => (1..=12).contains(&month) && (1..=31).contains(&day),
+        _ => false,
+    }
+}
+
+/// 서로 다른 타입이 섞인 컬럼의 타입 결정
+pub fn merge_types(current: ColumnType, next: ColumnType) -> ColumnType {
+    match (current, next) {
+        (a, b) if a == b => a,
+        (ColumnType::Integer, ColumnType::Float) | (ColumnType::Float, ColumnType::Integer) => {
+            ColumnType::Float
+        }
+        _ => ColumnType::String,
+    }
+}
+
+/// 업로드 파서가 알려준 컬럼 순서로 스키마 정렬
+///
+/// CBOR 맵은 키를 정렬해 저장하므로 CSV 헤더 순서는 파서에서 따로 받는다.
+pub fn apply_column_order(schema: &mut DatasetSchema, columns: &[String], has_header: bool) {
+    schema.has_header = has_header;
+    schema.columns.sort_by_key(|column| {
+        columns
+            .iter()
+            .position(|name| *name == column.name)
+            .unwrap_or(usize::MAX)
+    });
+}
+
+// =====================
+// 2) 타입 변환
+// =====================
+
+/// 헤더 행을 제외한 레코드 (헤더가 있는 배열 레코드만 첫 행 제외)
+pub fn skip_header<'a>(schema: &DatasetSchema, records: &'a [CborValue]) -> &'a [CborValue] {
+    match records.first() {
+        Some(CborValue::Array(_)) if schema.has_header => &records[1..],
+        _ => records,
+    }
+}
+
+/// 레코드에서 컬럼 값 찾기 (맵은 이름, 배열은 위치, 단일 값은 그대로)
+pub fn column_value<'a>(record: &'a CborValue, name: &str, index: usize) -> Option<&'a CborValue> {
+    match record {
+        CborValue::Map(fields) => fields.get(&CborValue::Text(name.to_string())),
+        CborValue::Array(items) => items.get(index),
+        other => Some(other),
+    }
+}
+
+/// 문자열 컬럼 값들로 컬럼 타입 결정 (모두 비어 있으면 String)
+pub fn text_column_type<'a>(values: impl IntoIterator<Item = &'a str>) -> ColumnType {
+    values
+        .into_iter()
+        .filter_map(infer_text_type)
+        .reduce(merge_types)
+        .unwrap_or(ColumnType::String)
+}
+
+/// 문자열 값을 컬럼 타입에 맞는 CBOR 값으로 변환 (빈 값은 null)
+pub fn typed_value(text: &str, column_type: ColumnType) -> CborValue {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return CborValue::Null;
+    }
+
+    match column_type {
+        ColumnType::Integer => trimmed
+            .parse::<i64>()
+            .map(|number| CborValue::Integer(number.into()))
+            .unwrap_or_else(|_| CborValue::Text(text.to_string())),
+        ColumnType::Float => trimmed
+            .parse::<f64>()
+            .map(CborValue::Float)
+            .unwrap_or_else(|_| CborValue::Text(text.to_string())),
+        ColumnType::Bool => CborValue::Bool(trimmed.eq_ignore_ascii_case("true")),
+        _ => CborValue::Text(text.to_string()),
+    }
+}
+
+// =====================
+// 3) JSON Schema 검증
+// =====================
+
+/// 업로더가 지정한 JSON Schema로 모든 레코드 검증
+///
+/// 지원 키워드: type, enum, const, minimum, maximum, exclusiveMinimum,
+/// exclusiveMaximum, minLength, maxLength, items, minItems, maxItems,
+/// properties, required, additionalProperties. 그 외 키워드는 무시한다.
+pub fn validate_json_schema(records: &[CborValue], schema: &str) -> Result<(), BackendError> {
+    if schema.len() > MAX_JSON_SCHEMA_BYTES {
+        return Err(BackendError::quota_exceeded(
+            "json_schema",
+            MAX_JSON_SCHEMA_BYTES as u64,
+        ));
+    }
+
+    let schema = serde_json::from_str::<JsonValue>(schema)
+        .ok()
+        .filter(|schema| schema.is_object() || schema.is_boolean())
+        .ok_or_else(|| BackendError::validation("json_schema", "invalid_format"))?;
+
+    for (index, record) in records.iter().enumerate() {
+        let matches = serde_json::to_value(record)
+            .map(|value| matches_schema(&value, &schema))
+            .unwrap_or(false);
+        if !matches {
+            return Err(BackendError::validation(
+                format!("records[{}]", index),
+                "schema_mismatch",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// 값이 스키마를 만족하는지 확인
+fn matches_schema(value: &JsonValue, schema: &JsonValue) -> bool {
+    let schema = match schema {
+        JsonValue::Bool(allowed) => return *allowed,
+        JsonValue::Object(schema) => schema,
+        _ => return true,
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let matches_type = match expected {
+            JsonValue::String(name) => matches_type(value, name),
+            JsonValue::Array(names) => names
+                .iter()
+                .filter_map(JsonValue::as_str)
+                .any(|name| matches_type(value, name)),
+            _ => true,
+        };
+        if !matches_type {
+            return false;
+        }
+    }
+
+    if let Some(JsonValue::Array(options)) = schema.get("enum") {
+        if !options.contains(value) {
+            return false;
+        }
+    }
+    if let Some(constant) = schema.get("const") {
+        if constant != value {
+            return false;
+        }
+    }
+
+    let bound = |keyword: &str| schema.get(keyword).and_then(JsonValue::as_f64);
+    let size = |keyword: &str| schema.get(keyword).and_then(JsonValue::as_u64);
+
+    match value {
+        JsonValue::Number(number) => {
+            let number = number.as_f64().unwrap_or(f64::NAN);
+            if bound("minimum").is_some_and(|min| number < min)
+                || bound("maximum").is_some_and(|max| number > max)
+                || bound("exclusiveMinimum").is_some_and(|min| number <= min)
+                || bound("exclusiveMaximum").is_some_and(|max| number >= max)
+            {
+                return false;
+            }
+        }
+        JsonValue::String(text) => {
+            let length = text.chars().count() as u64;
+            if size("minLength").is_some_and(|min| length < min)
+                || size("maxLength").is_some_and(|max| length > max)
+            {
+                return false;
+            }
+        }
+        JsonValue::Array(items) => {
+            let length = items.len() as u64;
+            if size("minItems").is_some_and(|min| length < min)
+                || size("maxItems").is_some_and(|max| length > max)
+            {
+                return false;
+            }
+            if let Some(item_schema) = schema.get("items") {
+                if !items.iter().all(|item| matches_schema(item, item_schema)) {
+                    return false;
+                }
+            }
+        }
+        JsonValue::Object(fields) => {
+            if let Some(JsonValue::Array(required)) = schema.get("required") {
+                let missing = required
+                    .iter()
+                    .filter_map(JsonValue::as_str)
+                    .any(|name| !fields.contains_key(name));
+                if missing {
+                    return false;
+                }
+            }
+
+            let properties = schema.get("properties").and_then(JsonValue::as_object);
+            for (name, field) in fields {
+                let field_schema = properties
+                    .and_then(|properties| properties.get(name))
+                    .or_else(|| schema.get("additionalProperties"));
+                if let Some(field_schema) = field_schema {
+                    if !matches_schema(field, field_schema) {
+                        return false;
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+
+    true
+}
+
+/// JSON Schema type 키워드 확인
+fn matches_type(value: &JsonValue, name: &str) -> bool {
+    match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64()
+                || value.is_u64()
+                || value.as_f64().is_some_and(|number| number.fract() == 0.0)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(cells: &[&str]) -> CborValue {
+        CborValue::Array(
+            cells
+                .iter()
+                .map(|cell| CborValue::Text(cell.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_infer_csv_schema_with_header() {
+        let records = vec![
+            row(&["name", "age", "score", "active", "joined"]),
+            row(&["kim", "31", "4.5", "true", "2024-01-15"]),
+            row(&["lee", "", "3", "false", "2024/02/01"]),
+        ];
+
+        let schema = infer_schema(&records);
+        assert!(schema.has_header);
+        assert_eq!(schema.record_count, 2);
+
+        let types: Vec<(&str, ColumnType, bool)> = schema
+            .columns
+            .iter()
+            .map(|column| (column.name.as_str(), column.column_type, column.nullable))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                ("name", ColumnType::String, false),
+                ("age", ColumnType::Integer, true),
+                ("score", ColumnType::Float, false),
+                ("active", ColumnType::Bool, false),
+                ("joined", ColumnType::Date, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json_schema_validation() {
+        let json: JsonValue =
+            serde_json::from_str(r#"[{"id": 1, "tags": ["a"]}, {"id": 2, "tags": []}]"#).unwrap();
+        let records = match serde_cbor::value::to_value(json).unwrap() {
+            CborValue::Array(records) => records,
+            _ => unreachable!(),
+        };
+
+        let schema = infer_schema(&records);
+        assert!(!schema.has_header);
+        assert_eq!(schema.columns[0].name, "id");
+        assert_eq!(schema.columns[1].column_type, ColumnType::Array);
+
+        let valid = r#"{"type": "object", "required": ["id"],
+            "properties": {"id": {"type": "integer", "minimum": 1}, "tags": {"type": "array"}}}"#;
+        assert!(validate_json_schema(&records, valid).is_ok());
+
+        let invalid = r#"{"type": "object", "properties": {"id": {"maximum": 1}}}"#;
+        assert_eq!(
+            validate_json_schema(&records, invalid),
+            Err(BackendError::validation("records[1]", "schema_mismatch"))
+        );
+        assert!(validate_json_schema(&records, "not json").is_err());
+    }
+}
@@ -1,6 +1,9 @@
 // backend/src/storage.rs
 
+use crate::error::BackendError;
+use crate::i18n::Locale;
 use crate::types::*;
+use candid::Principal;
 use ic_stable_structures::{
     memory_manager::{MemoryId, MemoryManager, VirtualMemory},
     DefaultMemoryImpl, StableBTreeMap, StableCell,
@@ -47,6 +50,43 @@ fn get_minted_hashes_memory() -> Memory {
     BACKEND_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(6)))
 }
 
+fn get_audit_log_memory() -> Memory {
+    BACKEND_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(8)))
+}
+
+fn get_user_locales_memory() -> Memory {
+    BACKEND_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(9)))
+}
+
+// 10-16은 wallet_storage가 사용하므로 backend 추가 저장소는 20번대 사용
+fn get_data_schemas_memory() -> Memory {
+    BACKEND_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(20)))
+}
+
+fn get_data_http_hashes_memory() -> Memory {
+    BACKEND_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(23)))
+}
+
+fn get_datasets_memory() -> Memory {
+    BACKEND_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(21)))
+}
+
+fn get_data_datasets_memory() -> Memory {
+    BACKEND_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(24)))
+}
+
+fn get_dataset_record_hashes_memory() -> Memory {
+    BACKEND_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(25)))
+}
+
+fn get_latest_versions_memory() -> Memory {
+    BACKEND_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(22)))
+}
+
+fn get_pii_hash_key_memory() -> Memory {
+    BACKEND_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(26)))
+}
+
 // 데이터 해시를 위한 타입
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct DataHash([u8; 32]);
@@ -69,6 +109,28 @@ impl ic_stable_structures::Storable for DataHash {
         };
 }
 
+// 데이터셋 레코드 해시 목록 (레코드 순서대로 32바이트씩 이어 붙임)
+#[derive(Clone)]
+pub struct RecordHashes(Vec<[u8; 32]>);
+
+impl ic_stable_structures::Storable for RecordHashes {
+    fn to_bytes(&self) -> std::borrow::Cow<'_, [u8]> {
+        std::borrow::Cow::Owned(self.0.concat())
+    }
+
+    fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
+        RecordHashes(
+            bytes
+                .chunks_exact(32)
+                .map(|chunk| chunk.try_into().expect("32바이트 해시"))
+                .collect(),
+        )
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound =
+        ic_stable_structures::storable::Bound::Unbounded;
+}
+
 // 저장소들 - RefCell<StableBTreeMap> 직접 초기화 방식 사용
 thread_local! {
     static UPLOADED_DATA: RefCell<StableBTreeMap<u64, DataBlob, Memory>> = RefCell::new(
@@ -100,12 +162,58 @@ thread_local! {
             BACKEND_MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(6)))
         )
     );
+
+    // 순번 -> 감사 로그 항목 (추가만 허용)
+    static AUDIT_LOG: RefCell<StableBTreeMap<u64, AuditEntry, Memory>> = RefCell::new(
+        StableBTreeMap::init(get_audit_log_memory())
+    );
+
+    // 사용자 -> 선호 메시지 언어
+    static USER_LOCALES: RefCell<StableBTreeMap<Principal, Locale, Memory>> = RefCell::new(
+        StableBTreeMap::init(get_user_locales_memory())
+    );
+
+    // 스키마 ID -> 업로드 시 추론된 데이터셋 스키마
+    static DATA_SCHEMAS: RefCell<StableBTreeMap<u64, DatasetSchema, Memory>> = RefCell::new(
+        StableBTreeMap::init(get_data_schemas_memory())
+    );
+
+    // 데이터셋 ID -> 업로드 단위 데이터셋
+    static DATASETS: RefCell<StableBTreeMap<u64, Dataset, Memory>> = RefCell::new(
+        StableBTreeMap::init(get_datasets_memory())
+    );
+
+    // 데이터 ID -> HTTP 응답 본문 해시 (업로드 시 한 번 계산, 인증 트리 재구성용)
+    static DATA_HTTP_HASHES: RefCell<StableBTreeMap<u64, DataHash, Memory>> = RefCell::new(
+        StableBTreeMap::init(get_data_http_hashes_memory())
+    );
+
+    // 데이터셋 ID -> 레코드 해시 목록 (증명/커밋먼트 생성 시 블롭을 다시 해시하지 않음)
+    static DATASET_RECORD_HASHES: RefCell<StableBTreeMap<u64, RecordHashes, Memory>> = RefCell::new(
+        StableBTreeMap::init(get_dataset_record_hashes_memory())
+    );
+
+    // (데이터 ID, 데이터셋 ID) 인덱스 (같은 레코드가 여러 데이터셋에 포함될 수 있음)
+    static DATA_DATASETS: RefCell<StableBTreeMap<(u64, u64), (), Memory>> = RefCell::new(
+        StableBTreeMap::init(get_data_datasets_memory())
+    );
+
+    // 버전 계보 ID(첫 버전 데이터셋 ID) -> 최신 버전 데이터셋 ID
+    static LATEST_VERSIONS: RefCell<StableBTreeMap<u64, u64, Memory>> = RefCell::new(
+        StableBTreeMap::init(get_latest_versions_memory())
+    );
 }
 
 // 카운터들은 별도로 초기화
 thread_local! {
     static UPLOAD_COUNTER: RefCell<Option<StableCell<u64, Memory>>> = const { RefCell::new(None) };
     static MINT_COUNTER: RefCell<Option<StableCell<u64, Memory>>> = const { RefCell::new(None) };
+
+    // 개인정보 해시(HMAC) 키 (모두 0이면 아직 raw_rand로 채우지 않은 상태)
+    static PII_HASH_KEY: RefCell<StableCell<[u8; 32], Memory>> = RefCell::new(
+        StableCell::init(get_pii_hash_key_memory(), [0; 32])
+            .expect("Failed to initialize PII hash key")
+    );
 }
 
 // =====================
@@ -189,6 +297,16 @@ pub fn init_storage() {
         }
     });
 
+    let indexed = rebuild_data_dataset_index();
+    if indexed > 0 {
+        ic_cdk::println!("Indexed records of {} datasets", indexed);
+    }
+
+    let linked = rebuild_latest_versions();
+    if linked > 0 {
+        ic_cdk::println!("Linked latest versions of {} datasets", linked);
+    }
+
     ic_cdk::println!("Backend storage initialization completed");
 }
 
@@ -315,61 +433,178 @@ pub fn check_multiple_data_status(data_list: &[Vec<u8>]) -> Vec<(Option<u64>, bo
 // 4) 업로드 데이터 관리
 // =====================
 
-/// 업로드 데이터 저장 (중복 검사 포함)
-pub fn store_upload_data(parsed_data: Vec<CborValue>, mime_type: &str) -> Result<Vec<u64>, String> {
-    let mut data_ids = Vec::new();
-    let timestamp = ic_cdk::api::time();
+/// 업로드 데이터를 레코드 단위로 저장하고 데이터셋 생성 (중복 검사 포함)
+///
+/// 이미 민팅된 레코드가 하나라도 있으면 아무것도 저장하지 않는다.
+pub fn store_upload_data(
+    owner: Principal,
+    name: Option<String>,
+    parsed_data: Vec<CborValue>,
+    mime_type: &str,
+    schema: DatasetSchema,
+) -> Result<Dataset, BackendError> {
+    store_upload_data_at(
+        owner,
+        name,
+        parsed_data,
+        mime_type,
+        schema,
+        ic_cdk::api::time(),
+    )
+}
 
+fn store_upload_data_at(
+    owner: Principal,
+    name: Option<String>,
+    parsed_data: Vec<CborValue>,
+    mime_type: &str,
+    schema: DatasetSchema,
+    timestamp: u64,
+) -> Result<Dataset, BackendError> {
+    let mut records = Vec::with_capacity(parsed_data.len());
     for value in parsed_data {
-        let bytes = serde_cbor::to_vec(&value).map_err(|e| format!("CBOR 직렬화 실패: {}", e))?;
-
-        // 중복 검사
+        let bytes = serde_cbor::to_vec(&value).map_err(BackendError::internal)?;
         let hash = calculate_data_hash(&bytes);
+        records.push((hash, bytes));
+    }
+    ensure_not_minted(records.iter().map(|(hash, _)| hash))?;
 
-        // 이미 존재하는 데이터인지 확인
-        if let Some(existing_id) = DATA_HASHES.with(|hashes| hashes.borrow().get(&hash)) {
-            // 이미 민팅되었는지 확인
-            if MINTED_HASHES.with(|hashes| hashes.borrow().contains_key(&hash)) {
-                return Err(format!(
-                    "데이터가 이미 민팅되었습니다. (데이터 ID: {})",
-                    existing_id
-                ));
-            }
-            // 민팅되지 않았다면 기존 ID 반환
-            data_ids.push(existing_id);
-            continue;
-        }
+    let schema_id = store_schema(schema);
+    let mut stored = Vec::with_capacity(records.len());
+    let mut total_size = 0;
 
-        // 데이터 ID 생성
-        let data_id = with_upload_counter(|counter| {
-            let current = *counter.get();
-            let next_id = current + 1;
-            counter
-                .set(next_id)
-                .map_err(|e| format!("카운터 업데이트 실패: {:?}", e))?;
-            Ok(next_id)
-        })?;
-
-        // 데이터 저장
+    for (hash, bytes) in records {
+        let record_hash = hash.0;
+        total_size += bytes.len() as u64;
         let data_blob = DataBlob {
             data: bytes,
             mime_type: mime_type.to_string(),
             timestamp,
+            schema_id: Some(schema_id),
+            asset: None,
         };
+        stored.push((store_record(hash, data_blob)?, record_hash));
+    }
 
-        UPLOADED_DATA.with(|storage| {
-            storage.borrow_mut().insert(data_id, data_blob);
-        });
+    Ok(create_dataset(
+        owner,
+        name,
+        mime_type,
+        Some(schema_id),
+        stored,
+        total_size,
+        timestamp,
+    ))
+}
 
-        // 해시 저장
-        DATA_HASHES.with(|hashes| {
-            hashes.borrow_mut().insert(hash, data_id);
-        });
+/// 바이너리 자산을 하나의 블롭으로 저장하고 데이터셋 생성 (중복 검사 포함)
+pub fn store_binary_data(
+    owner: Principal,
+    name: Option<String>,
+    content: Vec<u8>,
+    mime_type: &str,
+    asset: AssetInfo,
+) -> Result<Dataset, BackendError> {
+    let hash = calculate_data_hash(&content);
+    ensure_not_minted([&hash])?;
+
+    let total_size = content.len() as u64;
+    let record_hash = hash.0;
+    let name = name.or_else(|| asset.file_name.clone());
+    let timestamp = ic_cdk::api::time();
+    let data_blob = DataBlob {
+        data: content,
+        mime_type: mime_type.to_string(),
+        timestamp,
+        schema_id: None,
+        asset: Some(asset),
+    };
+    let data_id = store_record(hash, data_blob)?;
+
+    Ok(create_dataset(
+        owner,
+        name,
+        mime_type,
+        None,
+        vec![(data_id, record_hash)],
+        total_size,
+        timestamp,
+    ))
+}
+
+/// 이미 민팅된 레코드가 있으면 해당 데이터 ID들로 에러
+fn ensure_not_minted<'a>(
+    hashes: impl IntoIterator<Item = &'a DataHash>,
+) -> Result<(), BackendError> {
+    let minted: Vec<u64> = MINTED_HASHES.with(|minted| {
+        let minted = minted.borrow();
+        hashes
+            .into_iter()
+            .filter(|hash| minted.contains_key(hash))
+            .filter_map(|hash| DATA_HASHES.with(|data| data.borrow().get(hash)))
+            .collect()
+    });
+
+    if minted.is_empty() {
+        Ok(())
+    } else {
+        Err(BackendError::AlreadyMinted { indices: minted })
+    }
+}
 
-        data_ids.push(data_id);
+/// 레코드 저장 (같은 내용이 이미 있으면 기존 ID 반환)
+fn store_record(hash: DataHash, data_blob: DataBlob) -> Result<u64, BackendError> {
+    if let Some(existing_id) = DATA_HASHES.with(|hashes| hashes.borrow().get(&hash)) {
+        return Ok(existing_id);
+    }
+
+    let data_id = next_data_id()?;
+    let http_hash = crate::http::render_data_body(&data_blob)
+        .ok()
+        .map(|body| calculate_data_hash(&body));
+    crate::certification::certify_blob(data_id, hash.0, http_hash.as_ref().map(|hash| hash.0));
+
+    UPLOADED_DATA.with(|storage| {
+        storage.borrow_mut().insert(data_id, data_blob);
+    });
+    DATA_HASHES.with(|hashes| {
+        hashes.borrow_mut().insert(hash, data_id);
+    });
+    if let Some(http_hash) = http_hash {
+        DATA_HTTP_HASHES.with(|hashes| {
+            hashes.borrow_mut().insert(data_id, http_hash);
+        });
     }
 
-    Ok(data_ids)
+    Ok(data_id)
+}
+
+/// 다음 업로드 데이터 ID 발급
+fn next_data_id() -> Result<u64, BackendError> {
+    with_upload_counter(|counter| {
+        let current = *counter.get();
+        let next_id = current + 1;
+        counter
+            .set(next_id)
+            .map_err(|e| format!("카운터 업데이트 실패: {:?}", e))?;
+        Ok(next_id)
+    })
+    .map_err(BackendError::internal)
+}
+
+/// 스키마 저장 후 ID 반환
+fn store_schema(schema: DatasetSchema) -> u64 {
+    DATA_SCHEMAS.with(|schemas| {
+        let mut schemas = schemas.borrow_mut();
+        let schema_id = schemas.last_key_value().map_or(1, |(id, _)| id + 1);
+        schemas.insert(schema_id, schema);
+        schema_id
+    })
+}
+
+/// 스키마 조회
+pub fn get_schema(schema_id: u64) -> Option<DatasetSchema> {
+    DATA_SCHEMAS.with(|schemas| schemas.borrow().get(&schema_id))
 }
 
 /// 업로드 데이터 조회
@@ -377,6 +612,29 @@ pub fn get_uploaded_data(data_id: u64) -> Option<Vec<u8>> {
     UPLOADED_DATA.with(|storage| storage.borrow().get(&data_id).map(|blob| blob.data))
 }
 
+/// 업로드 데이터 조회 (MIME 타입 포함)
+pub fn get_data_blob(data_id: u64) -> Option<DataBlob> {
+    UPLOADED_DATA.with(|storage| storage.borrow().get(&data_id))
+}
+
+/// 가린 컬럼을 적용한 업로드 데이터 (레코드를 디코딩할 수 없으면 None)
+///
+/// 바이너리 자산과 스키마가 없는 데이터는 가릴 컬럼이 없으므로 그대로 반환한다.
+pub fn get_masked_data_blob(data_id: u64, masked_columns: &[String]) -> Option<DataBlob> {
+    let mut blob = get_data_blob(data_id)?;
+    if masked_columns.is_empty() || blob.asset.is_some() {
+        return Some(blob);
+    }
+    let Some(schema) = blob.schema_id.and_then(get_schema) else {
+        return Some(blob);
+    };
+
+    let mut record: CborValue = serde_cbor::from_slice(&blob.data).ok()?;
+    crate::preview::mask_record(&mut record, &schema, masked_columns);
+    blob.data = serde_cbor::to_vec(&record).ok()?;
+    Some(blob)
+}
+
 /// 업로드 데이터 목록 조회
 pub fn list_uploaded_data() -> Vec<DataInfo> {
     UPLOADED_DATA.with(|storage| {
@@ -388,13 +646,24 @@ pub fn list_uploaded_data() -> Vec<DataInfo> {
                 mime_type: blob.mime_type.clone(),
                 timestamp: blob.timestamp,
                 size: blob.data.len() as u64,
+                schema: blob.schema_id.and_then(get_schema),
+                asset: blob.asset,
             })
             .collect()
     })
 }
 
 /// 업로드 데이터 삭제
-pub fn delete_uploaded_data(data_id: u64) -> Result<String, String> {
+///
+/// 레코드는 같은 내용이면 소유자와 관계없이 공유되므로, 데이터셋이나 판매글에 포함된
+/// 레코드는 삭제하지 않는다 (다른 데이터셋의 증명, 버전 비교, 쿼리가 깨짐).
+pub fn delete_uploaded_data(data_id: u64) -> Result<String, BackendError> {
+    if !datasets_containing(data_id).is_empty()
+        || !crate::marketplace_storage::listings_containing(data_id).is_empty()
+    {
+        return Err(BackendError::invalid_state("data_in_use"));
+    }
+
     // 먼저 데이터를 가져와서 해시 계산
     let data_hash = UPLOADED_DATA.with(|storage| {
         storage
@@ -406,7 +675,7 @@ pub fn delete_uploaded_data(data_id: u64) -> Result<String, String> {
     // 민팅된 데이터인지 확인
     if let Some(hash) = &data_hash {
         if MINTED_HASHES.with(|hashes| hashes.borrow().contains_key(hash)) {
-            return Err("민팅된 데이터는 삭제할 수 없습니다".to_string());
+            return Err(BackendError::invalid_state("data_already_minted"));
         }
     }
 
@@ -420,9 +689,13 @@ pub fn delete_uploaded_data(data_id: u64) -> Result<String, String> {
                         hashes.borrow_mut().remove(&hash);
                     });
                 }
+                DATA_HTTP_HASHES.with(|hashes| {
+                    hashes.borrow_mut().remove(&data_id);
+                });
+                crate::certification::uncertify_blob(data_id);
                 Ok(format!("데이터 ID {} 삭제 완료", data_id))
             }
-            None => Err(format!("데이터 ID {}를 찾을 수 없습니다", data_id)),
+            None => Err(BackendError::not_found("data", data_id)),
         }
     })
 }
@@ -464,6 +737,7 @@ pub fn store_mint_request(request: MintRequest) -> u64 {
             .borrow_mut()
             .insert(request_id, MintStatus::Pending);
     });
+    crate::certification::certify_mint_status(request_id, &MintStatus::Pending);
 
     request_id
 }
@@ -484,20 +758,36 @@ pub fn get_mint_status(request_id: u64) -> Option<MintStatus> {
 }
 
 /// 민팅 상태 업데이트 (민팅 완료 시 해시 기록)
-pub fn update_mint_status(request_id: u64, new_status: MintStatus) -> Result<(), String> {
+pub fn update_mint_status(request_id: u64, new_status: MintStatus) -> Result<(), BackendError> {
     // 민팅이 완료된 경우
     if let MintStatus::Completed(_) = &new_status {
         // 해당 요청의 메타데이터를 가져와서 민팅된 것으로 표시
         if let Some(request) = get_mint_request(request_id) {
             for metadata in &request.metadata {
                 let hash = calculate_data_hash(metadata);
+                // 업로드된 레코드를 직접 민팅한 경우 그 레코드를 포함한 데이터셋도 민팅됨으로 표시
+                if let Some(data_id) = DATA_HASHES.with(|data| data.borrow().get(&hash)) {
+                    mark_record_datasets_minted(data_id);
+                }
                 MINTED_HASHES.with(|hashes| {
                     hashes.borrow_mut().insert(hash, request_id);
                 });
             }
+            // 데이터셋 민팅이면 데이터셋의 레코드도 민팅됨으로 표시
+            if let Some(dataset_id) = request.dataset_id {
+                mark_dataset_minted(dataset_id, request_id);
+            }
         }
     }
 
+    record_audit(
+        ic_cdk::api::id(),
+        AuditAction::MintStatusChanged,
+        format!("request_id={}, status={:?}", request_id, new_status),
+    );
+
+    crate::certification::certify_mint_status(request_id, &new_status);
+
     MINT_STATUS_MAP.with(|status_map| {
         status_map.borrow_mut().insert(request_id, new_status);
         Ok(())
@@ -609,6 +899,8 @@ pub fn get_uploaded_data_info(data_id: u64) -> Option<crate::types::DataInfo> {
                 mime_type: blob.mime_type.clone(),
                 timestamp: blob.timestamp,
                 size: blob.data.len() as u64,
+                schema: blob.schema_id.and_then(get_schema),
+                asset: blob.asset,
             })
     })
 }
@@ -627,11 +919,906 @@ pub fn data_id_exists(data_id: u64) -> bool {
 }
 
 /// 여러 데이터 ID가 모두 존재하는지 확인
-pub fn validate_data_ids_exist(data_ids: &[u64]) -> Result<(), String> {
+pub fn validate_data_ids_exist(data_ids: &[u64]) -> Result<(), BackendError> {
     for &data_id in data_ids {
         if !data_id_exists(data_id) {
-            return Err(format!("데이터 ID {}를 찾을 수 없습니다", data_id));
+            return Err(BackendError::not_found("data", data_id));
+        }
+    }
+    Ok(())
+}
+
+/// 민팅 상태와 업로드 데이터 전체를 인증 트리에 등록
+///
+/// 데이터는 업로드 시 저장한 원본/HTTP 본문 해시만 읽는다.
+pub fn certify_all_data(tree: &mut crate::certification::CertifiedTree) {
+    MINT_STATUS_MAP.with(|status_map| {
+        for (request_id, status) in status_map.borrow().iter() {
+            crate::certification::insert_mint_status(tree, request_id, &status);
+        }
+    });
+    DATA_HASHES.with(|hashes| {
+        DATA_HTTP_HASHES.with(|http_hashes| {
+            let http_hashes = http_hashes.borrow();
+            for (hash, data_id) in hashes.borrow().iter() {
+                let http_hash = http_hashes.get(&data_id).map(|hash| hash.0);
+                crate::certification::insert_blob(tree, data_id, hash.0, http_hash);
+            }
+        });
+    });
+}
+
+// =====================
+// 8) 감사 로그
+// =====================
+
+/// 첫 항목의 이전 해시
+const AUDIT_GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// 감사 로그 기록
+pub fn record_audit(actor: Principal, action: AuditAction, details: String) {
+    append_audit_entry(actor, action, details, ic_cdk::api::time());
+}
+
+/// 감사 로그 항목 해시 (이전 해시를 포함하므로 앞 항목이 바뀌면 이후 체인이 모두 달라짐)
+fn audit_entry_hash(
+    seq: u64,
+    timestamp: u64,
+    actor: &Principal,
+    action: &AuditAction,
+    details: &str,
+    prev_hash: &str,
+) -> String {
+    let payload = serde_cbor::to_vec(&(seq, timestamp, actor, action, details, prev_hash))
+        .expect("감사 로그 직렬화 실패");
+    hex::encode(Sha256::digest(&payload))
+}
+
+fn append_audit_entry(
+    actor: Principal,
+    action: AuditAction,
+    details: String,
+    timestamp: u64,
+) -> AuditEntry {
+    AUDIT_LOG.with(|log| {
+        let mut log = log.borrow_mut();
+
+        let (seq, prev_hash) = match log.last_key_value() {
+            Some((last_seq, last)) => (last_seq + 1, last.hash),
+            None => (1, AUDIT_GENESIS_HASH.to_string()),
+        };
+        let hash = audit_entry_hash(seq, timestamp, &actor, &action, &details, &prev_hash);
+
+        let entry = AuditEntry {
+            seq,
+            timestamp,
+            actor,
+            action,
+            details,
+            prev_hash,
+            hash,
+        };
+        log.insert(seq, entry.clone());
+        entry
+    })
+}
+
+/// 감사 로그 조회 (순번 오름차순)
+pub fn get_audit_log(from_seq: Option<u64>, limit: u64) -> AuditPage {
+    let limit = limit as usize;
+
+    let mut entries: Vec<AuditEntry> = AUDIT_LOG.with(|log| {
+        log.borrow()
+            .range(from_seq.unwrap_or(0)..)
+            .take(limit + 1)
+            .map(|(_, entry)| entry)
+            .collect()
+    });
+
+    let next_seq = if entries.len() > limit {
+        entries.pop().map(|entry| entry.seq)
+    } else {
+        None
+    };
+
+    AuditPage { entries, next_seq }
+}
+
+/// 감사 로그 해시 체인 검증 (from_seq부터 limit개)
+pub fn verify_audit_chain(from_seq: Option<u64>, limit: u64) -> AuditVerification {
+    let from_seq = from_seq.unwrap_or(1).max(1);
+
+    AUDIT_LOG.with(|log| {
+        let log = log.borrow();
+
+        let mut expected_prev = match from_seq {
+            1 => AUDIT_GENESIS_HASH.to_string(),
+            _ => match log.get(&(from_seq - 1)) {
+                Some(previous) => previous.hash,
+                None => {
+                    return AuditVerification {
+                        valid: false,
+                        checked: 0,
+                        broken_at: Some(from_seq - 1),
+                        next_seq: None,
+                    }
+                }
+            },
+        };
+
+        let mut checked = 0;
+        let mut expected_seq = from_seq;
+        for (seq, entry) in log.range(from_seq..).take(limit as usize) {
+            let recomputed = audit_entry_hash(
+                entry.seq,
+                entry.timestamp,
+                &entry.actor,
+                &entry.action,
+                &entry.details,
+                &entry.prev_hash,
+            );
+            // 순번 누락, 키와 내용 불일치, 이전 해시 불일치, 내용 변조 확인
+            if seq != expected_seq
+                || entry.seq != seq
+                || entry.prev_hash != expected_prev
+                || entry.hash != recomputed
+            {
+                return AuditVerification {
+                    valid: false,
+                    checked,
+                    broken_at: Some(expected_seq.min(seq)),
+                    next_seq: None,
+                };
+            }
+
+            expected_prev = entry.hash;
+            expected_seq += 1;
+            checked += 1;
+        }
+
+        let has_more = log.contains_key(&expected_seq);
+        AuditVerification {
+            valid: true,
+            checked,
+            broken_at: None,
+            next_seq: has_more.then_some(expected_seq),
+        }
+    })
+}
+
+// =====================
+// 9) 사용자 언어 설정
+// =====================
+
+/// 사용자의 선호 언어 조회 (설정하지 않았으면 한국어)
+pub fn get_user_locale(user: Principal) -> Locale {
+    USER_LOCALES
+        .with(|locales| locales.borrow().get(&user))
+        .unwrap_or_default()
+}
+
+/// 사용자의 선호 언어 저장
+pub fn set_user_locale(user: Principal, locale: Locale) {
+    USER_LOCALES.with(|locales| {
+        locales.borrow_mut().insert(user, locale);
+    });
+}
+
+// =====================
+// 10) 데이터셋
+// =====================
+
+/// 데이터셋 이름 최대 길이
+const MAX_DATASET_NAME_LENGTH: usize = 100;
+
+/// 데이터셋 이름 검증 (업로드 전에 호출)
+pub fn validate_dataset_name(name: Option<&str>) -> Result<(), BackendError> {
+    match name.map(str::trim) {
+        Some("") => Err(BackendError::validation("dataset_name", "empty")),
+        Some(name) if name.chars().count() > MAX_DATASET_NAME_LENGTH => {
+            Err(BackendError::too_long(
+                "dataset_name",
+                name.chars().count(),
+                MAX_DATASET_NAME_LENGTH,
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// 데이터셋 생성 (records는 업로드 순서의 (레코드 ID, 레코드 해시), 해시로 머클 루트 계산)
+fn create_dataset(
+    owner: Principal,
+    name: Option<String>,
+    mime_type: &str,
+    schema_id: Option<u64>,
+    records: Vec<(u64, [u8; 32])>,
+    total_size: u64,
+    created_at: u64,
+) -> Dataset {
+    let (record_ids, record_hashes): (Vec<u64>, Vec<[u8; 32]>) = records.into_iter().unzip();
+    DATASETS.with(|datasets| {
+        let mut datasets = datasets.borrow_mut();
+        let id = datasets.last_key_value().map_or(1, |(id, _)| id + 1);
+        let name = name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| format!("dataset-{}", id));
+        let record_range = record_ids
+            .iter()
+            .min()
+            .zip(record_ids.iter().max())
+            .map(|(&start, &end)| RecordRange { start, end });
+
+        let dataset = Dataset {
+            id,
+            owner,
+            name,
+            mime_type: mime_type.to_string(),
+            schema_id,
+            record_ids,
+            record_range,
+            total_size,
+            root_hash: hex::encode(crate::merkle::merkle_root(&record_hashes)),
+            created_at,
+            lineage_id: None,
+            version: 1,
+            previous_version: None,
+            key_column: None,
+            changes: None,
+            fingerprint: Vec::new(),
+            minted: false,
+            quality: None,
+            pii_report: None,
+        };
+        datasets.insert(id, dataset.clone());
+        DATASET_RECORD_HASHES.with(|hashes| {
+            hashes.borrow_mut().insert(id, RecordHashes(record_hashes));
+        });
+        index_dataset_records(&dataset);
+        dataset
+    })
+}
+
+fn index_dataset_records(dataset: &Dataset) {
+    DATA_DATASETS.with(|index| {
+        let mut index = index.borrow_mut();
+        for &record_id in &dataset.record_ids {
+            index.insert((record_id, dataset.id), ());
         }
+    });
+}
+
+/// 인덱스가 비어 있으면 기존 데이터셋으로 (데이터 ID, 데이터셋 ID) 인덱스 구성
+fn rebuild_data_dataset_index() -> u64 {
+    let has_index = DATA_DATASETS.with(|index| !index.borrow().is_empty());
+    if has_index {
+        return 0;
+    }
+
+    let datasets: Vec<Dataset> =
+        DATASETS.with(|datasets| datasets.borrow().iter().map(|(_, d)| d).collect());
+    for dataset in &datasets {
+        index_dataset_records(dataset);
+    }
+
+    datasets.len() as u64
+}
+
+/// 레코드를 포함한 데이터셋들
+pub fn datasets_containing(data_id: u64) -> Vec<Dataset> {
+    let dataset_ids: Vec<u64> = DATA_DATASETS.with(|index| {
+        index
+            .borrow()
+            .range((data_id, 0)..=(data_id, u64::MAX))
+            .map(|((_, dataset_id), _)| dataset_id)
+            .collect()
+    });
+    dataset_ids.into_iter().filter_map(get_dataset).collect()
+}
+
+/// 데이터셋 조회 (레코드 ID 포함)
+pub fn get_dataset(dataset_id: u64) -> Option<Dataset> {
+    DATASETS.with(|datasets| datasets.borrow().get(&dataset_id))
+}
+
+/// 데이터셋 정보 조회
+pub fn get_dataset_info(dataset_id: u64) -> Option<DatasetInfo> {
+    get_dataset(dataset_id).map(to_dataset_info)
+}
+
+/// 데이터셋의 레코드를 업로드 순서대로 최대 limit개 디코딩 (헤더 행 포함)
+pub fn dataset_records(dataset: &Dataset, limit: usize) -> Vec<CborValue> {
+    dataset
+        .record_ids
+        .iter()
+        .take(limit)
+        .filter_map(|&data_id| dataset_record(data_id))
+        .collect()
+}
+
+/// 레코드 하나 디코딩 (쿼리처럼 필요한 만큼만 읽을 때 사용)
+pub fn dataset_record(data_id: u64) -> Option<CborValue> {
+    UPLOADED_DATA.with(|storage| {
+        let blob = storage.borrow().get(&data_id)?;
+        serde_cbor::from_slice(&blob.data).ok()
+    })
+}
+
+/// 데이터셋 품질 점수의 레코드 수 가중 평균 (품질 보고서가 없으면 None)
+pub fn datasets_quality_score(dataset_ids: &[u64]) -> Option<u8> {
+    let reports: Vec<QualityReport> = dataset_ids
+        .iter()
+        .filter_map(|&dataset_id| get_dataset(dataset_id)?.quality)
+        .collect();
+    if reports.is_empty() {
+        return None;
+    }
+
+    let rows: u64 = reports.iter().map(|report| report.row_count.max(1)).sum();
+    let weighted: u64 = reports
+        .iter()
+        .map(|report| u64::from(report.score) * report.row_count.max(1))
+        .sum();
+    Some((weighted / rows) as u8)
+}
+
+/// 사용자가 업로드한 데이터셋 목록 (최신순)
+pub fn list_datasets_by_owner(owner: Principal) -> Vec<DatasetInfo> {
+    DATASETS.with(|datasets| {
+        datasets
+            .borrow()
+            .iter()
+            .rev()
+            .filter(|(_, dataset)| dataset.owner == owner)
+            .map(|(_, dataset)| to_dataset_info(dataset))
+            .collect()
+    })
+}
+
+/// 데이터셋 레코드 해시 (생성 시 저장한 목록)
+///
+/// 목록이 없는 데이터셋은 블롭에서 다시 계산하고, 저장된 루트와 일치해야 한다.
+fn dataset_record_hashes(dataset: &Dataset) -> Result<Vec<[u8; 32]>, BackendError> {
+    if let Some(RecordHashes(hashes)) =
+        DATASET_RECORD_HASHES.with(|hashes| hashes.borrow().get(&dataset.id))
+    {
+        return Ok(hashes);
+    }
+
+    let hashes = UPLOADED_DATA.with(|storage| {
+        let storage = storage.borrow();
+        dataset
+            .record_ids
+            .iter()
+            .map(|&data_id| {
+                storage
+                    .get(&data_id)
+                    .map(|blob| calculate_data_hash(&blob.data).0)
+                    .ok_or_else(|| BackendError::not_found("data", data_id))
+            })
+            .collect::<Result<Vec<_>, _>>()
+    })?;
+
+    if hex::encode(crate::merkle::merkle_root(&hashes)) != dataset.root_hash {
+        return Err(BackendError::invalid_state("dataset_modified"));
+    }
+    Ok(hashes)
+}
+
+/// 레코드의 데이터셋 포함 증명
+pub fn get_record_proof(dataset_id: u64, record_id: u64) -> Result<RecordProof, BackendError> {
+    let dataset =
+        get_dataset(dataset_id).ok_or_else(|| BackendError::not_found("dataset", dataset_id))?;
+    let leaf_index = dataset
+        .record_ids
+        .iter()
+        .position(|&id| id == record_id)
+        .ok_or_else(|| BackendError::not_found("record", record_id))?;
+    let hashes = dataset_record_hashes(&dataset)?;
+
+    Ok(RecordProof {
+        dataset_id,
+        record_id,
+        leaf_index: leaf_index as u64,
+        record_count: hashes.len() as u64,
+        leaf_hash: hex::encode(crate::merkle::hash_leaf(&hashes[leaf_index])),
+        path: crate::merkle::to_proof_steps(crate::merkle::merkle_path(&hashes, leaf_index)),
+        root_hash: dataset.root_hash,
+    })
+}
+
+/// 데이터셋 민팅 시 토큰 메타데이터에 기록할 커밋먼트 (JSON 바이트)
+///
+/// 소유자만 민팅할 수 있고, 이미 민팅된 레코드가 있으면 거부한다.
+pub fn dataset_commitment(dataset_id: u64, requester: Principal) -> Result<Vec<u8>, BackendError> {
+    let dataset =
+        get_dataset(dataset_id).ok_or_else(|| BackendError::not_found("dataset", dataset_id))?;
+    if dataset.owner != requester {
+        return Err(BackendError::unauthorized("mint_dataset"));
+    }
+
+    let hashes = dataset_record_hashes(&dataset)?;
+    let minted: Vec<u64> = MINTED_HASHES.with(|minted| {
+        let minted = minted.borrow();
+        dataset
+            .record_ids
+            .iter()
+            .zip(&hashes)
+            .filter(|(_, hash)| minted.contains_key(&DataHash(**hash)))
+            .map(|(&data_id, _)| data_id)
+            .collect()
+    });
+    if !minted.is_empty() {
+        return Err(BackendError::AlreadyMinted { indices: minted });
+    }
+
+    let commitment = serde_json::json!({
+        "type": "dataset_commitment",
+        "dataset_id": dataset.id,
+        "merkle_root": dataset.root_hash,
+        "hash_algorithm": "sha256",
+        "record_count": dataset.record_ids.len(),
+    });
+    serde_json::to_vec(&commitment).map_err(BackendError::internal)
+}
+
+/// 레코드를 포함한 데이터셋들을 민팅됨으로 표시 (유사 데이터셋 검사 대상에 포함)
+fn mark_record_datasets_minted(data_id: u64) {
+    for mut dataset in datasets_containing(data_id) {
+        if dataset.minted {
+            continue;
+        }
+        dataset.minted = true;
+        DATASETS.with(|datasets| {
+            datasets.borrow_mut().insert(dataset.id, dataset);
+        });
+    }
+}
+
+/// 민팅이 끝난 데이터셋의 레코드를 민팅됨으로 표시
+fn mark_dataset_minted(dataset_id: u64, request_id: u64) {
+    let Some(mut dataset) = get_dataset(dataset_id) else {
+        return;
+    };
+    let hashes: Vec<DataHash> = UPLOADED_DATA.with(|storage| {
+        let storage = storage.borrow();
+        dataset
+            .record_ids
+            .iter()
+            .filter_map(|data_id| storage.get(data_id))
+            .map(|blob| calculate_data_hash(&blob.data))
+            .collect()
+    });
+    MINTED_HASHES.with(|minted| {
+        let mut minted = minted.borrow_mut();
+        for hash in hashes {
+            minted.insert(hash, request_id);
+        }
+    });
+
+    dataset.minted = true;
+    DATASETS.with(|datasets| {
+        datasets.borrow_mut().insert(dataset_id, dataset);
+    });
+}
+
+fn to_dataset_info(dataset: Dataset) -> DatasetInfo {
+    DatasetInfo {
+        id: dataset.id,
+        owner: dataset.owner,
+        name: dataset.name,
+        mime_type: dataset.mime_type,
+        schema: dataset.schema_id.and_then(get_schema),
+        record_range: dataset.record_range,
+        record_count: dataset.record_ids.len() as u64,
+        total_size: dataset.total_size,
+        root_hash: dataset.root_hash,
+        created_at: dataset.created_at,
+        lineage_id: dataset.lineage_id.unwrap_or(dataset.id),
+        version: dataset.version,
+        previous_version: dataset.previous_version,
+        key_column: dataset.key_column,
+        changes: dataset.changes,
+        minted: dataset.minted,
+        quality: dataset.quality,
+        pii_report: dataset.pii_report,
+    }
+}
+
+// =====================
+// 11) 데이터셋 버전
+// =====================
+
+/// 계보의 최신 버전 데이터셋 ID (새 버전이 없으면 계보의 첫 데이터셋)
+pub fn latest_dataset_id(dataset_id: u64) -> u64 {
+    let lineage = get_dataset(dataset_id).map_or(dataset_id, |dataset| dataset.lineage());
+    LATEST_VERSIONS
+        .with(|latest| latest.borrow().get(&lineage))
+        .unwrap_or(lineage)
+}
+
+/// 비어 있으면 데이터셋의 버전 연결로 계보별 최신 버전 구성
+///
+/// 데이터셋 ID는 게시 순서대로 증가하므로 계보마다 마지막 ID가 최신 버전이다.
+fn rebuild_latest_versions() -> u64 {
+    let has_versions = LATEST_VERSIONS.with(|latest| !latest.borrow().is_empty());
+    if has_versions {
+        return 0;
+    }
+
+    let versions: Vec<(u64, u64)> = DATASETS.with(|datasets| {
+        datasets
+            .borrow()
+            .iter()
+            .filter_map(|(id, dataset)| dataset.lineage_id.map(|lineage| (lineage, id)))
+            .collect()
+    });
+    LATEST_VERSIONS.with(|latest| {
+        let mut latest = latest.borrow_mut();
+        for &(lineage, id) in &versions {
+            latest.insert(lineage, id);
+        }
+    });
+
+    versions.len() as u64
+}
+
+/// 새 버전 게시 가능 여부 확인 (업로드 전에 호출)
+///
+/// 소유자만, 그리고 최신 버전 위에만 새 버전을 게시할 수 있다.
+pub fn validate_new_version(previous_id: u64, owner: Principal) -> Result<(), BackendError> {
+    let previous =
+        get_dataset(previous_id).ok_or_else(|| BackendError::not_found("dataset", previous_id))?;
+    if previous.owner != owner {
+        return Err(BackendError::unauthorized("publish_version"));
+    }
+    if latest_dataset_id(previous_id) != previous_id {
+        return Err(BackendError::invalid_state("not_latest_version"));
     }
     Ok(())
 }
+
+/// 새로 만든 데이터셋에 키 컬럼 등을 기록하고, 지정 시 기존 데이터셋의 다음 버전으로 연결
+pub fn publish_dataset_version(
+    mut dataset: Dataset,
+    previous_id: Option<u64>,
+    key_column: Option<String>,
+) -> Result<Dataset, BackendError> {
+    dataset.key_column = key_column;
+
+    if let Some(previous_id) = previous_id {
+        validate_new_version(previous_id, dataset.owner)?;
+        let previous = get_dataset(previous_id)
+            .ok_or_else(|| BackendError::not_found("dataset", previous_id))?;
+
+        dataset.lineage_id = Some(previous.lineage());
+        dataset.version = previous.version + 1;
+        dataset.previous_version = Some(previous.id);
+        dataset.changes = Some(diff_records(&previous, &dataset)?.changes);
+
+        LATEST_VERSIONS.with(|latest| {
+            latest.borrow_mut().insert(previous.lineage(), dataset.id);
+        });
+    }
+
+    DATASETS.with(|datasets| {
+        datasets.borrow_mut().insert(dataset.id, dataset.clone());
+    });
+    Ok(dataset)
+}
+
+/// 데이터셋 버전 이력 (최신 버전부터)
+pub fn get_dataset_versions(dataset_id: u64) -> Vec<DatasetVersionSummary> {
+    let mut versions = Vec::new();
+    let mut next = get_dataset(dataset_id).map(|_| latest_dataset_id(dataset_id));
+    while let Some(dataset) = next.and_then(get_dataset) {
+        next = dataset.previous_version;
+        versions.push(DatasetVersionSummary {
+            dataset_id: dataset.id,
+            version: dataset.version,
+            record_count: dataset.record_ids.len() as u64,
+            root_hash: dataset.root_hash,
+            created_at: dataset.created_at,
+            changes: dataset.changes,
+        });
+    }
+    versions
+}
+
+/// 두 버전 사이의 차이 (to를 생략하면 최신 버전과 비교)
+///
+/// 두 버전 중 하나라도 can_read가 허용해야 한다 (이전 버전 구매자가 최신 버전과 비교하는 경우 포함).
+pub fn get_dataset_diff(
+    from_dataset_id: u64,
+    to_dataset_id: Option<u64>,
+    can_read: impl Fn(&Dataset) -> bool,
+) -> Result<DatasetDiff, BackendError> {
+    let from = get_dataset(from_dataset_id)
+        .ok_or_else(|| BackendError::not_found("dataset", from_dataset_id))?;
+    let to_dataset_id = to_dataset_id.unwrap_or_else(|| latest_dataset_id(from_dataset_id));
+    let to = get_dataset(to_dataset_id)
+        .ok_or_else(|| BackendError::not_found("dataset", to_dataset_id))?;
+    if from.lineage() != to.lineage() {
+        return Err(BackendError::invalid_state("different_lineage"));
+    }
+    if !can_read(&from) && !can_read(&to) {
+        return Err(BackendError::unauthorized("get_dataset_diff"));
+    }
+    diff_records(&from, &to)
+}
+
+/// 두 데이터셋의 레코드 차이 계산
+fn diff_records(from: &Dataset, to: &Dataset) -> Result<DatasetDiff, BackendError> {
+    let key_column = to.key_column.as_deref().or(from.key_column.as_deref());
+    let (added, removed, changed) = diff_keyed_records(
+        &record_keys(from, key_column)?,
+        &record_keys(to, key_column)?,
+    );
+
+    Ok(DatasetDiff {
+        from_dataset_id: from.id,
+        to_dataset_id: to.id,
+        from_version: from.version,
+        to_version: to.version,
+        changes: VersionChanges {
+            added: added.len() as u64,
+            removed: removed.len() as u64,
+            changed: changed.len() as u64,
+        },
+        added,
+        removed,
+        changed,
+    })
+}
+
+/// (레코드 ID, 키 컬럼 값) 목록 (키 컬럼이 없으면 값은 None)
+fn record_keys(
+    dataset: &Dataset,
+    key_column: Option<&str>,
+) -> Result<Vec<(u64, Option<CborValue>)>, BackendError> {
+    let Some(key_column) = key_column else {
+        return Ok(dataset.record_ids.iter().map(|&id| (id, None)).collect());
+    };
+    let key = CborValue::Text(key_column.to_string());
+
+    UPLOADED_DATA.with(|storage| {
+        let storage = storage.borrow();
+        dataset
+            .record_ids
+            .iter()
+            .map(|&data_id| {
+                let blob = storage
+                    .get(&data_id)
+                    .ok_or_else(|| BackendError::not_found("data", data_id))?;
+                let value = match serde_cbor::from_slice(&blob.data) {
+                    Ok(CborValue::Map(mut fields)) => fields.remove(&key),
+                    _ => None,
+                };
+                Ok((data_id, value))
+            })
+            .collect()
+    })
+}
+
+/// 레코드 차이 계산 (추가, 삭제, 변경)
+///
+/// 같은 내용은 업로드 시 같은 ID로 저장되므로 ID가 같으면 변경 없음으로 본다.
+/// ID가 다르지만 키 컬럼 값이 같은 레코드 쌍은 변경으로 짝짓는다.
+fn diff_keyed_records(
+    from: &[(u64, Option<CborValue>)],
+    to: &[(u64, Option<CborValue>)],
+) -> (Vec<u64>, Vec<u64>, Vec<ChangedRecord>) {
+    let from_ids: std::collections::HashSet<u64> = from.iter().map(|(id, _)| *id).collect();
+    let to_ids: std::collections::HashSet<u64> = to.iter().map(|(id, _)| *id).collect();
+
+    let mut removed_by_key: std::collections::BTreeMap<&CborValue, u64> = from
+        .iter()
+        .filter(|(id, _)| !to_ids.contains(id))
+        .filter_map(|(id, key)| key.as_ref().map(|key| (key, *id)))
+        .collect();
+
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    for (id, key) in to.iter().filter(|(id, _)| !from_ids.contains(id)) {
+        match key.as_ref().and_then(|key| removed_by_key.remove(key)) {
+            Some(previous_id) => changed.push(ChangedRecord {
+                previous_id,
+                current_id: *id,
+            }),
+            None => added.push(*id),
+        }
+    }
+
+    let matched: std::collections::HashSet<u64> =
+        changed.iter().map(|record| record.previous_id).collect();
+    let removed = from
+        .iter()
+        .map(|(id, _)| *id)
+        .filter(|id| !to_ids.contains(id) && !matched.contains(id))
+        .collect();
+
+    (added, removed, changed)
+}
+
+// =====================
+// 12) 유사 데이터셋 탐지
+// =====================
+
+/// 다른 사용자가 민팅한 데이터셋 중 경고 기준 이상으로 유사한 것 (유사도 내림차순)
+pub fn find_similar_minted_datasets(fingerprint: &[u64], owner: Principal) -> Vec<SimilarityMatch> {
+    let mut matches: Vec<SimilarityMatch> = DATASETS.with(|datasets| {
+        datasets
+            .borrow()
+            .iter()
+            .filter(|(_, dataset)| dataset.minted && dataset.owner != owner)
+            .filter_map(|(id, dataset)| {
+                let similarity_percent =
+                    crate::fingerprint::similarity_percent(fingerprint, &dataset.fingerprint);
+                (similarity_percent >= crate::fingerprint::WARN_SIMILARITY_PERCENT).then_some(
+                    SimilarityMatch {
+                        dataset_id: id,
+                        owner: dataset.owner,
+                        similarity_percent,
+                    },
+                )
+            })
+            .collect()
+    });
+    matches.sort_by_key(|found| std::cmp::Reverse(found.similarity_percent));
+    matches
+}
+
+/// 거부 기준 이상으로 유사하면 에러, 아니면 경고 목록 반환
+pub fn check_near_duplicates(
+    fingerprint: &[u64],
+    owner: Principal,
+) -> Result<Vec<SimilarityMatch>, BackendError> {
+    let matches = find_similar_minted_datasets(fingerprint, owner);
+    match matches.first() {
+        Some(top) if top.similarity_percent >= crate::fingerprint::REJECT_SIMILARITY_PERCENT => {
+            Err(BackendError::NearDuplicate {
+                dataset_id: top.dataset_id,
+                similarity_percent: top.similarity_percent,
+            })
+        }
+        _ => Ok(matches),
+    }
+}
+
+// =====================
+// 13) 개인정보 해시 키
+// =====================
+
+/// 개인정보 해시(HMAC) 키 (아직 생성되지 않았으면 None)
+pub fn pii_hash_key() -> Option<[u8; 32]> {
+    let key = PII_HASH_KEY.with(|cell| *cell.borrow().get());
+    (key != [0; 32]).then_some(key)
+}
+
+/// 키가 없으면 raw_rand로 생성하도록 예약 (init/post_upgrade에서는 비동기 호출을 할 수 없음)
+pub fn ensure_pii_hash_key() {
+    if pii_hash_key().is_some() {
+        return;
+    }
+    ic_cdk_timers::set_timer(std::time::Duration::ZERO, || {
+        ic_cdk::spawn(async {
+            match ic_cdk::api::management_canister::main::raw_rand().await {
+                Ok((bytes,)) if bytes.len() >= 32 && pii_hash_key().is_none() => {
+                    let mut key = [0u8; 32];
+                    key.copy_from_slice(&bytes[..32]);
+                    PII_HASH_KEY
+                        .with(|cell| cell.borrow_mut().set(key))
+                        .expect("Failed to store PII hash key");
+                    ic_cdk::println!("PII hash key generated");
+                }
+                Ok(_) => {}
+                Err((code, message)) => {
+                    ic_cdk::println!("Failed to generate PII hash key: {:?} {}", code, message);
+                    ensure_pii_hash_key();
+                }
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_keyed_records_pairs_changed_rows() {
+        let key = |value: &str| Some(CborValue::Text(value.to_string()));
+        let from = vec![(1, key("a")), (2, key("b")), (3, key("c"))];
+        let to = vec![(1, key("a")), (4, key("b")), (5, key("d"))];
+
+        let (added, removed, changed) = diff_keyed_records(&from, &to);
+        assert_eq!(added, vec![5]);
+        assert_eq!(removed, vec![3]);
+        assert_eq!(
+            changed,
+            vec![ChangedRecord {
+                previous_id: 2,
+                current_id: 4
+            }]
+        );
+
+        let unkeyed = |records: &[(u64, Option<CborValue>)]| {
+            records
+                .iter()
+                .map(|(id, _)| (*id, None))
+                .collect::<Vec<_>>()
+        };
+        let (added, removed, changed) = diff_keyed_records(&unkeyed(&from), &unkeyed(&to));
+        assert_eq!((added, removed), (vec![4, 5], vec![2, 3]));
+        assert!(changed.is_empty());
+    }
+
+    #[test]
+    fn test_audit_chain_detects_tampering() {
+        let actor = Principal::from_slice(&[1]);
+        for i in 0..3 {
+            append_audit_entry(
+                actor,
+                AuditAction::DataUploaded,
+                format!("data_ids=[{}]", i),
+                i,
+            );
+        }
+
+        let verification = verify_audit_chain(None, 10);
+        assert!(verification.valid);
+        assert_eq!(verification.checked, 3);
+
+        let page = get_audit_log(None, 2);
+        assert_eq!(page.entries.len(), 2);
+        assert_eq!(page.entries[1].prev_hash, page.entries[0].hash);
+        assert_eq!(page.next_seq, Some(3));
+
+        // 두 번째 항목 내용 변조
+        AUDIT_LOG.with(|log| {
+            let mut log = log.borrow_mut();
+            let mut entry = log.get(&2).unwrap();
+            entry.details = "data_ids=[99]".to_string();
+            log.insert(2, entry);
+        });
+
+        let verification = verify_audit_chain(None, 10);
+        assert!(!verification.valid);
+        assert_eq!(verification.broken_at, Some(2));
+    }
+
+    #[test]
+    fn test_shared_record_cannot_be_deleted() {
+        // 카운터 초기화 로그(println)는 네이티브 테스트에서 호출할 수 없으므로 직접 초기화
+        UPLOAD_COUNTER.with(|counter| {
+            *counter.borrow_mut() = Some(StableCell::init(get_upload_counter_memory(), 0).unwrap());
+        });
+        let records =
+            vec![serde_cbor::value::to_value(serde_json::json!({"city": "Seoul"})).unwrap()];
+        let schema = crate::schema::infer_schema(&records);
+        let alice = Principal::from_slice(&[1]);
+        let bob = Principal::from_slice(&[2]);
+
+        let first = store_upload_data_at(
+            alice,
+            None,
+            records.clone(),
+            "application/json",
+            schema.clone(),
+            1,
+        )
+        .unwrap();
+        let second =
+            store_upload_data_at(bob, None, records, "application/json", schema, 2).unwrap();
+        assert_eq!(first.record_ids, second.record_ids);
+
+        let data_id = first.record_ids[0];
+        assert_eq!(
+            delete_uploaded_data(data_id),
+            Err(BackendError::invalid_state("data_in_use"))
+        );
+        assert!(get_data_blob(data_id).is_some());
+        assert!(get_record_proof(second.id, data_id).is_ok());
+    }
+}
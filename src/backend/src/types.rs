@@ -14,12 +14,76 @@ use std::borrow::Cow;
 pub struct UploadRequest {
     pub content: Vec<u8>,
     pub mime_type: String,
+    pub json_schema: Option<String>, // 레코드를 검증할 JSON Schema (선택)
+    pub csv_options: Option<CsvOptions>, // text/csv 파싱 옵션 (선택)
+    pub excel_options: Option<ExcelOptions>, // .xlsx 파싱 옵션 (선택)
+    pub asset_options: Option<AssetOptions>, // 이미지/PDF/압축 파일 메타데이터 (선택)
+    pub name: Option<String>,        // 데이터셋 이름 (기본: 파일 이름 또는 "dataset-<id>")
+    pub previous_version: Option<u64>, // 지정 시 이 데이터셋의 새 버전으로 게시
+    pub key_column: Option<String>,  // 버전 간 변경 레코드를 찾을 키 컬럼 (선택)
+    pub pii_mode: Option<PiiMode>,   // 개인정보 발견 시 처리 방식 (기본: 거부)
+}
+
+/// CSV 구분자
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum CsvDelimiter {
+    Comma,
+    Tab,
+    Semicolon,
+    Pipe,
+}
+
+/// 업로드 텍스트 인코딩 (EUC-KR/CP949는 공공데이터 CSV에서 흔함)
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum TextEncoding {
+    Utf8,
+    EucKr,
+    Cp949,
+}
+
+/// CSV 파싱 옵션 (생략한 항목은 기본값 사용)
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct CsvOptions {
+    pub has_header: Option<bool>,        // None이면 첫 행을 보고 자동 판단
+    pub delimiter: Option<CsvDelimiter>, // 기본 Comma
+    pub quote: Option<String>,           // 한 글자 ASCII, 기본 "
+    pub encoding: Option<TextEncoding>,  // 기본 Utf8
+}
+
+/// XLSX 파싱 옵션 (생략한 항목은 기본값 사용)
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct ExcelOptions {
+    pub sheet: Option<String>,    // 시트 이름, 기본 첫 시트
+    pub has_header: Option<bool>, // None이면 첫 행을 보고 자동 판단
+}
+
+/// 바이너리 자산 업로드 옵션 (클라이언트 제공 메타데이터)
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+pub struct AssetOptions {
+    pub file_name: Option<String>,    // 원본 파일 이름 (최대 255자)
+    pub thumbnail: Option<Vec<u8>>,   // PNG/JPEG/GIF/WebP 썸네일 (최대 64KB)
+    pub preview_text: Option<String>, // 목록에 보일 설명 (최대 1,000자)
+}
+
+/// 저장된 바이너리 자산 정보
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct AssetInfo {
+    pub file_name: Option<String>,
+    pub width: Option<u32>, // 이미지일 때 헤더에서 읽은 크기
+    pub height: Option<u32>,
+    pub thumbnail: Option<Vec<u8>>,
+    pub thumbnail_mime_type: Option<String>,
+    pub preview_text: Option<String>,
 }
 
 /// 업로드 응답
-#[derive(CandidType)]
+#[derive(CandidType, Deserialize, Debug)]
 pub struct UploadResponse {
-    pub data: Vec<Vec<u8>>, // 데이터 ID들을 바이트로 변환
+    pub dataset_id: u64,
+    pub record_ids: Vec<u64>, // 업로드 순서대로의 레코드 ID (중복 레코드는 기존 ID)
+    pub root_hash: String,    // 레코드 해시의 머클 루트 (hex)
+    pub similar_datasets: Vec<SimilarityMatch>, // 유사한 민팅 데이터셋 경고 (거부 기준 미만)
+    pub pii_report: Option<PiiReport>, // 개인정보 탐지 결과 (바이너리 자산은 None)
 }
 
 /// 저장된 데이터 정보
@@ -28,6 +92,10 @@ pub struct DataBlob {
     pub data: Vec<u8>,
     pub mime_type: String,
     pub timestamp: u64,
+    #[serde(default)]
+    pub schema_id: Option<u64>, // 업로드 시 추론된 스키마
+    #[serde(default)]
+    pub asset: Option<AssetInfo>, // 단일 블롭으로 저장된 바이너리 자산
 }
 
 /// 데이터 정보 (조회용)
@@ -37,6 +105,8 @@ pub struct DataInfo {
     pub mime_type: String,
     pub timestamp: u64,
     pub size: u64,
+    pub schema: Option<DatasetSchema>,
+    pub asset: Option<AssetInfo>,
 }
 
 // =====================
@@ -49,6 +119,8 @@ pub struct MintRequest {
     pub owner: Option<Principal>,
     pub cid: String,
     pub metadata: Vec<Vec<u8>>,
+    #[serde(default)]
+    pub dataset_id: Option<u64>, // 지정 시 데이터셋 머클 루트를 메타데이터에 기록
 }
 
 /// 민팅 응답
@@ -167,3 +239,527 @@ impl Storable for MintRequestData {
     const BOUND: ic_stable_structures::storable::Bound =
         ic_stable_structures::storable::Bound::Unbounded;
 }
+
+// =====================
+// 5) 감사 로그 관련 타입
+// =====================
+
+/// 감사 대상 작업
+#[derive(CandidType, Deserialize, Serialize, Clone, PartialEq, Debug)]
+pub enum AuditAction {
+    DataUploaded,
+    DataDeleted,
+    MintRequested,
+    MintStatusChanged,
+    ListingCreated,
+    ListingUpdated,
+    ListingDeleted,
+    ListingSold,
+    AdminAction,
+}
+
+/// 감사 로그 항목 (이전 항목 해시로 연결)
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct AuditEntry {
+    pub seq: u64,
+    pub timestamp: u64,
+    pub actor: Principal,
+    pub action: AuditAction,
+    pub details: String,
+    pub prev_hash: String, // 이전 항목 해시 (hex)
+    pub hash: String,      // 이 항목 해시 (hex)
+}
+
+impl Storable for AuditEntry {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(serde_cbor::to_vec(self).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).unwrap()
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound =
+        ic_stable_structures::storable::Bound::Unbounded;
+}
+
+/// 감사 로그 페이지 (순번 오름차순)
+#[derive(CandidType, Deserialize)]
+pub struct AuditPage {
+    pub entries: Vec<AuditEntry>,
+    pub next_seq: Option<u64>,
+}
+
+/// 감사 로그 해시 체인 검증 결과
+#[derive(CandidType, Deserialize, Debug)]
+pub struct AuditVerification {
+    pub valid: bool,
+    pub checked: u64,
+    pub broken_at: Option<u64>, // 처음으로 체인이 끊긴 순번
+    pub next_seq: Option<u64>,  // 이어서 검증할 순번
+}
+
+// =====================
+// 6) 인증 응답 타입
+// =====================
+
+/// 인증된 값 (payload의 SHA-256이 인증 트리 leaf와 일치해야 함)
+#[derive(CandidType, Deserialize)]
+pub struct CertifiedValue {
+    pub id: u64,
+    pub payload: Option<Vec<u8>>, // 판매글/민팅 상태는 CBOR, 업로드 데이터는 원본 바이트
+}
+
+/// 인증 쿼리 응답
+///
+/// 클라이언트는 certificate의 certified_data가 witness 루트 해시와 같은지,
+/// 각 값이 witness의 /<label>/<id(8바이트 big-endian)> 경로 leaf와 일치하는지 확인한다.
+#[derive(CandidType, Deserialize)]
+pub struct CertifiedResponse {
+    pub label: String,
+    pub values: Vec<CertifiedValue>,
+    pub certificate: Option<Vec<u8>>, // 쿼리 호출에서만 제공
+    pub witness: Vec<u8>,             // CBOR 인코딩된 해시 트리
+    pub next_cursor: Option<String>,
+}
+
+// =====================
+// 7) HTTP 게이트웨이 타입
+// =====================
+
+pub type HeaderField = (String, String);
+
+/// HTTP 게이트웨이 요청
+#[derive(CandidType, Deserialize, Clone)]
+pub struct HttpRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<HeaderField>,
+    pub body: Vec<u8>,
+    pub certificate_version: Option<u16>,
+}
+
+/// HTTP 게이트웨이 응답
+#[derive(CandidType, Deserialize, Clone)]
+pub struct HttpResponse {
+    pub status_code: u16,
+    pub headers: Vec<HeaderField>,
+    pub body: Vec<u8>,
+    pub streaming_strategy: Option<StreamingStrategy>,
+    pub upgrade: Option<bool>,
+}
+
+/// 스트리밍 다음 조각 위치
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq)]
+pub struct StreamingCallbackToken {
+    pub data_id: u64,
+    pub chunk_index: u64,
+}
+
+/// 스트리밍 콜백 응답
+#[derive(CandidType, Deserialize, Clone)]
+pub struct StreamingCallbackHttpResponse {
+    pub body: Vec<u8>,
+    pub token: Option<StreamingCallbackToken>,
+}
+
+candid::define_function!(pub StreamingCallback : (StreamingCallbackToken) -> (StreamingCallbackHttpResponse) query);
+
+/// 큰 본문 스트리밍 방식
+#[derive(CandidType, Deserialize, Clone)]
+pub enum StreamingStrategy {
+    Callback {
+        callback: StreamingCallback,
+        token: StreamingCallbackToken,
+    },
+}
+
+// =====================
+// 8) 스키마 관련 타입
+// =====================
+
+/// 컬럼 타입
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ColumnType {
+    Integer,
+    Float,
+    Bool,
+    Date,
+    String,
+    Array,
+    Object,
+}
+
+/// 컬럼 스키마
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct ColumnSchema {
+    pub name: String,
+    pub column_type: ColumnType,
+    pub nullable: bool, // 값이 비어 있거나 null인 레코드가 있었는지
+}
+
+/// 업로드 단위로 추론된 데이터셋 스키마
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct DatasetSchema {
+    pub columns: Vec<ColumnSchema>,
+    pub record_count: u64,
+    pub has_header: bool,            // CSV 첫 행을 헤더로 판단했는지
+    pub json_schema: Option<String>, // 업로더가 지정한 JSON Schema (검증 통과)
+}
+
+impl Storable for DatasetSchema {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(serde_cbor::to_vec(self).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<'_, [u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).unwrap()
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound =
+        ic_stable_structures::storable::Bound::Unbounded;
+}
+
+// =====================
+// 9) 데이터셋 관련 타입
+// =====================
+
+/// 레코드 ID 범위 (양 끝 포함)
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct RecordRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+/// 업로드 한 번으로 만들어지는 데이터셋 (내부 저장용)
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct Dataset {
+    pub id: u64,
+    pub owner: Principal,
+    pub name: String,
+    pub mime_type: String,
+    pub schema_id: Option<u64>,
+    pub record_ids: Vec<u64>, // 업로드 순서 (머클 트리의 잎 순서)
+    pub record_range: Option<RecordRange>, // 새로 저장된 레코드는 연속 ID
+    pub total_size: u64,      // 레코드 바이트 합계
+    pub root_hash: String,    // 레코드 해시의 머클 루트 (hex)
+    pub created_at: u64,
+    #[serde(default)]
+    pub lineage_id: Option<u64>, // 첫 버전 데이터셋 ID (첫 버전이면 None)
+    #[serde(default = "first_version")]
+    pub version: u32,
+    #[serde(default)]
+    pub previous_version: Option<u64>, // 직전 버전 데이터셋 ID
+    #[serde(default)]
+    pub key_column: Option<String>,
+    #[serde(default)]
+    pub changes: Option<VersionChanges>, // 직전 버전 대비 변경 개수
+    #[serde(default)]
+    pub fingerprint: Vec<u64>, // 정규화된 레코드의 MinHash 서명 (유사 데이터셋 탐지)
+    #[serde(default)]
+    pub minted: bool, // 데이터셋 단위로 민팅 완료됨
+    #[serde(default)]
+    pub quality: Option<QualityReport>, // 레코드 데이터셋의 품질 지표 (바이너리 자산은 None)
+    #[serde(default)]
+    pub pii_report: Option<PiiReport>, // 업로드 시 개인정보 탐지 결과
+}
+
+fn first_version() -> u32 {
+    1
+}
+
+impl Dataset {
+    /// 버전 계보 ID (첫 버전 데이터셋 ID)
+    pub fn lineage(&self) -> u64 {
+        self.lineage_id.unwrap_or(self.id)
+    }
+}
+
+/// 버전 간 레코드 변경 개수
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct VersionChanges {
+    pub added: u64,
+    pub removed: u64,
+    pub changed: u64, // 키 컬럼이 같고 내용이 다른 레코드
+}
+
+/// 데이터셋 버전 이력 항목
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct DatasetVersionSummary {
+    pub dataset_id: u64,
+    pub version: u32,
+    pub record_count: u64,
+    pub root_hash: String,
+    pub created_at: u64,
+    pub changes: Option<VersionChanges>,
+}
+
+/// 내용이 바뀐 레코드 (이전 버전 ID -> 새 버전 ID)
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ChangedRecord {
+    pub previous_id: u64,
+    pub current_id: u64,
+}
+
+/// 두 버전 사이의 레코드 차이
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq)]
+pub struct DatasetDiff {
+    pub from_dataset_id: u64,
+    pub to_dataset_id: u64,
+    pub from_version: u32,
+    pub to_version: u32,
+    pub added: Vec<u64>,   // 새 버전에만 있는 레코드 ID
+    pub removed: Vec<u64>, // 이전 버전에만 있는 레코드 ID
+    pub changed: Vec<ChangedRecord>,
+    pub changes: VersionChanges,
+}
+
+/// 데이터셋 정보 (조회용, 레코드 ID 목록 제외)
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct DatasetInfo {
+    pub id: u64,
+    pub owner: Principal,
+    pub name: String,
+    pub mime_type: String,
+    pub schema: Option<DatasetSchema>,
+    pub record_range: Option<RecordRange>,
+    pub record_count: u64,
+    pub total_size: u64,
+    pub root_hash: String,
+    pub created_at: u64,
+    pub lineage_id: u64,
+    pub version: u32,
+    pub previous_version: Option<u64>,
+    pub key_column: Option<String>,
+    pub changes: Option<VersionChanges>,
+    pub minted: bool,
+    pub quality: Option<QualityReport>,
+    pub pii_report: Option<PiiReport>,
+}
+
+/// 다른 사용자가 민팅한 데이터셋과의 유사도
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq)]
+pub struct SimilarityMatch {
+    pub dataset_id: u64,
+    pub owner: Principal,
+    pub similarity_percent: u8, // MinHash 추정 자카드 유사도 (0-100)
+}
+
+impl Storable for Dataset {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(serde_cbor::to_vec(self).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<'_, [u8]>) -> Self {
+        serde_cbor::from_slice(&bytes).unwrap()
+    }
+
+    const BOUND: ic_stable_structures::storable::Bound =
+        ic_stable_structures::storable::Bound::Unbounded;
+}
+
+/// 머클 증명 단계에서 형제 노드의 위치
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ProofSide {
+    Left,
+    Right,
+}
+
+/// 머클 증명 한 단계 (잎에서 루트 방향)
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq)]
+pub struct ProofStep {
+    pub hash: String, // 형제 노드 해시 (hex)
+    pub side: ProofSide,
+}
+
+/// 레코드 포함 증명
+///
+/// `SHA-256(0x00 || SHA-256(레코드 바이트))`가 `leaf_hash`이고, `path`를 따라 올라간 값이
+/// `root_hash`와 같으면 레코드가 데이터셋에 포함되어 있음을 뜻한다.
+/// 경로 길이와 방향은 `leaf_index`와 `record_count`로 정해진다.
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq)]
+pub struct RecordProof {
+    pub dataset_id: u64,
+    pub record_id: u64,
+    pub leaf_index: u64,
+    pub record_count: u64,
+    pub leaf_hash: String,
+    pub path: Vec<ProofStep>,
+    pub root_hash: String,
+}
+
+// =====================
+// 10) 품질 관련 타입
+// =====================
+
+/// 컬럼별 품질 지표
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct ColumnQuality {
+    pub name: String,
+    pub completeness_percent: u8,     // 비어 있지 않은 값 비율
+    pub type_consistency_percent: u8, // 가장 많은 타입에 속한 값 비율
+    pub filled_count: u64,
+    pub consistent_count: u64,
+    pub numeric_count: u64, // 숫자 컬럼에서 숫자로 읽힌 값 개수
+    pub outlier_count: u64, // IQR 1.5배 범위를 벗어난 값 개수
+}
+
+/// 업로드 시 계산한 데이터셋 품질 보고서
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct QualityReport {
+    pub score: u8, // 종합 점수 (0-100)
+    pub row_count: u64,
+    pub completeness_percent: u8,
+    pub type_consistency_percent: u8,
+    pub duplicate_rate_percent: u8,
+    pub outlier_ratio_percent: u8,
+    pub columns: Vec<ColumnQuality>,
+}
+
+// =====================
+// 11) 미리보기 관련 타입
+// =====================
+
+/// 판매글 미리보기 생성 옵션 (판매자 지정)
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, Default)]
+pub struct PreviewOptions {
+    pub row_limit: Option<u32>, // 미리보기 행 수 (기본 5, 최대 20)
+    pub visible_columns: Option<Vec<String>>, // 공개할 컬럼 (기본: 전체)
+    pub masked_columns: Option<Vec<String>>, // 값 일부를 가리고 통계를 숨길 컬럼
+}
+
+/// 컬럼 요약 통계
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct ColumnSummary {
+    pub name: String,
+    pub column_type: ColumnType,
+    pub filled_count: u64,
+    pub distinct_count: u64,
+    pub min: Option<String>, // 숫자/날짜 컬럼만 (가린 컬럼은 None)
+    pub max: Option<String>,
+    pub mean: Option<f64>, // 숫자 컬럼만
+    pub masked: bool,
+}
+
+/// 업로드 데이터에서 자동 생성한 미리보기
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct DataPreview {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>, // 앞쪽 레코드 (가린 컬럼은 마스킹된 값)
+    pub column_stats: Vec<ColumnSummary>,
+    pub total_rows: u64,
+    pub sampled_rows: u64, // 통계 계산에 사용한 레코드 수
+}
+
+// =====================
+// 12) 개인정보 관련 타입
+// =====================
+
+/// 탐지하는 개인정보 종류
+#[derive(
+    CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub enum PiiKind {
+    Email,
+    Phone,
+    ResidentRegistrationNumber, // 주민등록번호
+    CardNumber,
+    IpAddress,
+}
+
+/// 개인정보가 발견되었을 때의 처리 방식
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum PiiMode {
+    #[default]
+    Reject, // 업로드 거부
+    Warn,   // 그대로 저장하고 보고서만 반환
+    Redact, // 일치한 부분을 "[EMAIL]" 등으로 치환
+    Hash,   // 일치한 부분을 캐니스터 키의 HMAC-SHA256으로 치환 (같은 값은 같은 해시)
+}
+
+/// 필드별 개인정보 탐지 결과
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct PiiFinding {
+    pub field: String, // 맵 레코드는 키 경로, 배열 레코드는 "[열 번호]"
+    pub kind: PiiKind,
+    pub record_count: u64,
+    pub sample_records: Vec<u64>, // 발견된 레코드 인덱스 (최대 5개)
+}
+
+/// 업로드 개인정보 탐지 보고서
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct PiiReport {
+    pub mode: PiiMode,
+    pub records_affected: u64,
+    pub findings: Vec<PiiFinding>,
+}
+
+// =====================
+// 13) 데이터셋 쿼리 관련 타입
+// =====================
+
+/// 쿼리 값 (레코드 값과 필터 비교 값)
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub enum QueryValue {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    Text(String),
+}
+
+/// 필터 비교 연산자
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+}
+
+/// 컬럼 필터 (숫자끼리는 수치로, 문자열/날짜는 사전순으로 비교)
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct QueryFilter {
+    pub column: String,
+    pub op: FilterOp,
+    pub value: QueryValue,
+}
+
+/// 집계 함수
+#[derive(CandidType, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum AggregateFunction {
+    Count, // 컬럼 지정 시 값이 있는 행 수
+    Sum,
+    Avg,
+}
+
+/// 집계 항목
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct QueryAggregate {
+    pub function: AggregateFunction,
+    pub column: Option<String>, // Sum/Avg는 필수
+}
+
+/// 데이터셋 쿼리 요청
+///
+/// 필터는 모두 만족해야 한다(AND). 집계나 그룹을 지정하면 행 대신 그룹별 집계를 반환한다.
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct DatasetQuery {
+    pub dataset_id: u64,
+    pub columns: Option<Vec<String>>, // 반환할 컬럼 (기본: 전체, 집계와 함께 쓸 수 없음)
+    pub filters: Option<Vec<QueryFilter>>,
+    pub group_by: Option<Vec<String>>,
+    pub aggregates: Option<Vec<QueryAggregate>>, // 그룹만 지정하면 Count
+    pub limit: Option<u32>,                      // 기본 100, 최대 1000
+    pub offset: Option<u32>,
+}
+
+/// 데이터셋 쿼리 결과
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<QueryValue>>,
+    pub has_more: bool, // offset+limit 뒤에 행(또는 그룹)이 더 있는지
+}
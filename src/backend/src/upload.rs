@@ -1,30 +1,88 @@
 // backend/src/upload.rs
 
+use crate::error::BackendError;
+use crate::schema;
+use crate::types::{CsvDelimiter, CsvOptions, ExcelOptions, TextEncoding};
+use calamine::{Data, Reader, Xlsx};
 use csv::ReaderBuilder;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use quick_xml::events::{BytesStart, Event};
 use serde_cbor::value::{to_value, Value as CborValue};
 use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+use std::io::Cursor;
 use std::str;
 
+/// 지원하는 표 형식 MIME 타입
+pub const MIME_JSON: &str = "application/json";
+pub const MIME_CSV: &str = "text/csv";
+pub const MIME_NDJSON: &str = "application/x-ndjson";
+pub const MIME_XML: &str = "application/xml";
+pub const MIME_PARQUET: &str = "application/vnd.apache.parquet";
+pub const MIME_XLSX: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+/// 레코드로 파싱하는 표 형식 MIME 타입 목록
+pub const TABULAR_MIME_TYPES: [&str; 6] = [
+    MIME_JSON,
+    MIME_CSV,
+    MIME_NDJSON,
+    MIME_XML,
+    MIME_PARQUET,
+    MIME_XLSX,
+];
+
+/// 파싱된 업로드 데이터
+pub struct ParsedUpload {
+    pub records: Vec<CborValue>,
+    /// 컬럼 순서 (CSV/Parquet/XLSX처럼 맵 레코드로 바뀌어 순서를 잃는 형식만)
+    pub columns: Option<Vec<String>>,
+    pub has_header: bool,
+}
+
+impl ParsedUpload {
+    fn records(records: Vec<CborValue>) -> Self {
+        ParsedUpload {
+            records,
+            columns: None,
+            has_header: false,
+        }
+    }
+}
+
 /// 업로드 데이터 파싱
-pub fn upload_data(content: Vec<u8>, mime_type: &str) -> Result<Vec<CborValue>, String> {
+///
+/// 모든 형식은 같은 레코드 모델(`Vec<CborValue>`)로 정규화되어
+/// `validate_data`와 `store_upload_data`에서 동일하게 처리된다.
+pub fn upload_data(
+    content: Vec<u8>,
+    mime_type: &str,
+    csv_options: Option<&CsvOptions>,
+    excel_options: Option<&ExcelOptions>,
+) -> Result<ParsedUpload, BackendError> {
     // 빈 데이터 체크
     if content.is_empty() {
-        return Err("업로드된 데이터가 비어 있습니다".to_string());
+        return Err(BackendError::validation("content", "empty"));
     }
 
     match mime_type {
-        "application/json" => parse_json_data(content),
-        "text/csv" => parse_csv_data(content),
-        _ => Err(format!("지원하지 않는 MIME 타입: {}", mime_type)),
+        MIME_JSON => parse_json_data(content).map(ParsedUpload::records),
+        MIME_CSV => parse_csv_data(content, csv_options.cloned().unwrap_or_default()),
+        MIME_NDJSON => parse_ndjson_data(content).map(ParsedUpload::records),
+        MIME_XML => parse_xml_data(content).map(ParsedUpload::records),
+        MIME_PARQUET => parse_parquet_data(content),
+        MIME_XLSX => parse_xlsx_data(content, excel_options.cloned().unwrap_or_default()),
+        _ => Err(BackendError::validation("mime_type", "unsupported")),
     }
 }
 
 /// JSON 데이터 파싱
-fn parse_json_data(content: Vec<u8>) -> Result<Vec<CborValue>, String> {
-    let json: JsonValue =
-        serde_json::from_slice(&content).map_err(|e| format!("JSON 파싱 실패: {}", e))?;
+fn parse_json_data(content: Vec<u8>) -> Result<Vec<CborValue>, BackendError> {
+    let json: JsonValue = serde_json::from_slice(&content).map_err(|e| {
+        ic_cdk::println!("JSON 파싱 실패: {}", e);
+        BackendError::validation("content", "invalid_json")
+    })?;
 
-    let cbor = to_value(json).map_err(|e| format!("CBOR 변환 실패: {}", e))?;
+    let cbor = to_value(json).map_err(BackendError::internal)?;
 
     // 배열이면 그대로 반환, 단일 값이면 배열로 래핑
     match cbor {
@@ -34,27 +92,546 @@ fn parse_json_data(content: Vec<u8>) -> Result<Vec<CborValue>, String> {
 }
 
 /// CSV 데이터 파싱
-fn parse_csv_data(content: Vec<u8>) -> Result<Vec<CborValue>, String> {
+///
+/// 행은 컬럼 이름을 키로 하는 CBOR 맵으로 변환하고, 값은 컬럼 단위로 추론한
+/// 타입(정수/실수/불리언, 그 외 문자열)으로 바꾼다. 빈 값과 모자란 필드는 null.
+fn parse_csv_data(content: Vec<u8>, options: CsvOptions) -> Result<ParsedUpload, BackendError> {
+    let text = decode_text(content, options.encoding.unwrap_or(TextEncoding::Utf8))?;
+
     let mut rdr = ReaderBuilder::new()
         .has_headers(false)
-        .from_reader(content.as_slice());
-
-    let mut results = Vec::new();
+        .flexible(true)
+        .delimiter(delimiter_byte(
+            options.delimiter.unwrap_or(CsvDelimiter::Comma),
+        ))
+        .quote(quote_byte(options.quote.as_deref())?)
+        .from_reader(text.as_bytes());
 
+    let mut rows: Vec<Vec<String>> = Vec::new();
     for result in rdr.records() {
-        let record = result.map_err(|e| format!("CSV 레코드 파싱 실패: {}", e))?;
+        let record = result.map_err(|e| {
+            ic_cdk::println!("CSV 레코드 파싱 실패: {}", e);
+            BackendError::validation("content", "invalid_csv")
+        })?;
+        rows.push(record.iter().map(str::to_string).collect());
+    }
+
+    text_rows_to_records(rows, options.has_header)
+}
+
+/// 문자열 행들을 컬럼 이름을 키로 하는 타입 있는 맵 레코드로 변환 (CSV/XLSX 공용)
+///
+/// has_header가 None이면 첫 행을 보고 헤더 여부를 판단한다.
+fn text_rows_to_records(
+    rows: Vec<Vec<String>>,
+    has_header: Option<bool>,
+) -> Result<ParsedUpload, BackendError> {
+    let header = match has_header {
+        Some(true) => Some(rows.first().cloned().unwrap_or_default()),
+        Some(false) => None,
+        None => {
+            let text_rows: Vec<CborValue> = rows
+                .iter()
+                .map(|row| CborValue::Array(row.iter().cloned().map(CborValue::Text).collect()))
+                .collect();
+            schema::detect_header(&text_rows)
+        }
+    };
+    let has_header = header.is_some();
+    let body = rows.get(usize::from(has_header)..).unwrap_or_default();
+
+    if body.is_empty() {
+        return Err(BackendError::validation("content", "empty"));
+    }
+
+    // 컬럼 이름 (헤더가 비었거나 행이 더 길면 column_N)
+    let width = body.iter().map(Vec::len).max().unwrap_or(0);
+    let mut columns: Vec<String> = Vec::with_capacity(width);
+    for index in 0..width.max(header.as_ref().map_or(0, Vec::len)) {
+        let name = header
+            .as_ref()
+            .and_then(|names| names.get(index))
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| format!("column_{}", index + 1));
+        if columns.contains(&name) {
+            return Err(BackendError::validation("csv_header", "duplicate"));
+        }
+        columns.push(name);
+    }
+
+    let column_types: Vec<_> = (0..columns.len())
+        .map(|index| {
+            schema::text_column_type(
+                body.iter()
+                    .filter_map(|row| row.get(index).map(String::as_str)),
+            )
+        })
+        .collect();
+
+    let records = body
+        .iter()
+        .map(|row| {
+            let fields: BTreeMap<CborValue, CborValue> = columns
+                .iter()
+                .enumerate()
+                .map(|(index, name)| {
+                    let value = row.get(index).map_or(CborValue::Null, |text| {
+                        schema::typed_value(text, column_types[index])
+                    });
+                    (CborValue::Text(name.clone()), value)
+                })
+                .collect();
+            CborValue::Map(fields)
+        })
+        .collect();
+
+    Ok(ParsedUpload {
+        records,
+        columns: Some(columns),
+        has_header,
+    })
+}
+
+/// NDJSON 데이터 파싱 (한 줄에 JSON 값 하나, 빈 줄은 무시)
+fn parse_ndjson_data(content: Vec<u8>) -> Result<Vec<CborValue>, BackendError> {
+    let text = decode_text(content, TextEncoding::Utf8)?;
+
+    let mut records = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let json: JsonValue = serde_json::from_str(line).map_err(|_| {
+            BackendError::validation(format!("lines[{}]", index + 1), "invalid_json")
+        })?;
+        records.push(to_value(json).map_err(BackendError::internal)?);
+    }
+
+    if records.is_empty() {
+        return Err(BackendError::validation("content", "empty"));
+    }
+    Ok(records)
+}
+
+/// XML 요소 트리 (파싱 중간 표현)
+struct XmlElement {
+    name: String,
+    fields: BTreeMap<CborValue, CborValue>,
+    text: String,
+}
 
-        let row: Vec<CborValue> = record
-            .iter()
-            .map(|field| CborValue::Text(field.to_string()))
-            .collect();
+impl XmlElement {
+    fn new(start: &BytesStart) -> Result<Self, BackendError> {
+        let mut fields = BTreeMap::new();
+        for attribute in start.attributes() {
+            let attribute = attribute.map_err(|_| invalid_xml())?;
+            let raw = String::from_utf8_lossy(&attribute.value).into_owned();
+            let value = quick_xml::escape::unescape(&raw).map_err(|_| invalid_xml())?;
+            fields.insert(
+                CborValue::Text(format!(
+                    "@{}",
+                    String::from_utf8_lossy(attribute.key.as_ref())
+                )),
+                CborValue::Text(value.into_owned()),
+            );
+        }
+        Ok(XmlElement {
+            name: String::from_utf8_lossy(start.name().as_ref()).into_owned(),
+            fields,
+            text: String::new(),
+        })
+    }
 
-        results.push(CborValue::Array(row));
+    /// 자식/속성이 없으면 텍스트 값, 있으면 맵 (텍스트는 "#text")
+    fn into_value(mut self) -> CborValue {
+        let text = self.text.trim().to_string();
+        if self.fields.is_empty() {
+            return CborValue::Text(text);
+        }
+        if !text.is_empty() {
+            self.fields
+                .insert(CborValue::Text("#text".to_string()), CborValue::Text(text));
+        }
+        CborValue::Map(self.fields)
     }
 
-    if results.is_empty() {
-        return Err("CSV 데이터가 비어 있습니다".to_string());
+    /// 자식 요소 추가 (같은 이름이 반복되면 배열)
+    fn push_child(&mut self, name: String, value: CborValue) {
+        let key = CborValue::Text(name);
+        match self.fields.remove(&key) {
+            None => {
+                self.fields.insert(key, value);
+            }
+            Some(CborValue::Array(mut items)) => {
+                items.push(value);
+                self.fields.insert(key, CborValue::Array(items));
+            }
+            Some(existing) => {
+                self.fields
+                    .insert(key, CborValue::Array(vec![existing, value]));
+            }
+        }
     }
+}
+
+fn invalid_xml() -> BackendError {
+    BackendError::validation("content", "invalid_xml")
+}
+
+/// XML 데이터 파싱
+///
+/// 루트 요소의 자식 요소 하나가 레코드 하나가 된다. 속성은 "@이름", 자식 요소는
+/// 이름별 필드(반복되면 배열)로 변환한다. 자식이 없는 루트는 레코드 하나로 취급.
+fn parse_xml_data(content: Vec<u8>) -> Result<Vec<CborValue>, BackendError> {
+    let text = decode_text(content, TextEncoding::Utf8)?;
+    let mut reader = quick_xml::Reader::from_str(&text);
+
+    let mut stack: Vec<XmlElement> = Vec::new();
+    let mut records: Vec<CborValue> = Vec::new();
+    let mut root: Option<XmlElement> = None;
+
+    loop {
+        match reader.read_event().map_err(|_| invalid_xml())? {
+            Event::Start(start) => stack.push(XmlElement::new(&start)?),
+            Event::Empty(start) => {
+                let element = XmlElement::new(&start)?;
+                close_xml_element(element, &mut stack, &mut records, &mut root);
+            }
+            Event::Text(text) => {
+                if let Some(element) = stack.last_mut() {
+                    element
+                        .text
+                        .push_str(&text.unescape().map_err(|_| invalid_xml())?);
+                }
+            }
+            Event::CData(data) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&String::from_utf8_lossy(&data));
+                }
+            }
+            Event::End(_) => {
+                let element = stack.pop().ok_or_else(invalid_xml)?;
+                close_xml_element(element, &mut stack, &mut records, &mut root);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !stack.is_empty() {
+        return Err(invalid_xml());
+    }
+    if records.is_empty() {
+        if let Some(root) = root {
+            records.push(root.into_value());
+        }
+    }
+    if records.is_empty() {
+        return Err(BackendError::validation("content", "empty"));
+    }
+    Ok(records)
+}
+
+/// 닫힌 요소를 부모에 붙이거나, 루트 바로 아래 요소면 레코드로 추가
+fn close_xml_element(
+    element: XmlElement,
+    stack: &mut [XmlElement],
+    records: &mut Vec<CborValue>,
+    root: &mut Option<XmlElement>,
+) {
+    match stack.len() {
+        0 => *root = Some(element),
+        1 => records.push(element.into_value()),
+        _ => {
+            let name = element.name.clone();
+            if let Some(parent) = stack.last_mut() {
+                parent.push_child(name, element.into_value());
+            }
+        }
+    }
+}
+
+/// Parquet 데이터 파싱 (행 하나가 컬럼 이름을 키로 하는 맵 레코드)
+fn parse_parquet_data(content: Vec<u8>) -> Result<ParsedUpload, BackendError> {
+    let invalid = |_| BackendError::validation("content", "invalid_parquet");
+    let reader = SerializedFileReader::new(bytes::Bytes::from(content)).map_err(invalid)?;
 
-    Ok(results)
+    let columns: Vec<String> = reader
+        .metadata()
+        .file_metadata()
+        .schema_descr()
+        .root_schema()
+        .get_fields()
+        .iter()
+        .map(|field| field.name().to_string())
+        .collect();
+
+    let mut records = Vec::new();
+    for row in reader.get_row_iter(None).map_err(invalid)? {
+        let row = row.map_err(invalid)?;
+        records.push(to_value(row.to_json_value()).map_err(BackendError::internal)?);
+    }
+
+    if records.is_empty() {
+        return Err(BackendError::validation("content", "empty"));
+    }
+    Ok(ParsedUpload {
+        records,
+        columns: Some(columns),
+        has_header: true,
+    })
+}
+
+/// XLSX 데이터 파싱 (지정한 시트, 없으면 첫 시트)
+fn parse_xlsx_data(content: Vec<u8>, options: ExcelOptions) -> Result<ParsedUpload, BackendError> {
+    let mut workbook: Xlsx<_> = calamine::open_workbook_from_rs(Cursor::new(content))
+        .map_err(|_| BackendError::validation("content", "invalid_xlsx"))?;
+
+    let sheet = match options.sheet {
+        Some(sheet) => sheet,
+        None => workbook
+            .sheet_names()
+            .first()
+            .cloned()
+            .ok_or_else(|| BackendError::validation("content", "empty"))?,
+    };
+    let range = workbook
+        .worksheet_range(&sheet)
+        .map_err(|_| BackendError::not_found("sheet", &sheet))?;
+
+    let rows: Vec<Vec<String>> = range
+        .rows()
+        .map(|row| row.iter().map(excel_cell_text).collect())
+        .filter(|row: &Vec<String>| row.iter().any(|cell| !cell.is_empty()))
+        .collect();
+
+    text_rows_to_records(rows, options.has_header)
+}
+
+/// 엑셀 셀 값을 문자열로 변환 (타입은 CSV와 같은 방식으로 다시 추론)
+fn excel_cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Int(number) => number.to_string(),
+        // 엑셀은 정수도 실수로 저장하는 경우가 많다
+        Data::Float(number) if number.fract() == 0.0 && number.abs() < 1e15 => {
+            (*number as i64).to_string()
+        }
+        Data::Float(number) => number.to_string(),
+        Data::String(text) | Data::DateTimeIso(text) | Data::DurationIso(text) => text.clone(),
+        Data::Bool(flag) => flag.to_string(),
+        Data::DateTime(datetime) => excel_serial_to_iso(datetime.as_f64()),
+        Data::Error(_) | Data::Empty => String::new(),
+    }
+}
+
+/// 엑셀 날짜 일련번호(1899-12-30 기준)를 ISO 8601 문자열로 변환
+fn excel_serial_to_iso(serial: f64) -> String {
+    let days = serial.floor() as i64 - 25_569; // 1970-01-01 기준 일수
+    let seconds = ((serial - serial.floor()) * 86_400.0).round() as i64;
+
+    // 그레고리력 변환 (days from civil 역산)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    let date = format!("{:04}-{:02}-{:02}", year, month, day);
+    if seconds == 0 {
+        date
+    } else {
+        format!(
+            "{}T{:02}:{:02}:{:02}",
+            date,
+            seconds / 3_600,
+            seconds % 3_600 / 60,
+            seconds % 60
+        )
+    }
+}
+
+/// 업로드 바이트를 지정한 인코딩으로 디코딩 (UTF-8 BOM 제거)
+fn decode_text(content: Vec<u8>, encoding: TextEncoding) -> Result<String, BackendError> {
+    let invalid = || BackendError::validation("encoding", "invalid_encoding");
+    match encoding {
+        TextEncoding::Utf8 => {
+            let mut text = String::from_utf8(content).map_err(|_| invalid())?;
+            if text.starts_with('\u{feff}') {
+                text.drain(..'\u{feff}'.len_utf8());
+            }
+            Ok(text)
+        }
+        // encoding_rs의 EUC-KR은 WHATWG 정의에 따라 CP949 확장까지 포함
+        TextEncoding::EucKr | TextEncoding::Cp949 => encoding_rs::EUC_KR
+            .decode_without_bom_handling_and_without_replacement(&content)
+            .map(|text| text.into_owned())
+            .ok_or_else(invalid),
+    }
+}
+
+fn delimiter_byte(delimiter: CsvDelimiter) -> u8 {
+    match delimiter {
+        CsvDelimiter::Comma => b',',
+        CsvDelimiter::Tab => b'\t',
+        CsvDelimiter::Semicolon => b';',
+        CsvDelimiter::Pipe => b'|',
+    }
+}
+
+fn quote_byte(quote: Option<&str>) -> Result<u8, BackendError> {
+    match quote.map(str::as_bytes) {
+        None => Ok(b'"'),
+        Some([byte]) if byte.is_ascii() => Ok(*byte),
+        Some(_) => Err(BackendError::validation("quote", "invalid_format")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field<'a>(record: &'a CborValue, name: &str) -> &'a CborValue {
+        match record {
+            CborValue::Map(fields) => &fields[&CborValue::Text(name.to_string())],
+            _ => panic!("맵 레코드가 아닙니다"),
+        }
+    }
+
+    #[test]
+    fn test_typed_csv_with_euc_kr_and_semicolon() {
+        let (content, _, _) =
+            encoding_rs::EUC_KR.encode("지역;인구;비율\n서울;9400000;18.2\n부산;;6.5\n");
+        let options = CsvOptions {
+            has_header: Some(true),
+            delimiter: Some(CsvDelimiter::Semicolon),
+            quote: None,
+            encoding: Some(TextEncoding::EucKr),
+        };
+
+        let parsed = upload_data(content.into_owned(), "text/csv", Some(&options), None).unwrap();
+        assert!(parsed.has_header);
+        assert_eq!(
+            parsed.columns,
+            Some(vec![
+                "지역".to_string(),
+                "인구".to_string(),
+                "비율".to_string()
+            ])
+        );
+        assert_eq!(parsed.records.len(), 2);
+        assert_eq!(
+            field(&parsed.records[0], "지역"),
+            &CborValue::Text("서울".to_string())
+        );
+        assert_eq!(
+            field(&parsed.records[0], "인구"),
+            &CborValue::Integer(9400000)
+        );
+        assert_eq!(field(&parsed.records[1], "인구"), &CborValue::Null);
+        assert_eq!(field(&parsed.records[1], "비율"), &CborValue::Float(6.5));
+    }
+
+    #[test]
+    fn test_csv_header_auto_detection() {
+        let parsed = upload_data(b"a,b\n1,x\n2,y\n".to_vec(), "text/csv", None, None).unwrap();
+        assert!(parsed.has_header);
+        assert_eq!(field(&parsed.records[1], "a"), &CborValue::Integer(2));
+
+        let parsed = upload_data(b"1,x\n2,y\n".to_vec(), "text/csv", None, None).unwrap();
+        assert!(!parsed.has_header);
+        assert_eq!(
+            field(&parsed.records[0], "column_2"),
+            &CborValue::Text("x".to_string())
+        );
+    }
+
+    #[test]
+    fn test_ndjson_and_xml_records() {
+        let content = b"{\"id\":1,\"name\":\"a\"}\n\n{\"id\":2,\"name\":\"b\"}\n".to_vec();
+        let parsed = upload_data(content, MIME_NDJSON, None, None).unwrap();
+        assert_eq!(parsed.records.len(), 2);
+        assert_eq!(field(&parsed.records[1], "id"), &CborValue::Integer(2));
+
+        let content = br#"<rows>
+            <row id="1"><name>&lt;a&gt;</name><tag>x</tag><tag>y</tag></row>
+            <row id="2"><name>b</name></row>
+        </rows>"#
+            .to_vec();
+        let parsed = upload_data(content, MIME_XML, None, None).unwrap();
+        assert_eq!(parsed.records.len(), 2);
+        assert_eq!(
+            field(&parsed.records[0], "@id"),
+            &CborValue::Text("1".to_string())
+        );
+        assert_eq!(
+            field(&parsed.records[0], "name"),
+            &CborValue::Text("<a>".to_string())
+        );
+        assert_eq!(
+            field(&parsed.records[0], "tag"),
+            &CborValue::Array(vec![
+                CborValue::Text("x".to_string()),
+                CborValue::Text("y".to_string())
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parquet_rows_become_map_records() {
+        use parquet::data_type::{ByteArray, ByteArrayType, Int64Type};
+        use parquet::file::writer::SerializedFileWriter;
+        use std::sync::Arc;
+
+        let schema = Arc::new(
+            parquet::schema::parser::parse_message_type(
+                "message row { REQUIRED INT64 id; REQUIRED BYTE_ARRAY name (UTF8); }",
+            )
+            .unwrap(),
+        );
+        let mut buffer = Vec::new();
+        let mut writer =
+            SerializedFileWriter::new(&mut buffer, schema, Default::default()).unwrap();
+        let mut row_group = writer.next_row_group().unwrap();
+        let mut column = row_group.next_column().unwrap().unwrap();
+        column
+            .typed::<Int64Type>()
+            .write_batch(&[1, 2], None, None)
+            .unwrap();
+        column.close().unwrap();
+        let mut column = row_group.next_column().unwrap().unwrap();
+        column
+            .typed::<ByteArrayType>()
+            .write_batch(
+                &[ByteArray::from("서울"), ByteArray::from("부산")],
+                None,
+                None,
+            )
+            .unwrap();
+        column.close().unwrap();
+        row_group.close().unwrap();
+        writer.close().unwrap();
+
+        let parsed = upload_data(buffer, MIME_PARQUET, None, None).unwrap();
+        assert_eq!(
+            parsed.columns,
+            Some(vec!["id".to_string(), "name".to_string()])
+        );
+        assert_eq!(parsed.records.len(), 2);
+        assert_eq!(field(&parsed.records[0], "id"), &CborValue::Integer(1));
+        assert_eq!(
+            field(&parsed.records[1], "name"),
+            &CborValue::Text("부산".to_string())
+        );
+    }
+
+    #[test]
+    fn test_excel_serial_dates() {
+        assert_eq!(excel_serial_to_iso(45_292.0), "2024-01-01");
+        assert_eq!(excel_serial_to_iso(45_292.5), "2024-01-01T12:00:00");
+    }
 }
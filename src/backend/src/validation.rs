@@ -1,22 +1,24 @@
 // backend/src/validation.rs
 
+use crate::error::BackendError;
+use crate::pii;
 use crate::storage;
+use crate::types::{PiiMode, PiiReport};
 use serde_cbor::value::Value as CborValue;
 use std::collections::HashSet;
 
-/// 업로드된 데이터 검증
-pub fn validate_data(data: &[CborValue]) -> Result<(), String> {
+/// 업로드된 데이터 검증 및 개인정보 탐지
+///
+/// Redact/Hash 모드에서는 개인정보를 치환한 레코드로 이후 검증과 저장이 이루어진다.
+pub fn validate_data(data: &mut [CborValue], pii_mode: PiiMode) -> Result<PiiReport, BackendError> {
     // 1. 빈 데이터 체크
     if data.is_empty() {
-        return Err("업로드된 데이터가 비어 있습니다".to_string());
+        return Err(BackendError::validation("data", "empty"));
     }
 
     // 2. 데이터 개수 제한
     if data.len() > 10000 {
-        return Err(format!(
-            "데이터 레코드 수가 너무 많습니다. 최대 10,000개, 현재 {}개",
-            data.len()
-        ));
+        return Err(BackendError::quota_exceeded("records", 10000));
     }
 
     // 3. 각 레코드 검증
@@ -24,26 +26,40 @@ pub fn validate_data(data: &[CborValue]) -> Result<(), String> {
         validate_record(record, index)?;
     }
 
-    // 4. 업로드 데이터 내 중복 검증
+    // 4. 개인정보 탐지 (거부 모드면 발견 즉시 에러, 해시 모드는 캐니스터 키 필요)
+    let hash_key = match pii_mode {
+        PiiMode::Hash => Some(
+            storage::pii_hash_key()
+                .ok_or_else(|| BackendError::invalid_state("pii_hash_key_not_ready"))?,
+        ),
+        _ => None,
+    };
+    let pii_report = pii::scan_records(data, pii_mode, hash_key.as_ref());
+    if pii_mode == PiiMode::Reject && !pii_report.findings.is_empty() {
+        return Err(BackendError::PiiDetected {
+            findings: pii_report.findings,
+        });
+    }
+
+    // 5. 업로드 데이터 내 중복 검증
     validate_duplicates(data)?;
 
-    // 5. 기존 민팅된 데이터와의 중복 검증
+    // 6. 기존 민팅된 데이터와의 중복 검증
     validate_against_minted_data(data)?;
 
-    Ok(())
+    Ok(pii_report)
 }
 
 /// 개별 레코드 검증
-fn validate_record(record: &CborValue, index: usize) -> Result<(), String> {
+fn validate_record(record: &CborValue, index: usize) -> Result<(), BackendError> {
     // 레코드 크기 검증 (1MB 제한)
-    let serialized =
-        serde_cbor::to_vec(record).map_err(|e| format!("레코드 {} 직렬화 실패: {}", index, e))?;
+    let serialized = serde_cbor::to_vec(record).map_err(BackendError::internal)?;
 
     if serialized.len() > 1024 * 1024 {
-        return Err(format!(
-            "레코드 {}의 크기가 너무 큽니다. 최대 1MB, 현재 {}바이트",
-            index,
-            serialized.len()
+        return Err(BackendError::too_large(
+            format!("records[{}]", index),
+            serialized.len(),
+            1024 * 1024,
         ));
     }
 
@@ -54,14 +70,14 @@ fn validate_record(record: &CborValue, index: usize) -> Result<(), String> {
 }
 
 /// 텍스트 필드 검증
-fn validate_text_fields(value: &CborValue, index: usize) -> Result<(), String> {
+fn validate_text_fields(value: &CborValue, index: usize) -> Result<(), BackendError> {
     match value {
         CborValue::Text(text) => {
             if text.len() > 10000 {
-                return Err(format!(
-                    "레코드 {}의 텍스트 필드가 너무 깁니다. 최대 10,000자, 현재 {}자",
-                    index,
-                    text.len()
+                return Err(BackendError::too_long(
+                    format!("records[{}]", index),
+                    text.len(),
+                    10000,
                 ));
             }
         }
@@ -82,30 +98,29 @@ fn validate_text_fields(value: &CborValue, index: usize) -> Result<(), String> {
 }
 
 /// 중복 데이터 검증
-fn validate_duplicates(data: &[CborValue]) -> Result<(), String> {
+fn validate_duplicates(data: &[CborValue]) -> Result<(), BackendError> {
     let mut seen = HashSet::new();
     let mut duplicates = Vec::new();
 
     for (index, record) in data.iter().enumerate() {
         if let Ok(bytes) = serde_cbor::to_vec(record) {
             if !seen.insert(bytes) {
-                duplicates.push(index);
+                duplicates.push(index as u64);
             }
         }
     }
 
     if !duplicates.is_empty() {
-        return Err(format!(
-            "중복된 데이터가 발견되었습니다. 인덱스: {:?}",
-            duplicates
-        ));
+        return Err(BackendError::DuplicateRecords {
+            indices: duplicates,
+        });
     }
 
     Ok(())
 }
 
 /// 기존 민팅된 데이터와의 중복 검증
-fn validate_against_minted_data(data: &[CborValue]) -> Result<(), String> {
+fn validate_against_minted_data(data: &[CborValue]) -> Result<(), BackendError> {
     let mut minted_indices = Vec::new();
     let mut existing_indices = Vec::new();
 
@@ -116,7 +131,7 @@ fn validate_against_minted_data(data: &[CborValue]) -> Result<(), String> {
             let status = storage::check_multiple_data_status(&[bytes]);
             if let Some((existing_id, is_minted)) = status.first() {
                 if *is_minted {
-                    minted_indices.push(index);
+                    minted_indices.push(index as u64);
                 } else if existing_id.is_some() {
                     existing_indices.push((index, existing_id.unwrap()));
                 }
@@ -126,10 +141,9 @@ fn validate_against_minted_data(data: &[CborValue]) -> Result<(), String> {
 
     // 이미 민팅된 데이터가 있으면 에러
     if !minted_indices.is_empty() {
-        return Err(format!(
-            "이미 민팅된 데이터가 포함되어 있습니다. 인덱스: {:?}",
-            minted_indices
-        ));
+        return Err(BackendError::AlreadyMinted {
+            indices: minted_indices,
+        });
     }
 
     // 이미 업로드되었지만 민팅되지 않은 데이터는 경고만
@@ -144,47 +158,44 @@ fn validate_against_minted_data(data: &[CborValue]) -> Result<(), String> {
 }
 
 /// 민팅 요청 검증 - 강화된 버전
-pub fn validate_mint_request(cid: &str, metadata: &[Vec<u8>]) -> Result<(), String> {
+pub fn validate_mint_request(cid: &str, metadata: &[Vec<u8>]) -> Result<(), BackendError> {
     // 1. CID 형식 검증 - 강화
     if cid.trim().is_empty() {
-        return Err("CID가 비어 있습니다".to_string());
+        return Err(BackendError::validation("cid", "empty"));
     }
 
     if cid.len() > 100 {
-        return Err(format!(
-            "CID가 너무 깁니다. 최대 100자, 현재 {}자",
-            cid.len()
-        ));
+        return Err(BackendError::too_long("cid", cid.len(), 100));
     }
 
     // CID 형식 검증 강화
     if !is_valid_cid(cid) {
-        return Err("유효하지 않은 CID 형식입니다".to_string());
+        return Err(BackendError::validation("cid", "invalid_format"));
     }
 
     // 2. 메타데이터 검증 - 강화
     if metadata.is_empty() {
-        return Err("메타데이터가 비어 있습니다".to_string());
+        return Err(BackendError::validation("metadata", "empty"));
     }
 
     if metadata.len() > 100 {
-        return Err(format!(
-            "메타데이터 개수가 너무 많습니다. 최대 100개, 현재 {}개",
-            metadata.len()
-        ));
+        return Err(BackendError::quota_exceeded("metadata", 100));
     }
 
     // 3. 각 메타데이터 크기 및 내용 검증
     for (index, data) in metadata.iter().enumerate() {
         if data.is_empty() {
-            return Err(format!("메타데이터 {}가 비어 있습니다", index));
+            return Err(BackendError::validation(
+                format!("metadata[{}]", index),
+                "empty",
+            ));
         }
 
         if data.len() > 1024 * 1024 {
-            return Err(format!(
-                "메타데이터 {}의 크기가 너무 큽니다. 최대 1MB, 현재 {}바이트",
-                index,
-                data.len()
+            return Err(BackendError::too_large(
+                format!("metadata[{}]", index),
+                data.len(),
+                1024 * 1024,
             ));
         }
 
@@ -196,15 +207,14 @@ pub fn validate_mint_request(cid: &str, metadata: &[Vec<u8>]) -> Result<(), Stri
     let mut already_minted = Vec::new();
     for (index, data) in metadata.iter().enumerate() {
         if storage::check_data_minted(data) {
-            already_minted.push(index);
+            already_minted.push(index as u64);
         }
     }
 
     if !already_minted.is_empty() {
-        return Err(format!(
-            "이미 민팅된 메타데이터가 포함되어 있습니다. 인덱스: {:?}",
-            already_minted
-        ));
+        return Err(BackendError::AlreadyMinted {
+            indices: already_minted,
+        });
     }
 
     Ok(())
@@ -236,21 +246,22 @@ fn is_valid_cid(cid: &str) -> bool {
 }
 
 /// 메타데이터 내용 검증
-fn validate_metadata_content(data: &[u8], index: usize) -> Result<(), String> {
+fn validate_metadata_content(data: &[u8], index: usize) -> Result<(), BackendError> {
     // 최소 10바이트 요구
     if data.len() < 10 {
-        return Err(format!(
-            "메타데이터 {}의 내용이 너무 짧습니다. 최소 10바이트 필요",
-            index
+        return Err(BackendError::too_small(
+            format!("metadata[{}]", index),
+            data.len(),
+            10,
         ));
     }
 
     // 최대 1MB 제한
     if data.len() > 1024 * 1024 {
-        return Err(format!(
-            "메타데이터 {}의 크기가 너무 큽니다. 최대 1MB, 현재 {}바이트",
-            index,
-            data.len()
+        return Err(BackendError::too_large(
+            format!("metadata[{}]", index),
+            data.len(),
+            1024 * 1024,
         ));
     }
 
@@ -258,57 +269,59 @@ fn validate_metadata_content(data: &[u8], index: usize) -> Result<(), String> {
 }
 
 /// 데이터 무결성 검증
-pub fn validate_data_integrity(data: &[u8]) -> Result<(), String> {
+pub fn validate_data_integrity(data: &[u8]) -> Result<(), BackendError> {
     // CBOR 형식 검증
     match serde_cbor::from_slice::<CborValue>(data) {
         Ok(_) => Ok(()),
-        Err(e) => Err(format!("데이터 무결성 검증 실패: {}", e)),
+        Err(_) => Err(BackendError::validation("data", "invalid_cbor")),
     }
 }
 
 /// 사용자 권한 검증 - 강화
-pub fn validate_user_permission(owner: Option<candid::Principal>) -> Result<(), String> {
+pub fn validate_user_permission(owner: Option<candid::Principal>) -> Result<(), BackendError> {
     if owner.is_none() {
         // caller() 사용 시 추가 검증 로직
         let caller = ic_cdk::caller();
         if caller == candid::Principal::anonymous() {
-            return Err("익명 사용자는 민팅을 요청할 수 없습니다".to_string());
+            return Err(BackendError::unauthorized("request_mint"));
         }
     } else {
         // 명시적으로 지정된 owner가 있는 경우 검증
         let owner_principal = owner.unwrap();
         if owner_principal == candid::Principal::anonymous() {
-            return Err("익명 사용자는 민팅을 요청할 수 없습니다".to_string());
+            return Err(BackendError::unauthorized("request_mint"));
         }
     }
     Ok(())
 }
 
 /// 데이터 크기 검증 - 강화
-pub fn validate_data_size(content: &[u8], max_size: usize) -> Result<(), String> {
+pub fn validate_data_size(content: &[u8], max_size: usize) -> Result<(), BackendError> {
     if content.is_empty() {
-        return Err("업로드된 데이터가 비어 있습니다".to_string());
+        return Err(BackendError::validation("content", "empty"));
     }
 
     if content.len() > max_size {
-        return Err(format!(
-            "데이터 크기가 너무 큽니다. 최대 {}바이트, 현재 {}바이트",
-            max_size,
-            content.len()
+        return Err(BackendError::quota_exceeded(
+            "upload_bytes",
+            max_size as u64,
         ));
     }
     Ok(())
 }
 
 /// 지원되는 MIME 타입 검증 - 강화
-pub fn validate_mime_type(mime_type: &str) -> Result<(), String> {
+pub fn validate_mime_type(mime_type: &str) -> Result<(), BackendError> {
     if mime_type.trim().is_empty() {
-        return Err("MIME 타입이 비어 있습니다".to_string());
+        return Err(BackendError::validation("mime_type", "empty"));
     }
 
-    match mime_type {
-        "application/json" | "text/csv" => Ok(()),
-        _ => Err(format!("지원하지 않는 MIME 타입: {}", mime_type)),
+    if crate::upload::TABULAR_MIME_TYPES.contains(&mime_type)
+        || crate::asset::is_asset_mime_type(mime_type)
+    {
+        Ok(())
+    } else {
+        Err(BackendError::validation("mime_type", "unsupported"))
     }
 }
 
@@ -329,14 +342,14 @@ mod tests {
 
     #[test]
     fn test_valid_data() {
-        let data = create_test_data();
-        assert!(validate_data(&data).is_ok());
+        let mut data = create_test_data();
+        assert!(validate_data(&mut data, PiiMode::Reject).is_ok());
     }
 
     #[test]
     fn test_empty_data() {
-        let data = vec![];
-        assert!(validate_data(&data).is_err());
+        let mut data = vec![];
+        assert!(validate_data(&mut data, PiiMode::Reject).is_err());
     }
 
     #[test]
@@ -375,7 +388,10 @@ mod tests {
     fn test_mime_type_validation() {
         assert!(validate_mime_type("application/json").is_ok());
         assert!(validate_mime_type("text/csv").is_ok());
-        assert!(validate_mime_type("application/pdf").is_err());
+        assert!(validate_mime_type("application/x-ndjson").is_ok());
+        assert!(validate_mime_type("application/vnd.apache.parquet").is_ok());
+        assert!(validate_mime_type("application/pdf").is_ok());
+        assert!(validate_mime_type("application/msword").is_err());
         assert!(validate_mime_type("").is_err());
     }
 